@@ -0,0 +1,379 @@
+/*!
+ * Derive macros for covenant-rs
+ * `#[derive(MagicSpellPayload)]` implements `covenant_rs::MagicSpellPayload`
+ * for a struct with named fields, generating the spell's JSON Schema from the
+ * field types. Enable covenant-rs's `derive` feature rather than depending on
+ * this crate directly.
+ *
+ * ```ignore
+ * #[derive(Serialize, MagicSpellPayload)]
+ * #[spell(name = "purchase")]
+ * #[serde(rename_all = "camelCase")]
+ * struct Purchase {
+ *     #[spell(min = 1)]
+ *     amount: u64,
+ *     #[spell(min_length = 3, max_length = 3)]
+ *     currency: String,
+ *     memo: Option<String>,
+ * }
+ * ```
+ *
+ * Strings, integers, floats and booleans map to their JSON types (unsigned
+ * integers with a minimum of 0), `Vec`s and sets to arrays, maps to objects.
+ * `Option` fields, and fields with `skip_serializing_if`, may be left out;
+ * `Option` fields may also be null. Any other type accepts any value.
+ * The spell name defaults to the struct name in camelCase. `#[serde(rename)]`,
+ * `#[serde(rename_all)]` (including their `serialize = ..` forms) and
+ * `#[serde(skip)]` are honoured.
+ */
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use serde_json::{json, Map, Value};
+use syn::meta::ParseNestedMeta;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericArgument, Lit, LitStr, PathArguments, Type};
+
+#[proc_macro_derive(MagicSpellPayload, attributes(spell))]
+pub fn derive_magic_spell_payload(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let (spell_name, schema) = spell_schema(input)?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let spell = LitStr::new(&spell_name, Span::call_site());
+    let schema = LitStr::new(&schema.to_string(), Span::call_site());
+
+    Ok(quote! {
+        impl #impl_generics ::covenant_rs::MagicSpellPayload for #ident #ty_generics #where_clause {
+            const SPELL: &'static str = #spell;
+
+            fn schema() -> ::covenant_rs::spells::JsonValue {
+                ::covenant_rs::spells::schema_from_json(#schema)
+            }
+        }
+    })
+}
+
+/// The spell name and JSON Schema for a struct
+fn spell_schema(input: &DeriveInput) -> syn::Result<(String, Value)> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(input, "MagicSpellPayload needs a struct with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(input, "MagicSpellPayload can only be derived for structs")),
+    };
+
+    let mut spell_name = None;
+    for attr in attrs_named(&input.attrs, "spell") {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                spell_name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown spell attribute; expected `name`"))
+            }
+        })?;
+    }
+    let spell_name = spell_name.unwrap_or_else(|| rename("camelCase", &input.ident.to_string()).unwrap_or_default());
+
+    let mut rename_all = None;
+    for attr in attrs_named(&input.attrs, "serde") {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                if let Some(style) = serialized_name(&meta)? {
+                    rename_all = Some(style);
+                }
+                Ok(())
+            } else {
+                skip_meta(&meta)
+            }
+        })?;
+    }
+
+    let mut properties = Map::new();
+    let mut required = vec![Value::String("spell".to_string())];
+    properties.insert("spell".to_string(), json!({ "type": "string", "const": spell_name }));
+
+    for field in fields {
+        let Some(ident) = &field.ident else { continue };
+        let rust_name = ident.to_string().trim_start_matches("r#").to_string();
+
+        let mut name = match &rename_all {
+            Some(style) => rename(&style.value(), &rust_name)
+                .ok_or_else(|| syn::Error::new_spanned(style, "unsupported rename_all style"))?,
+            None => rust_name,
+        };
+        let mut skipped = false;
+        let mut skippable = false;
+        for attr in attrs_named(&field.attrs, "serde") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(rename) = serialized_name(&meta)? {
+                        name = rename.value();
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    skipped = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    skippable = true;
+                    skip_meta(&meta)
+                } else if meta.path.is_ident("flatten") {
+                    Err(meta.error("MagicSpellPayload doesn't support flattened fields"))
+                } else {
+                    skip_meta(&meta)
+                }
+            })?;
+        }
+        if skipped {
+            continue;
+        }
+
+        let (mut schema, optional) = type_schema(&field.ty);
+        for attr in attrs_named(&field.attrs, "spell") {
+            attr.parse_nested_meta(|meta| {
+                let keyword = match meta.path.get_ident().map(|i| i.to_string()).as_deref() {
+                    Some("min") => "minimum",
+                    Some("max") => "maximum",
+                    Some("min_length") => "minLength",
+                    Some("max_length") => "maxLength",
+                    Some("min_items") => "minItems",
+                    Some("max_items") => "maxItems",
+                    _ => return Err(meta.error("unknown spell attribute; expected min, max, min_length, max_length, min_items or max_items")),
+                };
+                let value = number(&meta.value()?.parse::<Lit>()?)?;
+                if let Value::Object(schema) = &mut schema {
+                    schema.insert(keyword.to_string(), value);
+                }
+                Ok(())
+            })?;
+        }
+
+        if !optional && !skippable {
+            required.push(Value::String(name.clone()));
+        }
+        properties.insert(name, schema);
+    }
+
+    let schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    });
+    Ok((spell_name, schema))
+}
+
+fn attrs_named<'a>(attrs: &'a [Attribute], name: &'a str) -> impl Iterator<Item = &'a Attribute> {
+    attrs.iter().filter(move |attr| attr.path().is_ident(name))
+}
+
+/// The serialized side of `name = ".."` or `name(serialize = "..", deserialize = "..")`,
+/// if one is given
+fn serialized_name(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse::<LitStr>()?));
+    }
+
+    let mut serialized = None;
+    meta.parse_nested_meta(|nested| {
+        let value = nested.value()?.parse::<LitStr>()?;
+        if nested.path.is_ident("serialize") {
+            serialized = Some(value);
+        }
+        Ok(())
+    })?;
+    Ok(serialized)
+}
+
+/// Consume a serde attribute this macro doesn't care about
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_meta(&nested))?;
+    }
+    Ok(())
+}
+
+fn number(lit: &Lit) -> syn::Result<Value> {
+    match lit {
+        Lit::Int(int) => Ok(json!(int.base10_parse::<i64>()?)),
+        Lit::Float(float) => Ok(json!(float.base10_parse::<f64>()?)),
+        _ => Err(syn::Error::new_spanned(lit, "expected a number")),
+    }
+}
+
+/// JSON Schema for a field type, and whether the field may be left out
+fn type_schema(ty: &Type) -> (Value, bool) {
+    let path = match ty {
+        Type::Reference(reference) => return type_schema(&reference.elem),
+        Type::Array(array) => return (json!({ "type": "array", "items": type_schema(&array.elem).0 }), false),
+        Type::Slice(slice) => return (json!({ "type": "array", "items": type_schema(&slice.elem).0 }), false),
+        Type::Path(path) => path,
+        _ => return (json!({}), false),
+    };
+    let Some(segment) = path.path.segments.last() else { return (json!({}), false) };
+
+    let argument = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    let schema = match segment.ident.to_string().as_str() {
+        "Option" => {
+            let mut inner = argument.map(|ty| type_schema(ty).0).unwrap_or_else(|| json!({}));
+            if let Some(Value::String(kind)) = inner.get("type").cloned() {
+                inner["type"] = json!([kind, "null"]);
+            }
+            return (inner, true);
+        }
+        "Box" | "Rc" | "Arc" => return argument.map(type_schema).unwrap_or((json!({}), false)),
+        "String" | "str" | "char" | "Cow" => json!({ "type": "string" }),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => json!({ "type": "integer" }),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => json!({ "type": "integer", "minimum": 0 }),
+        "f32" | "f64" => json!({ "type": "number" }),
+        "bool" => json!({ "type": "boolean" }),
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => {
+            let items = argument.map(|ty| type_schema(ty).0).unwrap_or_else(|| json!({}));
+            json!({ "type": "array", "items": items })
+        }
+        "HashMap" | "BTreeMap" => json!({ "type": "object" }),
+        _ => json!({}),
+    };
+    (schema, false)
+}
+
+/// Apply a serde `rename_all` style to a snake_case field or PascalCase type name
+fn rename(style: &str, name: &str) -> Option<String> {
+    let words: Vec<String> = split_words(name);
+    let capitalize = |word: &String| {
+        let mut chars = word.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+    };
+
+    Some(match style {
+        "camelCase" => words.iter().enumerate()
+            .map(|(i, word)| if i == 0 { word.clone() } else { capitalize(word) })
+            .collect(),
+        "PascalCase" => words.iter().map(capitalize).collect(),
+        "snake_case" => words.join("_"),
+        "SCREAMING_SNAKE_CASE" => words.join("_").to_uppercase(),
+        "kebab-case" => words.join("-"),
+        "SCREAMING-KEBAB-CASE" => words.join("-").to_uppercase(),
+        "lowercase" => words.concat(),
+        "UPPERCASE" => words.concat().to_uppercase(),
+        _ => return None,
+    })
+}
+
+/// Lowercase words of a snake_case or PascalCase name
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for c in name.chars() {
+        if c == '_' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        } else if c.is_uppercase() && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+            current.extend(c.to_lowercase());
+        } else {
+            current.extend(c.to_lowercase());
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(source: &str) -> Value {
+        spell_schema(&syn::parse_str(source).unwrap()).unwrap().1
+    }
+
+    fn field_schema(ty: &str) -> (Value, bool) {
+        type_schema(&syn::parse_str(ty).unwrap())
+    }
+
+    #[test]
+    fn words_split_on_underscores_and_capitals() {
+        assert_eq!(split_words("escrow_release"), ["escrow", "release"]);
+        assert_eq!(split_words("EscrowRelease"), ["escrow", "release"]);
+        assert_eq!(split_words("_leading__double_"), ["leading", "double"]);
+    }
+
+    #[test]
+    fn rename_styles() {
+        assert_eq!(rename("camelCase", "payee_account_id").as_deref(), Some("payeeAccountId"));
+        assert_eq!(rename("camelCase", "EscrowRelease").as_deref(), Some("escrowRelease"));
+        assert_eq!(rename("PascalCase", "payee_account").as_deref(), Some("PayeeAccount"));
+        assert_eq!(rename("SCREAMING_SNAKE_CASE", "payeeAccount").as_deref(), Some("PAYEE_ACCOUNT"));
+        assert_eq!(rename("kebab-case", "payee_account").as_deref(), Some("payee-account"));
+        assert_eq!(rename("lowercase", "PayeeAccount").as_deref(), Some("payeeaccount"));
+        assert_eq!(rename("Title Case", "payee"), None);
+    }
+
+    #[test]
+    fn type_schemas() {
+        assert_eq!(field_schema("String"), (json!({ "type": "string" }), false));
+        assert_eq!(field_schema("u64"), (json!({ "type": "integer", "minimum": 0 }), false));
+        assert_eq!(field_schema("&'static str"), (json!({ "type": "string" }), false));
+        assert_eq!(field_schema("Option<bool>"), (json!({ "type": ["boolean", "null"] }), true));
+        assert_eq!(field_schema("Vec<i32>"), (json!({ "type": "array", "items": { "type": "integer" } }), false));
+        assert_eq!(field_schema("Box<f64>"), (json!({ "type": "number" }), false));
+        assert_eq!(field_schema("HashMap<String, u8>"), (json!({ "type": "object" }), false));
+        assert_eq!(field_schema("Custom"), (json!({}), false));
+    }
+
+    #[test]
+    fn skippable_fields_are_not_required() {
+        let schema = schema(r#"
+            struct Payment {
+                amount: u64,
+                #[serde(skip_serializing_if = "Vec::is_empty")]
+                tags: Vec<String>,
+                memo: Option<String>,
+                #[serde(skip)]
+                internal: bool,
+            }
+        "#);
+
+        assert_eq!(schema["required"], json!(["spell", "amount"]));
+        assert!(schema["properties"].get("tags").is_some());
+        assert!(schema["properties"].get("internal").is_none());
+    }
+
+    #[test]
+    fn serialize_renames_are_honoured() {
+        let schema = schema(r#"
+            #[spell(name = "pay")]
+            #[serde(rename_all(serialize = "camelCase", deserialize = "snake_case"))]
+            struct Payment {
+                payee_account: String,
+                #[serde(rename(serialize = "total", deserialize = "amount"))]
+                amount: u64,
+                #[serde(rename = "ccy")]
+                currency: String,
+            }
+        "#);
+
+        assert_eq!(schema["properties"]["spell"]["const"], "pay");
+        assert_eq!(schema["required"], json!(["spell", "payeeAccount", "total", "ccy"]));
+    }
+}
@@ -0,0 +1,340 @@
+/*!
+ * UniFFI bindings for mobile, built as a static/dynamic library over covenant-rs
+ * Exposes the client, contract builder and sign/verify flows to Swift and
+ * Kotlin. Keys stay on the platform side behind the `FfiSigner` callback
+ * interface (Keychain / Keystore), and async results come back through
+ * callback interfaces so the wrappers stay thin.
+ */
+
+use std::sync::{Arc, Mutex};
+
+use covenant_rs::{canonical, scheme, Authenticated, Contract, ContractBuilder, CovenantClient, CovenantError, SignStepResponse, SignatureScheme, Signer};
+
+uniffi::setup_scaffolding!("covenant");
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+pub enum FfiError {
+    #[error("{0}")]
+    Request(String),
+    #[error("{0}")]
+    Service(String),
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    Signing(String),
+    #[error("Out of order signature, waiting on {0}")]
+    OutOfOrder(String),
+    #[error("Request cancelled")]
+    Cancelled,
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<CovenantError> for FfiError {
+    fn from(error: CovenantError) -> Self {
+        match error {
+            CovenantError::RequestError(e) => FfiError::Request(e.to_string()),
+            CovenantError::ServiceError(message) => FfiError::Service(message),
+            CovenantError::ValidationError(message) => FfiError::Validation(message),
+            CovenantError::SessionlessError(message) => FfiError::Signing(message),
+            CovenantError::OutOfOrderSignature(next) => FfiError::OutOfOrder(next),
+            CovenantError::Cancelled => FfiError::Cancelled,
+            other => FfiError::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<uniffi::UnexpectedUniFFICallbackError> for FfiError {
+    fn from(error: uniffi::UnexpectedUniFFICallbackError) -> Self {
+        FfiError::Signing(error.reason)
+    }
+}
+
+/// Signature scheme of a platform key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum FfiSignatureScheme {
+    Secp256k1,
+    Ed25519,
+}
+
+impl From<FfiSignatureScheme> for SignatureScheme {
+    fn from(scheme: FfiSignatureScheme) -> Self {
+        match scheme {
+            FfiSignatureScheme::Secp256k1 => SignatureScheme::Secp256k1,
+            FfiSignatureScheme::Ed25519 => SignatureScheme::Ed25519,
+        }
+    }
+}
+
+/// Platform-held identity that signs on the client's behalf
+#[uniffi::export(callback_interface)]
+pub trait FfiSigner: Send + Sync {
+    fn user_uuid(&self) -> String;
+    fn public_key(&self) -> String;
+    fn sign(&self, message: String) -> Result<String, FfiError>;
+}
+
+/// Completion for calls returning a contract
+#[uniffi::export(callback_interface)]
+pub trait ContractCallback: Send + Sync {
+    fn on_success(&self, contract: FfiContract);
+    fn on_error(&self, message: String);
+}
+
+/// Completion for step signing
+#[uniffi::export(callback_interface)]
+pub trait SignCallback: Send + Sync {
+    fn on_success(&self, result: FfiSignResult);
+    fn on_error(&self, message: String);
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiStep {
+    pub id: String,
+    pub description: String,
+    pub completed: bool,
+    pub signed_by: Vec<String>,
+    pub awaiting: Vec<String>,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiContract {
+    pub uuid: String,
+    pub title: String,
+    pub description: String,
+    pub participants: Vec<String>,
+    pub status: String,
+    pub steps: Vec<FfiStep>,
+    /// The full contract as JSON, for fields not mirrored here
+    pub json: String,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiSignResult {
+    pub contract_uuid: String,
+    pub step_id: String,
+    pub step_completed: bool,
+    pub magic_triggered: bool,
+}
+
+impl From<&Contract> for FfiContract {
+    fn from(contract: &Contract) -> Self {
+        let steps = contract.steps.iter().map(|step| FfiStep {
+            id: step.id.clone(),
+            description: step.description.clone(),
+            completed: step.completed,
+            signed_by: step.signatures.iter()
+                .filter(|(_, signature)| signature.is_some())
+                .map(|(participant, _)| participant.clone())
+                .collect(),
+            awaiting: step.awaiting_signers(&contract.participants),
+        }).collect();
+
+        FfiContract {
+            uuid: contract.uuid.clone(),
+            title: contract.title.clone(),
+            description: contract.description.clone(),
+            participants: contract.participants.clone(),
+            status: contract.status.clone(),
+            steps,
+            json: serde_json::to_string(contract).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<SignStepResponse> for FfiSignResult {
+    fn from(response: SignStepResponse) -> Self {
+        FfiSignResult {
+            contract_uuid: response.contract_uuid,
+            step_id: response.step_id,
+            step_completed: response.step_completed,
+            magic_triggered: response.magic_triggered,
+        }
+    }
+}
+
+/// `ContractBuilder` behind a handle; setters mutate in place and return the handle
+#[derive(uniffi::Object)]
+pub struct FfiContractBuilder {
+    inner: Mutex<ContractBuilder>,
+}
+
+impl FfiContractBuilder {
+    fn update(self: Arc<Self>, f: impl FnOnce(ContractBuilder) -> ContractBuilder) -> Arc<Self> {
+        {
+            let mut builder = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            *builder = f(builder.clone());
+        }
+        self
+    }
+
+    fn snapshot(&self) -> ContractBuilder {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[uniffi::export]
+impl FfiContractBuilder {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { inner: Mutex::new(ContractBuilder::new()) })
+    }
+
+    pub fn title(self: Arc<Self>, title: String) -> Arc<Self> {
+        self.update(|b| b.title(title))
+    }
+
+    pub fn description(self: Arc<Self>, description: String) -> Arc<Self> {
+        self.update(|b| b.description(description))
+    }
+
+    pub fn participant(self: Arc<Self>, participant: String) -> Arc<Self> {
+        self.update(|b| b.participant(participant))
+    }
+
+    pub fn step(self: Arc<Self>, description: String) -> Arc<Self> {
+        self.update(|b| b.step(description))
+    }
+
+    /// Add a step whose magic spell is given as JSON
+    pub fn step_with_magic(self: Arc<Self>, description: String, magic_spell_json: String) -> Result<Arc<Self>, FfiError> {
+        let spell: serde_json::Value = serde_json::from_str(&magic_spell_json)
+            .map_err(|e| FfiError::Validation(e.to_string()))?;
+        Ok(self.update(|b| b.step_with_magic(description, spell)))
+    }
+
+    pub fn tag(self: Arc<Self>, tag: String) -> Arc<Self> {
+        self.update(|b| b.tag(tag))
+    }
+
+    /// Validate and return the creation payload as JSON
+    pub fn build_json(&self) -> Result<String, FfiError> {
+        Ok(self.snapshot().build()?.to_string())
+    }
+}
+
+/// `FfiSigner` as a `Signer`; the identity is read once, up front
+struct PlatformSigner {
+    uuid: String,
+    public_key: String,
+    scheme: SignatureScheme,
+    inner: Box<dyn FfiSigner>,
+}
+
+impl Signer for PlatformSigner {
+    fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+
+    fn sign(&self, message: &str) -> Result<String, CovenantError> {
+        self.inner.sign(message.to_string())
+            .map_err(|e| CovenantError::SessionlessError(e.to_string()))
+    }
+}
+
+/// Covenant client for Swift/Kotlin
+#[derive(uniffi::Object)]
+pub struct FfiClient {
+    anonymous: CovenantClient,
+    authenticated: Option<CovenantClient<Authenticated>>,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl FfiClient {
+    fn authenticated(&self) -> Result<&CovenantClient<Authenticated>, CovenantError> {
+        self.authenticated.as_ref()
+            .ok_or_else(|| CovenantError::SessionlessError("Signer required for signing".to_string()))
+    }
+
+    /// Signed when the client has an identity
+    async fn get(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        match &self.authenticated {
+            Some(client) => client.get_contract(uuid).await,
+            None => self.anonymous.get_contract(uuid).await,
+        }
+    }
+
+    async fn create(&self, builder: &ContractBuilder) -> Result<Contract, CovenantError> {
+        self.authenticated()?.create_contract(builder).await
+    }
+
+    async fn sign_step_inner(&self, contract_uuid: &str, step_id: &str) -> Result<SignStepResponse, CovenantError> {
+        self.authenticated()?.sign_step(contract_uuid, step_id, None).await
+    }
+}
+
+#[uniffi::export]
+impl FfiClient {
+    /// `scheme` is the scheme of `signer`'s key
+    #[uniffi::constructor]
+    pub fn new(base_url: String, signer: Option<Box<dyn FfiSigner>>, scheme: FfiSignatureScheme) -> Result<Arc<Self>, FfiError> {
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| FfiError::Other(e.to_string()))?;
+
+        let anonymous = CovenantClient::new(base_url)?;
+        let authenticated = signer.map(|inner| anonymous.clone().with_signer(PlatformSigner {
+            uuid: inner.user_uuid(),
+            public_key: inner.public_key(),
+            scheme: scheme.into(),
+            inner,
+        }));
+
+        Ok(Arc::new(Self {
+            anonymous,
+            authenticated,
+            runtime: Arc::new(runtime),
+        }))
+    }
+
+    pub fn get_contract(self: Arc<Self>, uuid: String, callback: Box<dyn ContractCallback>) {
+        let client = self.clone();
+        self.runtime.spawn(async move {
+            match client.get(&uuid).await {
+                Ok(contract) => callback.on_success(FfiContract::from(&contract)),
+                Err(e) => callback.on_error(e.to_string()),
+            }
+        });
+    }
+
+    pub fn create_contract(self: Arc<Self>, builder: Arc<FfiContractBuilder>, callback: Box<dyn ContractCallback>) {
+        let client = self.clone();
+        let builder = builder.snapshot();
+        self.runtime.spawn(async move {
+            match client.create(&builder).await {
+                Ok(contract) => callback.on_success(FfiContract::from(&contract)),
+                Err(e) => callback.on_error(e.to_string()),
+            }
+        });
+    }
+
+    pub fn sign_step(self: Arc<Self>, contract_uuid: String, step_id: String, callback: Box<dyn SignCallback>) {
+        let client = self.clone();
+        self.runtime.spawn(async move {
+            match client.sign_step_inner(&contract_uuid, &step_id).await {
+                Ok(response) => callback.on_success(response.into()),
+                Err(e) => callback.on_error(e.to_string()),
+            }
+        });
+    }
+}
+
+/// Verify a signature over a message under `scheme`
+#[uniffi::export]
+pub fn verify_signature(scheme: FfiSignatureScheme, signature: String, message: String, pub_key: String) -> Result<bool, FfiError> {
+    Ok(scheme::verify(scheme.into(), &signature, &message, &pub_key)?)
+}
+
+/// Canonical message a participant signs for a step
+#[uniffi::export]
+pub fn step_message(timestamp: i64, user_uuid: String, contract_uuid: String, step_id: String) -> String {
+    canonical::step_message(timestamp, &user_uuid, &contract_uuid, &step_id)
+}
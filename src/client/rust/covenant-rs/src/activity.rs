@@ -0,0 +1,397 @@
+/*!
+ * Activity feed tailing
+ * Follows the service's server-sent events feed of contract activity for an
+ * organization or participant, e.g. for ops dashboards. Every event carries
+ * a cursor; after a disconnect the tail reconnects and resumes from the last
+ * cursor it saw (sent as `Last-Event-ID`), so no events are skipped. A cursor
+ * saved by the caller can also be resumed from later with
+ * `ActivityFilter::resume_from`.
+ */
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "client")]
+use std::time::Duration;
+
+#[cfg(feature = "client")]
+use crate::{Anonymous, CovenantClient, CovenantError};
+
+/// Header carrying the cursor to resume from
+#[cfg(feature = "client")]
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Consecutive connection failures before `next` reports the error
+#[cfg(feature = "client")]
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    ContractCreated,
+    StepSigned,
+    StepCompleted,
+    ContractCompleted,
+    DisputeOpened,
+    DisputeResolved,
+    /// A kind this SDK doesn't know about yet
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(feature = "client")]
+impl ActivityKind {
+    fn name(&self) -> &'static str {
+        match self {
+            ActivityKind::ContractCreated => "contractCreated",
+            ActivityKind::StepSigned => "stepSigned",
+            ActivityKind::StepCompleted => "stepCompleted",
+            ActivityKind::ContractCompleted => "contractCompleted",
+            ActivityKind::DisputeOpened => "disputeOpened",
+            ActivityKind::DisputeResolved => "disputeResolved",
+            ActivityKind::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ActivityEvent {
+    /// Position in the feed; resume after this event from here
+    #[serde(default)]
+    pub cursor: String,
+    pub kind: ActivityKind,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    #[serde(rename = "orgUuid")]
+    pub org_uuid: Option<String>,
+    /// Participant who caused the event, when there is one
+    pub actor: Option<String>,
+    #[serde(rename = "stepId")]
+    pub step_id: Option<String>,
+    /// Milliseconds since the epoch
+    pub timestamp: i64,
+    /// Kind-specific fields, e.g. a dispute's reason
+    #[serde(default)]
+    pub details: serde_json::Value,
+}
+
+#[cfg(feature = "client")]
+/// Scope of an activity tail; an organization or a participant is required
+#[derive(Debug, Clone, Default)]
+pub struct ActivityFilter {
+    org_uuid: Option<String>,
+    participant: Option<String>,
+    kinds: Vec<ActivityKind>,
+    cursor: Option<String>,
+}
+
+#[cfg(feature = "client")]
+impl ActivityFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn org<S: Into<String>>(mut self, org_uuid: S) -> Self {
+        self.org_uuid = Some(org_uuid.into());
+        self
+    }
+
+    pub fn participant<S: Into<String>>(mut self, participant: S) -> Self {
+        self.participant = Some(participant.into());
+        self
+    }
+
+    /// Only events of this kind; may be given more than once
+    pub fn kind(mut self, kind: ActivityKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Start after the event with this cursor instead of at the live edge
+    pub fn resume_from<S: Into<String>>(mut self, cursor: S) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    fn validate(&self) -> Result<(), CovenantError> {
+        if self.org_uuid.is_none() && self.participant.is_none() {
+            return Err(CovenantError::ValidationError(
+                "Activity filter needs an organization or a participant".to_string()
+            ));
+        }
+        Ok(())
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(org_uuid) = &self.org_uuid {
+            params.push(("orgUuid", org_uuid.clone()));
+        }
+        if let Some(participant) = &self.participant {
+            params.push(("participant", participant.clone()));
+        }
+        if !self.kinds.is_empty() {
+            let kinds: Vec<&str> = self.kinds.iter().map(|kind| kind.name()).collect();
+            params.push(("kinds", kinds.join(",")));
+        }
+        params
+    }
+}
+
+#[cfg(feature = "client")]
+/// One parsed server-sent event
+#[derive(Debug, Default, PartialEq)]
+struct Frame {
+    id: Option<String>,
+    event: Option<String>,
+    data: String,
+    retry: Option<u64>,
+}
+
+#[cfg(feature = "client")]
+/// Parse one event block (the text between blank lines)
+fn parse_frame(block: &str) -> Frame {
+    let mut frame = Frame::default();
+    let mut data = Vec::new();
+
+    for line in block.lines() {
+        if line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "id" => frame.id = Some(value.to_string()),
+            "event" => frame.event = Some(value.to_string()),
+            "data" => data.push(value),
+            "retry" => frame.retry = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    frame.data = data.join("\n");
+    frame
+}
+
+#[cfg(feature = "client")]
+/// Position of the first blank line, and the length of the separator
+fn frame_end(buffer: &[u8]) -> Option<(usize, usize)> {
+    (0..buffer.len()).find_map(|i| {
+        if buffer[i..].starts_with(b"\r\n\r\n") {
+            Some((i, 4))
+        } else if buffer[i..].starts_with(b"\n\n") {
+            Some((i, 2))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Follow the activity feed. Nothing is sent until the first `next`.
+    pub fn tail_activity(&self, filter: ActivityFilter) -> ActivityTail<'_, A> {
+        ActivityTail {
+            client: self,
+            cursor: filter.cursor.clone(),
+            filter,
+            response: None,
+            buffer: Vec::new(),
+            retry: Duration::from_secs(1),
+            failures: 0,
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+/// Live cursor over the activity feed
+pub struct ActivityTail<'a, A = Anonymous> {
+    client: &'a CovenantClient<A>,
+    filter: ActivityFilter,
+    cursor: Option<String>,
+    response: Option<reqwest::Response>,
+    buffer: Vec<u8>,
+    /// Base reconnect delay; the service may change it with `retry:`
+    retry: Duration,
+    failures: u32,
+    done: bool,
+}
+
+#[cfg(feature = "client")]
+impl<A> ActivityTail<'_, A> {
+    /// Cursor of the last event returned, to resume from after a restart
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    /// Wait for the next event. Dropped connections are retried with backoff;
+    /// an error is returned after repeated failures, and calling `next` again
+    /// starts retrying afresh. Returns `None` once cancelled or if the filter
+    /// is invalid.
+    pub async fn next(&mut self) -> Option<Result<ActivityEvent, CovenantError>> {
+        if self.done {
+            return None;
+        }
+        if let Err(e) = self.filter.validate() {
+            self.done = true;
+            return Some(Err(e));
+        }
+
+        loop {
+            if let Some(event) = self.take_event() {
+                return Some(event);
+            }
+
+            let chunk = match self.response.as_mut() {
+                Some(response) => self.client.call_options.cancellable(async { Ok(response.chunk().await?) }).await,
+                None => match self.connect().await {
+                    Ok(response) => {
+                        self.response = Some(response);
+                        continue;
+                    }
+                    Err(e) => Err(e),
+                },
+            };
+
+            match chunk {
+                Ok(Some(bytes)) => self.buffer.extend_from_slice(&bytes),
+                Ok(None) => {
+                    self.reset();
+                    tokio::time::sleep(self.retry).await;
+                }
+                Err(CovenantError::Cancelled) => {
+                    self.done = true;
+                    return Some(Err(CovenantError::Cancelled));
+                }
+                Err(e) => {
+                    self.reset();
+                    self.failures += 1;
+                    if self.failures >= MAX_RECONNECT_ATTEMPTS {
+                        self.failures = 0;
+                        return Some(Err(e));
+                    }
+                    tokio::time::sleep(self.retry * 2u32.pow(self.failures - 1)).await;
+                }
+            }
+        }
+    }
+
+    async fn connect(&self) -> Result<reqwest::Response, CovenantError> {
+        let client = self.client;
+        let url = format!("{}/activity/feed", client.base_url);
+        let mut request = client.client.get(&url)
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .query(&self.filter.params());
+        if let Some(cursor) = &self.cursor {
+            request = request.header(LAST_EVENT_ID_HEADER, cursor.as_str());
+        }
+
+        let response = client.send(client.signed_read(request, None)?).await?;
+        if !response.status().is_success() {
+            return Err(CovenantError::ServiceError(format!("Activity feed failed with status {}", response.status())));
+        }
+        Ok(response)
+    }
+
+    /// Drop the connection and any partial frame so the next read reconnects
+    fn reset(&mut self) {
+        self.response = None;
+        self.buffer.clear();
+    }
+
+    /// Pop the next activity event out of the buffer, skipping heartbeats and other event types
+    fn take_event(&mut self) -> Option<Result<ActivityEvent, CovenantError>> {
+        while let Some((end, separator)) = frame_end(&self.buffer) {
+            let block: Vec<u8> = self.buffer.drain(..end + separator).take(end).collect();
+            let frame = parse_frame(&String::from_utf8_lossy(&block));
+
+            if let Some(retry) = frame.retry {
+                self.retry = Duration::from_millis(retry);
+            }
+            // The last id seen is the resume point, whether or not its frame carried an event
+            let frame_id = frame.id.filter(|id| !id.is_empty());
+            if let Some(id) = &frame_id {
+                self.cursor = Some(id.clone());
+            }
+            if frame.data.is_empty() || frame.event.as_deref().is_some_and(|event| event != "activity") {
+                continue;
+            }
+
+            let mut event: ActivityEvent = match serde_json::from_str(&frame.data) {
+                Ok(event) => event,
+                Err(e) => return Some(Err(e.into())),
+            };
+            if frame_id.is_some() || event.cursor.is_empty() {
+                event.cursor = self.cursor.clone().unwrap_or_default();
+            }
+            if !event.cursor.is_empty() {
+                self.cursor = Some(event.cursor.clone());
+            }
+
+            self.failures = 0;
+            return Some(Ok(event));
+        }
+
+        None
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = r#"{"kind":"stepSigned","contractUuid":"contract","orgUuid":null,"actor":null,"stepId":"step-1","timestamp":0}"#;
+
+    fn tail(client: &CovenantClient) -> ActivityTail<'_> {
+        client.tail_activity(ActivityFilter::new().org("org"))
+    }
+
+    #[test]
+    fn frames_keep_their_id_without_data() {
+        let frame = parse_frame("id: 41\nretry: 500");
+        assert_eq!(frame.id.as_deref(), Some("41"));
+        assert_eq!(frame.retry, Some(500));
+        assert!(frame.data.is_empty());
+    }
+
+    #[test]
+    fn frames_end_at_a_blank_line_of_either_style() {
+        assert_eq!(frame_end(b"data: a\n\ndata: b"), Some((7, 2)));
+        assert_eq!(frame_end(b"data: a\r\n\r\ndata: b"), Some((7, 4)));
+        assert_eq!(frame_end(b"data: a\r\n"), None);
+        assert_eq!(parse_frame("id: 7\r\ndata: a\r\ndata: b").data, "a\nb");
+    }
+
+    #[test]
+    fn id_only_frames_move_the_cursor() {
+        let client = CovenantClient::new("http://localhost".to_string()).unwrap();
+        let mut tail = tail(&client);
+        tail.buffer.extend_from_slice(format!(": heartbeat\nid: 41\n\ndata: {}\n\n", EVENT).as_bytes());
+
+        let event = tail.take_event().unwrap().unwrap();
+        assert_eq!(event.cursor, "41");
+        assert_eq!(tail.cursor(), Some("41"));
+
+        tail.buffer.extend_from_slice(b"event: ping\nid: 42\ndata: {}\n\n");
+        assert!(tail.take_event().is_none());
+        assert_eq!(tail.cursor(), Some("42"));
+    }
+
+    #[test]
+    fn frames_split_across_chunks() {
+        let client = CovenantClient::new("http://localhost".to_string()).unwrap();
+        let mut tail = tail(&client);
+        let stream = format!("id: 9\r\ndata: {}\r\n\r\n", EVENT);
+        let (first, rest) = stream.split_at(stream.len() - 3);
+
+        tail.buffer.extend_from_slice(first.as_bytes());
+        assert!(tail.take_event().is_none());
+        tail.buffer.extend_from_slice(rest.as_bytes());
+
+        let event = tail.take_event().unwrap().unwrap();
+        assert_eq!(event.cursor, "9");
+        assert_eq!(event.step_id.as_deref(), Some("step-1"));
+        assert!(tail.buffer.is_empty());
+    }
+}
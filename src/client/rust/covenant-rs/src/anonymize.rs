@@ -0,0 +1,251 @@
+/*!
+ * Contract anonymization
+ * Produces a redacted copy of a contract for support and analytics: the
+ * same shape (steps, phases, signing order, which steps are signed) with
+ * participants replaced by stable pseudonyms, amounts bucketed and free text
+ * optionally stripped. Pseudonyms are derived from a salt, so the same key
+ * maps to the same pseudonym across every contract anonymized with it.
+ */
+
+use std::collections::HashMap;
+
+use crate::preview::AMOUNT_KEYS;
+use crate::{Contract, SigningOrder, StepSignature};
+
+const PSEUDONYM_NAMESPACE: uuid::Uuid = uuid::Uuid::from_u128(0x2d8e_51b4_07c3_4f6a_9e21_b7d0_5c18_a3f9);
+const REDACTED: &str = "redacted";
+
+#[derive(Debug, Clone)]
+pub struct AnonymizeOptions {
+    /// Secret mixed into pseudonyms; keep it to get the same pseudonyms again
+    pub salt: String,
+    /// Round amounts down to their order of magnitude (1234 -> 1000)
+    pub bucket_amounts: bool,
+    /// Replace titles, descriptions and free-text spell fields
+    pub strip_descriptions: bool,
+    /// Keep metadata and variables (amounts in them are still bucketed)
+    pub keep_metadata: bool,
+}
+
+impl AnonymizeOptions {
+    pub fn new<S: Into<String>>(salt: S) -> Self {
+        Self {
+            salt: salt.into(),
+            bucket_amounts: true,
+            strip_descriptions: false,
+            keep_metadata: false,
+        }
+    }
+
+    pub fn bucket_amounts(mut self, bucket_amounts: bool) -> Self {
+        self.bucket_amounts = bucket_amounts;
+        self
+    }
+
+    pub fn strip_descriptions(mut self, strip_descriptions: bool) -> Self {
+        self.strip_descriptions = strip_descriptions;
+        self
+    }
+
+    pub fn keep_metadata(mut self, keep_metadata: bool) -> Self {
+        self.keep_metadata = keep_metadata;
+        self
+    }
+
+    /// Stable pseudonym for a key or identifier
+    pub fn pseudonym(&self, value: &str) -> String {
+        let id = uuid::Uuid::new_v5(&PSEUDONYM_NAMESPACE, format!("{}{}", self.salt, value).as_bytes());
+        format!("anon-{}", &id.simple().to_string()[..12])
+    }
+}
+
+/// Largest power of ten not above `amount`, keeping the sign
+fn bucket(amount: f64) -> f64 {
+    if amount == 0.0 || !amount.is_finite() {
+        return 0.0;
+    }
+    amount.signum() * 10f64.powf(amount.abs().log10().floor())
+}
+
+struct Redactor<'a> {
+    options: &'a AnonymizeOptions,
+    participants: HashMap<String, String>,
+}
+
+impl Redactor<'_> {
+    fn key(&self, key: &str) -> String {
+        self.participants.get(key).cloned().unwrap_or_else(|| self.options.pseudonym(key))
+    }
+
+    fn value(&self, field: Option<&str>, value: &serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(fields) => fields.iter()
+                .map(|(name, value)| (name.clone(), self.value(Some(name), value)))
+                .collect(),
+            serde_json::Value::Array(items) => items.iter().map(|item| self.value(field, item)).collect(),
+            serde_json::Value::Number(number) if self.options.bucket_amounts && field.is_some_and(|f| AMOUNT_KEYS.contains(&f)) => {
+                let bucketed = bucket(number.as_f64().unwrap_or_default());
+                if number.is_f64() { serde_json::json!(bucketed) } else { serde_json::json!(bucketed as i64) }
+            }
+            serde_json::Value::String(text) if self.participants.contains_key(text) => serde_json::json!(self.participants[text]),
+            serde_json::Value::String(_) if self.options.strip_descriptions && field != Some("spell") => serde_json::json!(REDACTED),
+            other => other.clone(),
+        }
+    }
+
+    fn order(&self, order: &SigningOrder) -> SigningOrder {
+        match order {
+            SigningOrder::Custom(sequence) => SigningOrder::Custom(sequence.iter().map(|key| self.key(key)).collect()),
+            other => other.clone(),
+        }
+    }
+}
+
+impl Contract {
+    /// Redacted copy that keeps the contract's structure
+    pub fn anonymize(&self, options: &AnonymizeOptions) -> Contract {
+        let redactor = Redactor {
+            options,
+            participants: self.participants.iter()
+                .map(|participant| (participant.clone(), options.pseudonym(participant)))
+                .collect(),
+        };
+
+        let mut contract = self.clone();
+        contract.uuid = options.pseudonym(&self.uuid);
+        contract.reference = None;
+        contract.participants = self.participants.iter().map(|key| redactor.key(key)).collect();
+        contract.signing_order = redactor.order(&self.signing_order);
+        contract.product_uuid = None;
+        contract.bdo_location = None;
+        contract.org_uuid = None;
+        contract.read_grants = self.read_grants.iter().map(|key| redactor.key(key)).collect();
+        contract.availability = self.availability.iter().map(|(key, windows)| (redactor.key(key), windows.clone())).collect();
+        contract.series_id = self.series_id.as_deref().map(|id| options.pseudonym(id));
+        contract.review = None;
+
+        if options.keep_metadata {
+            contract.metadata = self.metadata.iter().map(|(k, v)| (k.clone(), redactor.value(Some(k), v))).collect();
+            contract.variables = self.variables.iter().map(|(k, v)| (k.clone(), redactor.value(Some(k), v))).collect();
+        } else {
+            contract.metadata.clear();
+            contract.variables.clear();
+        }
+
+        if options.strip_descriptions {
+            contract.title = "Contract".to_string();
+            contract.description = String::new();
+        }
+
+        for (index, step) in contract.steps.iter_mut().enumerate() {
+            if options.strip_descriptions {
+                step.description = format!("Step {}", index + 1);
+                for (item, entry) in step.checklist.iter_mut().enumerate() {
+                    entry.text = format!("Item {}", item + 1);
+                }
+            }
+            for entry in step.checklist.iter_mut() {
+                entry.completed_by = entry.completed_by.as_ref().map(|by| redactor.key(by));
+            }
+            step.magic_spell = step.magic_spell.as_ref().map(|spell| redactor.value(None, spell));
+            step.signing_order = step.signing_order.as_ref().map(|order| redactor.order(order));
+            step.proposals.clear();
+            step.signatures = step.signatures.iter().map(|(key, signature)| {
+                let pseudonym = redactor.key(key);
+                let signature = signature.as_ref().map(|signature| StepSignature {
+                    signature: REDACTED.to_string(),
+                    message: REDACTED.to_string(),
+                    pub_key: signature.pub_key.as_ref().map(|_| pseudonym.clone()),
+                    ..signature.clone()
+                });
+                (pseudonym, signature)
+            }).collect();
+            if options.keep_metadata {
+                step.metadata = step.metadata.iter().map(|(k, v)| (k.clone(), redactor.value(Some(k), v))).collect();
+            } else {
+                step.metadata.clear();
+            }
+        }
+
+        contract
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractFixture;
+
+    fn signed_contract() -> Contract {
+        let mut contract = ContractFixture::new().seed(4).completed(2).build();
+        contract.metadata.insert("amount".to_string(), serde_json::json!(1234));
+        contract.steps[0].magic_spell = Some(serde_json::json!({
+            "spell": "pay",
+            "amount": 4321.5,
+            "payee": contract.participants[1],
+            "memo": "rent for March"
+        }));
+        contract
+    }
+
+    #[test]
+    fn pseudonyms_are_stable_per_salt() {
+        let options = AnonymizeOptions::new("salt");
+        assert_eq!(options.pseudonym("alice"), AnonymizeOptions::new("salt").pseudonym("alice"));
+        assert_ne!(options.pseudonym("alice"), options.pseudonym("bob"));
+        assert_ne!(options.pseudonym("alice"), AnonymizeOptions::new("pepper").pseudonym("alice"));
+
+        let contract = signed_contract();
+        let mut other = ContractFixture::new().seed(5).build();
+        other.participants[0] = contract.participants[0].clone();
+        assert_eq!(contract.anonymize(&options).participants[0], other.anonymize(&options).participants[0]);
+    }
+
+    #[test]
+    fn identities_and_signatures_dont_leak() {
+        let contract = signed_contract();
+        let anonymized = contract.anonymize(&AnonymizeOptions::new("salt").keep_metadata(true).strip_descriptions(true));
+        let json = serde_json::to_string(&anonymized).unwrap();
+
+        assert!(!json.contains(&contract.uuid));
+        for participant in &contract.participants {
+            assert!(!json.contains(participant.as_str()));
+        }
+        for signature in contract.steps.iter().flat_map(|step| step.signatures.values().flatten()) {
+            assert!(!json.contains(&signature.signature));
+        }
+        assert!(!json.contains("rent for March"));
+        assert!(!json.contains("Fixture"));
+    }
+
+    #[test]
+    fn structure_is_kept() {
+        let contract = signed_contract();
+        let anonymized = contract.anonymize(&AnonymizeOptions::new("salt"));
+
+        assert_eq!(anonymized.steps.len(), contract.steps.len());
+        let signed = |contract: &Contract| contract.steps.iter()
+            .map(|step| step.signatures.values().filter(|s| s.is_some()).count())
+            .collect::<Vec<_>>();
+        assert_eq!(signed(&anonymized), signed(&contract));
+        assert_eq!(anonymized.title, contract.title);
+        assert!(anonymized.metadata.is_empty());
+
+        let spell = anonymized.steps[0].magic_spell.as_ref().unwrap();
+        assert_eq!(spell["spell"], "pay");
+        assert_eq!(spell["amount"], 1000.0);
+        assert_eq!(spell["payee"], anonymized.participants[1]);
+    }
+
+    #[test]
+    fn amounts_bucket_to_their_magnitude() {
+        assert_eq!(bucket(1234.0), 1000.0);
+        assert_eq!(bucket(-56.0), -10.0);
+        assert_eq!(bucket(0.25), 0.1);
+        assert_eq!(bucket(0.0), 0.0);
+        assert_eq!(bucket(f64::NAN), 0.0);
+
+        let anonymized = signed_contract().anonymize(&AnonymizeOptions::new("salt").keep_metadata(true));
+        assert_eq!(anonymized.metadata["amount"], serde_json::json!(1000));
+    }
+}
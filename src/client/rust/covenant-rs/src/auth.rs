@@ -0,0 +1,13 @@
+/*!
+ * Client authentication states
+ * Type-state markers for `CovenantClient`, so calling a signing endpoint
+ * without a sessionless identity is a compile error rather than a runtime one.
+ */
+
+/// No identity: read endpoints only
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Anonymous;
+
+/// Has a sessionless identity: read and signing endpoints
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Authenticated;
@@ -0,0 +1,232 @@
+/*!
+ * Participant availability
+ * Each participant can publish the windows in which they're free to sign,
+ * stored on the contract under `availability` keyed by participant. The
+ * scheduling helpers intersect the windows of everyone who still has to sign
+ * a step and suggest the earliest slot long enough for a signing ceremony.
+ *
+ * Windows are UTC instants, so participants in different time zones compare
+ * directly. A signer who hasn't published any windows doesn't constrain the
+ * suggestion; they're listed on it so the caller can chase them.
+ */
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, CovenantError};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient, ServiceResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AvailabilityWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl AvailabilityWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Self, CovenantError> {
+        if end <= start {
+            return Err(CovenantError::ValidationError("Availability window must end after it starts".to_string()));
+        }
+        Ok(Self { start, end })
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Earliest time everyone can attend
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotSuggestion {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Signers without published availability, assumed free
+    pub unconstrained: Vec<String>,
+}
+
+/// Sorted, non-overlapping copy of `windows`
+fn merged(windows: &[AvailabilityWindow]) -> Vec<AvailabilityWindow> {
+    let mut sorted = windows.to_vec();
+    sorted.sort_by_key(|window| window.start);
+
+    let mut merged: Vec<AvailabilityWindow> = Vec::new();
+    for window in sorted {
+        match merged.last_mut() {
+            Some(last) if window.start <= last.end => last.end = last.end.max(window.end),
+            _ => merged.push(window),
+        }
+    }
+    merged
+}
+
+/// Times covered by both lists; both must be merged
+fn intersect(a: &[AvailabilityWindow], b: &[AvailabilityWindow]) -> Vec<AvailabilityWindow> {
+    let (mut i, mut j) = (0, 0);
+    let mut overlap = Vec::new();
+
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let end = a[i].end.min(b[j].end);
+        if start < end {
+            overlap.push(AvailabilityWindow { start, end });
+        }
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    overlap
+}
+
+impl Contract {
+    /// Windows a participant has published, sorted and merged
+    pub fn availability_of(&self, participant: &str) -> Vec<AvailabilityWindow> {
+        self.availability.get(participant).map(|windows| merged(windows)).unwrap_or_default()
+    }
+
+    /// Participants who still have to sign the step
+    fn remaining_signers(&self, step_id: &str) -> Result<Vec<String>, CovenantError> {
+        let step = self.steps.iter().find(|step| step.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+        Ok(step.awaiting_signers(&self.participants))
+    }
+
+    /// Earliest slot of at least `length`, no earlier than `not_before`, when
+    /// all of `signers` are available. `None` if their windows never overlap
+    /// for that long, or no signer has published any.
+    pub fn earliest_common_slot<S: AsRef<str>>(&self, signers: &[S], length: Duration, not_before: DateTime<Utc>) -> Option<SlotSuggestion> {
+        let mut common: Option<Vec<AvailabilityWindow>> = None;
+        let mut unconstrained = Vec::new();
+
+        for signer in signers {
+            let windows = self.availability_of(signer.as_ref());
+            if windows.is_empty() {
+                unconstrained.push(signer.as_ref().to_string());
+                continue;
+            }
+            common = Some(match common {
+                Some(common) => intersect(&common, &windows),
+                None => windows,
+            });
+        }
+
+        common?.into_iter()
+            .map(|window| (window.start.max(not_before), window.end))
+            .find(|(start, end)| *end - *start >= length)
+            .map(|(start, _)| SlotSuggestion { start, end: start + length, unconstrained })
+    }
+
+    /// Earliest slot for a signing ceremony on the step, with everyone who
+    /// hasn't signed it yet
+    pub fn suggest_ceremony_slot(&self, step_id: &str, length: Duration, not_before: DateTime<Utc>) -> Result<Option<SlotSuggestion>, CovenantError> {
+        let signers = self.remaining_signers(step_id)?;
+        Ok(self.earliest_common_slot(&signers, length, not_before))
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Publish this identity's availability on a contract, replacing any
+    /// windows set before. An empty list clears it.
+    pub async fn set_availability(&self, uuid: &str, windows: &[AvailabilityWindow]) -> Result<Contract, CovenantError> {
+        if windows.iter().any(|window| window.end <= window.start) {
+            return Err(CovenantError::ValidationError("Availability window must end after it starts".to_string()));
+        }
+
+        let (uuid, url) = self.contract_url(uuid, "availability").await?;
+        let payload = self.signed_payload(serde_json::json!({ "windows": merged(windows) }), Some(&uuid))?;
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Set availability failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractFixture;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        format!("2026-03-02T{:02}:00:00Z", hour).parse().unwrap()
+    }
+
+    fn window(start: u32, end: u32) -> AvailabilityWindow {
+        AvailabilityWindow::new(at(start), at(end)).unwrap()
+    }
+
+    /// The fixture with each participant's windows, by participant index
+    fn contract(windows: &[&[(u32, u32)]]) -> Contract {
+        let fixture = ContractFixture::new();
+        let mut contract = fixture.build();
+        for (index, windows) in windows.iter().enumerate() {
+            contract.availability.insert(
+                fixture.participant_uuid(index),
+                windows.iter().map(|(start, end)| window(*start, *end)).collect(),
+            );
+        }
+        contract
+    }
+
+    #[test]
+    fn windows_must_end_after_they_start() {
+        assert!(AvailabilityWindow::new(at(10), at(10)).is_err());
+        assert_eq!(window(9, 11).duration(), Duration::hours(2));
+    }
+
+    #[test]
+    fn published_windows_are_merged() {
+        let contract = contract(&[&[(13, 15), (9, 11), (10, 12)]]);
+        assert_eq!(contract.availability_of(&ContractFixture::new().participant_uuid(0)), [window(9, 12), window(13, 15)]);
+        assert!(contract.availability_of("nobody").is_empty());
+    }
+
+    #[test]
+    fn ceremony_slot_is_the_earliest_long_enough_overlap() {
+        let contract = contract(&[&[(9, 11), (14, 18)], &[(10, 12), (15, 17)]]);
+
+        let slot = contract.suggest_ceremony_slot("step-1", Duration::hours(1), at(0)).unwrap().unwrap();
+        assert_eq!((slot.start, slot.end), (at(10), at(11)));
+        assert!(slot.unconstrained.is_empty());
+
+        let slot = contract.suggest_ceremony_slot("step-1", Duration::hours(2), at(0)).unwrap().unwrap();
+        assert_eq!((slot.start, slot.end), (at(15), at(17)));
+
+        let slot = contract.suggest_ceremony_slot("step-1", Duration::hours(1), at(16)).unwrap().unwrap();
+        assert_eq!(slot.start, at(16));
+        assert_eq!(contract.suggest_ceremony_slot("step-1", Duration::hours(3), at(0)).unwrap(), None);
+    }
+
+    #[test]
+    fn signers_without_windows_are_unconstrained() {
+        let contract = contract(&[&[(9, 11)]]);
+        let slot = contract.suggest_ceremony_slot("step-1", Duration::hours(1), at(0)).unwrap().unwrap();
+        assert_eq!(slot.start, at(9));
+        assert_eq!(slot.unconstrained, [ContractFixture::new().participant_uuid(1)]);
+
+        assert_eq!(Contract::fixture().suggest_ceremony_slot("step-1", Duration::hours(1), at(0)).unwrap(), None);
+    }
+
+    #[test]
+    fn only_remaining_signers_count() {
+        let mut contract = contract(&[&[(9, 11)], &[(14, 16)]]);
+        let signed = ContractFixture::new().completed(1).build().steps[0].signatures.clone();
+        let first = ContractFixture::new().participant_uuid(0);
+        contract.steps[0].signatures.insert(first.clone(), signed[&first].clone());
+
+        let slot = contract.suggest_ceremony_slot("step-1", Duration::hours(1), at(0)).unwrap().unwrap();
+        assert_eq!(slot.start, at(14));
+        assert!(contract.suggest_ceremony_slot("step-9", Duration::hours(1), at(0)).is_err());
+    }
+}
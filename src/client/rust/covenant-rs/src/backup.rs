@@ -0,0 +1,258 @@
+/*!
+ * Signed contract backups
+ * One versioned archive of every contract a user participates in, with its
+ * signatures and a manifest of attachment URLs, signed by the user so it can
+ * be checked before it's restored. Used to migrate between deployments and to
+ * answer data-portability requests.
+ *
+ * Restoring recreates missing contracts with their original uuids, so running
+ * a restore twice is harmless. Signatures can't be replayed onto another
+ * service; the report lists the ones the target lacks so participants can
+ * re-sign. Attachments are listed, not embedded.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{canonical, scheme, Contract, CovenantError, SignatureScheme};
+#[cfg(feature = "client")]
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "client")]
+use crate::{Authenticated, ContractBuilder, CovenantClient};
+
+/// Archive format written by this version of the SDK
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Metadata key recording the backup a restored contract came from
+pub const RESTORED_FROM_KEY: &str = "restoredFrom";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BackupAttachment {
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BackupArchive {
+    pub version: u32,
+    /// Base URL of the service the backup was taken from
+    pub service: String,
+    /// Milliseconds since the epoch
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    pub owner: String,
+    #[serde(rename = "ownerPubKey")]
+    pub owner_pub_key: String,
+    #[serde(default)]
+    pub scheme: SignatureScheme,
+    #[serde(default)]
+    pub contracts: Vec<Contract>,
+    #[serde(default)]
+    pub attachments: Vec<BackupAttachment>,
+    pub signature: String,
+}
+
+impl BackupArchive {
+    /// Canonical message the owner signs: the archive without its signature
+    pub fn message(&self) -> Result<String, CovenantError> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("signature");
+        }
+        Ok(canonical::canonical_json(&value))
+    }
+
+    /// Check the owner's signature over the archive
+    pub fn verify(&self) -> Result<bool, CovenantError> {
+        scheme::verify(self.scheme, &self.signature, &self.message()?, &self.owner_pub_key)
+    }
+
+    /// Parse an archive, rejecting versions newer than this SDK understands
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, CovenantError> {
+        let version = serde_json::from_slice::<serde_json::Value>(bytes)?
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .ok_or_else(|| CovenantError::ValidationError("Backup has no version".to_string()))?;
+
+        if version == 0 || version > u64::from(BACKUP_VERSION) {
+            return Err(CovenantError::ValidationError(format!("Unsupported backup version {}", version)));
+        }
+
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    #[cfg(feature = "client")]
+    /// Attachment URLs listed by the archived contracts
+    fn manifest(contracts: &[Contract]) -> Vec<BackupAttachment> {
+        contracts.iter()
+            .flat_map(|contract| contract.attachment_urls().into_iter().map(|url| BackupAttachment {
+                contract_uuid: contract.uuid.clone(),
+                url,
+            }))
+            .collect()
+    }
+}
+
+/// A signature present in the backup but not on the target service
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingSignature {
+    pub step_id: String,
+    pub signer: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreOutcome {
+    /// Recreated on the target under its original uuid
+    Created,
+    /// Already on the target with every backed-up signature
+    UpToDate,
+    /// On the target, but its steps differ or it lacks backed-up signatures
+    Diverged,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct RestoredContract {
+    pub contract_uuid: String,
+    pub outcome: RestoreOutcome,
+    pub missing_signatures: Vec<MissingSignature>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RestoreReport {
+    pub contracts: Vec<RestoredContract>,
+}
+
+impl RestoreReport {
+    pub fn count(&self, outcome: &RestoreOutcome) -> usize {
+        self.contracts.iter().filter(|c| &c.outcome == outcome).count()
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &RestoredContract> {
+        self.contracts.iter().filter(|c| matches!(c.outcome, RestoreOutcome::Failed(_)))
+    }
+}
+
+#[cfg(feature = "client")]
+/// Backed-up signatures that `current` doesn't have. Recreated contracts get
+/// fresh step ids, so steps are matched by id and then by position.
+fn missing_signatures(backup: &Contract, current: Option<&Contract>) -> Vec<MissingSignature> {
+    let mut missing: Vec<MissingSignature> = backup.steps.iter().enumerate()
+        .flat_map(|(index, step)| {
+            let current_step = current.and_then(|c| c.steps.iter().find(|s| s.id == step.id).or(c.steps.get(index)));
+            step.signatures.iter()
+                .filter(|(_, signature)| signature.is_some())
+                .filter(move |(signer, _)| !current_step
+                    .and_then(|s| s.signatures.get(*signer))
+                    .is_some_and(|s| s.is_some()))
+                .map(|(signer, _)| MissingSignature { step_id: step.id.clone(), signer: signer.clone() })
+        })
+        .collect();
+    missing.sort_by(|a, b| a.step_id.cmp(&b.step_id).then_with(|| a.signer.cmp(&b.signer)));
+    missing
+}
+
+#[cfg(feature = "client")]
+fn same_structure(a: &Contract, b: &Contract) -> bool {
+    a.steps.len() == b.steps.len()
+        && a.steps.iter().zip(&b.steps).all(|(a, b)| a.description == b.description)
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Write a signed archive of every contract this identity participates
+    /// in. Returns the number of contracts archived.
+    pub async fn backup_my_contracts<W>(&self, writer: &mut W) -> Result<usize, CovenantError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let summaries = self.get_my_contracts().await?;
+        let uuids: Vec<&str> = summaries.iter().map(|summary| summary.uuid.as_str()).collect();
+        let contracts = self.get_contracts(&uuids).await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let signer = self.identity();
+        let mut archive = BackupArchive {
+            version: BACKUP_VERSION,
+            service: self.base_url.clone(),
+            created_at: self.now().timestamp_millis(),
+            owner: signer.uuid().to_string(),
+            owner_pub_key: signer.public_key().to_string(),
+            scheme: signer.scheme(),
+            attachments: BackupArchive::manifest(&contracts),
+            contracts,
+            signature: String::new(),
+        };
+        archive.signature = signer.sign(&archive.message()?)?;
+
+        writer.write_all(&serde_json::to_vec(&archive)?).await?;
+        writer.flush().await?;
+        Ok(archive.contracts.len())
+    }
+}
+
+#[cfg(feature = "client")]
+/// Verify a backup and recreate or reconcile its contracts on `target`.
+/// Per-contract failures are reported rather than aborting the restore.
+pub async fn restore_backup<R>(reader: &mut R, target: &CovenantClient<Authenticated>) -> Result<RestoreReport, CovenantError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    let archive = BackupArchive::from_slice(&bytes)?;
+
+    if !archive.verify()? {
+        return Err(CovenantError::ValidationError("Backup signature does not verify".to_string()));
+    }
+
+    let mut report = RestoreReport::default();
+    for contract in &archive.contracts {
+        let restored = match restore_contract(&archive, contract, target).await {
+            Ok((outcome, missing_signatures)) => RestoredContract {
+                contract_uuid: contract.uuid.clone(),
+                outcome,
+                missing_signatures,
+            },
+            Err(e) => RestoredContract {
+                contract_uuid: contract.uuid.clone(),
+                outcome: RestoreOutcome::Failed(e.to_string()),
+                missing_signatures: missing_signatures(contract, None),
+            },
+        };
+        report.contracts.push(restored);
+    }
+
+    Ok(report)
+}
+
+#[cfg(feature = "client")]
+async fn restore_contract(archive: &BackupArchive, contract: &Contract, target: &CovenantClient<Authenticated>) -> Result<(RestoreOutcome, Vec<MissingSignature>), CovenantError> {
+    if let Some(existing) = target.find_contract(&contract.uuid).await? {
+        let missing = missing_signatures(contract, Some(&existing));
+        let outcome = if missing.is_empty() && same_structure(contract, &existing) {
+            RestoreOutcome::UpToDate
+        } else {
+            RestoreOutcome::Diverged
+        };
+        return Ok((outcome, missing));
+    }
+
+    let mut source = contract.clone();
+    source.org_uuid = None;
+    let builder = ContractBuilder::from_contract(&source).metadata(RESTORED_FROM_KEY, serde_json::json!({
+        "service": archive.service,
+        "createdAt": archive.created_at
+    }));
+
+    let mut payload = builder.build()?;
+    if let Some(fields) = payload.as_object_mut() {
+        fields.insert("uuid".to_string(), serde_json::Value::String(contract.uuid.clone()));
+    }
+
+    let created = target.submit_contract(payload).await?;
+    Ok((RestoreOutcome::Created, missing_signatures(contract, Some(&created))))
+}
@@ -0,0 +1,635 @@
+/*!
+ * Contract builder
+ */
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::canonical;
+use crate::limits::AUTH_FIELDS_ESTIMATE;
+use crate::variables;
+use crate::{ChecklistItem, Contract, ContractLimits, ContractStep, CovenantError, MagicSpellPayload, ParticipantBook, Phase, PhaseBuilder, SigningOrder, RecurrenceRule, Review, Reviewer, SpellRegistry, SpellWarning, Visibility};
+
+/// Namespace for series ids derived from a recurring contract's payload
+const SERIES_NAMESPACE: uuid::Uuid = uuid::Uuid::from_u128(0x91c4_7e0b_3a58_4d2f_b6e1_05f8_c27a_94d3);
+
+/// Builder for creating contracts
+#[derive(Debug, Clone)]
+pub struct ContractBuilder {
+    title: Option<String>,
+    description: Option<String>,
+    participants: Vec<String>,
+    /// Names `participants_from` couldn't find in its book
+    unknown_participants: Vec<String>,
+    steps: Vec<StepBuilder>,
+    product_uuid: Option<String>,
+    bdo_location: Option<String>,
+    org_uuid: Option<String>,
+    tags: Vec<String>,
+    signing_order: SigningOrder,
+    visibility: Option<Visibility>,
+    recurrence: Option<RecurrenceRule>,
+    series_id: Option<String>,
+    occurrence: Option<u32>,
+    revocation_window: Option<u64>,
+    publish_activity: bool,
+    phases: Vec<String>,
+    metadata: HashMap<String, serde_json::Value>,
+    spell_registry: Option<SpellRegistry>,
+    limits: ContractLimits,
+    content_addressed: bool,
+    review: Option<Review>,
+    variables: HashMap<String, serde_json::Value>,
+    /// Server-side draft this builder was loaded from
+    draft_uuid: Option<String>,
+}
+
+impl ContractBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: None,
+            description: None,
+            participants: Vec::new(),
+            unknown_participants: Vec::new(),
+            steps: Vec::new(),
+            product_uuid: None,
+            bdo_location: None,
+            org_uuid: None,
+            tags: Vec::new(),
+            signing_order: SigningOrder::Any,
+            visibility: None,
+            recurrence: None,
+            series_id: None,
+            occurrence: None,
+            revocation_window: None,
+            publish_activity: false,
+            phases: Vec::new(),
+            metadata: HashMap::new(),
+            spell_registry: None,
+            limits: ContractLimits::default(),
+            content_addressed: false,
+            review: None,
+            variables: HashMap::new(),
+            draft_uuid: None,
+        }
+    }
+
+    /// Builder recreating a contract's structure: steps, spells, phases,
+    /// tags and metadata, without signatures or completion state
+    pub fn from_contract(contract: &Contract) -> Self {
+        let mut builder = Self::new()
+            .title(contract.title.clone())
+            .description(contract.description.clone())
+            .participants(contract.participants.iter().cloned())
+            .tags(contract.tags.iter().cloned())
+            .signing_order(contract.signing_order.clone());
+
+        builder.product_uuid = contract.product_uuid.clone();
+        builder.bdo_location = contract.bdo_location.clone();
+        builder.org_uuid = contract.org_uuid.clone();
+        builder.visibility = Some(contract.visibility);
+        builder.recurrence = contract.recurrence.clone();
+        builder.revocation_window = contract.revocation_window;
+        builder.publish_activity = contract.publish_activity;
+        builder.review = contract.review.as_ref().map(|review| Review {
+            reviewers: review.reviewers.clone(),
+            required_roles: review.required_roles.clone(),
+            ..Review::default()
+        });
+        builder.metadata = contract.metadata.clone();
+        builder.variables = contract.variables.clone();
+        builder.phases = contract.phases.iter().map(|phase| phase.name.clone()).collect();
+        builder.steps = contract.steps.iter().map(|step| StepBuilder {
+            description: step.description.clone(),
+            magic_spell: step.magic_spell.clone(),
+            spell_error: None,
+            deadline: step.deadline_time(),
+            signing_order: step.signing_order.clone(),
+            phase: step.phase.clone(),
+            weight: step.weight,
+            checklist: step.checklist.iter().map(|item| item.text.clone()).collect(),
+            metadata: step.metadata.clone(),
+        }).collect();
+
+        builder
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn participant<S: Into<String>>(mut self, participant: S) -> Self {
+        self.participants.push(participant.into());
+        self
+    }
+
+    pub fn participants<I, S>(mut self, participants: I) -> Self 
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.participants.extend(participants.into_iter().map(|p| p.into()));
+        self
+    }
+
+    /// Add participants by name from a book. Unknown names fail `build`.
+    pub fn participants_from<I, S>(mut self, book: &ParticipantBook, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for name in names {
+            match book.key(name.as_ref()) {
+                Some(key) => self.participants.push(key.to_string()),
+                None => self.unknown_participants.push(name.as_ref().to_string()),
+            }
+        }
+        self
+    }
+
+    pub fn step<S: Into<String>>(mut self, description: S) -> Self {
+        self.steps.push(StepBuilder::new(description));
+        self
+    }
+
+    pub fn step_with_magic<S: Into<String>>(mut self, description: S, magic_spell: serde_json::Value) -> Self {
+        self.steps.push(StepBuilder::new(description).magic_spell(magic_spell));
+        self
+    }
+
+    /// Add a step with a typed spell payload
+    pub fn step_with_payload<S: Into<String>, P: MagicSpellPayload>(mut self, description: S, payload: &P) -> Self {
+        self.steps.push(StepBuilder::new(description).spell_payload(payload));
+        self
+    }
+
+    /// Add a step configured through a `StepBuilder`
+    pub fn step_with<S, F>(mut self, description: S, configure: F) -> Self
+    where
+        S: Into<String>,
+        F: FnOnce(StepBuilder) -> StepBuilder,
+    {
+        self.steps.push(configure(StepBuilder::new(description)));
+        self
+    }
+
+    /// Add a named group of steps configured through a `PhaseBuilder`
+    pub fn phase<S, F>(mut self, name: S, configure: F) -> Self
+    where
+        S: Into<String>,
+        F: FnOnce(PhaseBuilder) -> PhaseBuilder,
+    {
+        let name = name.into();
+        let phase = configure(PhaseBuilder::default());
+        self.steps.extend(phase.steps.into_iter().map(|mut step| {
+            step.phase = Some(name.clone());
+            step
+        }));
+        self.phases.push(name);
+        self
+    }
+
+    pub fn product_uuid<S: Into<String>>(mut self, product_uuid: S) -> Self {
+        self.product_uuid = Some(product_uuid.into());
+        self
+    }
+
+    pub fn bdo_location<S: Into<String>>(mut self, bdo_location: S) -> Self {
+        self.bdo_location = Some(bdo_location.into());
+        self
+    }
+
+    pub fn org_uuid<S: Into<String>>(mut self, org_uuid: S) -> Self {
+        self.org_uuid = Some(org_uuid.into());
+        self
+    }
+
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags.extend(tags.into_iter().map(|t| t.into()));
+        self
+    }
+
+    pub fn metadata<S: Into<String>>(mut self, key: S, value: serde_json::Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Signing order applied to every step without its own order
+    pub fn signing_order(mut self, signing_order: SigningOrder) -> Self {
+        self.signing_order = signing_order;
+        self
+    }
+
+    /// Who can read the contract (anyone with the UUID by default)
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// How long after signing a signer may still revoke, while the step is
+    /// open. Zero disables revocation.
+    pub fn revocation_window(mut self, window: chrono::Duration) -> Self {
+        self.revocation_window = Some(window.num_seconds().max(0) as u64);
+        self
+    }
+
+    /// Post step completions to Dolores activity feeds, for clients with a
+    /// Dolores service attached
+    pub fn publish_activity(mut self, publish: bool) -> Self {
+        self.publish_activity = publish;
+        self
+    }
+
+    /// Bind a `{{name}}` placeholder used in step descriptions
+    pub fn variable<S: Into<String>>(mut self, name: S, value: serde_json::Value) -> Self {
+        self.variables.insert(name.into(), value);
+        self
+    }
+
+    /// Add a reviewer; the contract starts as a draft awaiting review
+    pub fn reviewer<K: Into<String>, R: Into<String>>(mut self, pub_key: K, role: R) -> Self {
+        self.review.get_or_insert_with(Review::default).reviewers.push(Reviewer {
+            pub_key: pub_key.into(),
+            role: role.into(),
+        });
+        self
+    }
+
+    /// Require an approval from a reviewer with this role before signing opens
+    pub fn require_approval<S: Into<String>>(mut self, role: S) -> Self {
+        self.review.get_or_insert_with(Review::default).required_roles.push(role.into());
+        self
+    }
+
+    /// Derive the contract UUID from the payload and creator key, so creating
+    /// the same contract again returns the existing one instead of a duplicate
+    pub fn content_addressed(mut self, content_addressed: bool) -> Self {
+        self.content_addressed = content_addressed;
+        self
+    }
+
+    #[cfg(feature = "client")]
+    pub(crate) fn is_content_addressed(&self) -> bool {
+        self.content_addressed
+    }
+
+    /// The draft this builder edits, if it was loaded with `edit_draft`
+    pub fn draft_uuid(&self) -> Option<&str> {
+        self.draft_uuid.as_deref()
+    }
+
+    #[cfg(feature = "client")]
+    pub(crate) fn with_draft_uuid(mut self, uuid: String) -> Self {
+        self.draft_uuid = Some(uuid);
+        self
+    }
+
+    /// Make this the first occurrence of a new recurring series. The series
+    /// id is derived from the payload, so identical builders agree on it.
+    pub fn recurrence(mut self, rule: RecurrenceRule) -> Self {
+        self.recurrence = Some(rule);
+        if self.series_id.is_none() {
+            self.occurrence = Some(1);
+        }
+        self
+    }
+
+    pub(crate) fn series(mut self, series_id: String, occurrence: u32) -> Self {
+        self.series_id = Some(series_id);
+        self.occurrence = Some(occurrence);
+        self
+    }
+
+    /// Validate spells against a registry when building
+    pub fn spell_registry(mut self, registry: SpellRegistry) -> Self {
+        self.spell_registry = Some(registry);
+        self
+    }
+
+    /// Check step spells against a registry, returning warnings for unknown types
+    pub fn validate_spells(&self, registry: &SpellRegistry) -> Result<Vec<SpellWarning>, CovenantError> {
+        let step_ids: Vec<String> = (1..=self.steps.len()).map(|n| format!("step-{}", n)).collect();
+        registry.validate_spells(self.steps.iter().zip(&step_ids)
+            .filter_map(|(step, id)| step.magic_spell.as_ref().map(|spell| (id.as_str(), spell))))
+    }
+
+    /// Size and complexity limits checked by `build()`
+    pub fn limits(mut self, limits: ContractLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Approximate size in bytes of the payload sent on create, auth fields included
+    pub fn estimate_payload_size(&self) -> Result<usize, CovenantError> {
+        Ok(self.build_payload()?.to_string().len() + AUTH_FIELDS_ESTIMATE)
+    }
+
+    pub fn build(&self) -> Result<serde_json::Value, CovenantError> {
+        let payload = self.build_payload()?;
+        self.limits.check_payload_size(payload.to_string().len() + AUTH_FIELDS_ESTIMATE)?;
+        Ok(payload)
+    }
+
+    fn build_payload(&self) -> Result<serde_json::Value, CovenantError> {
+        let title = self.title.as_ref()
+            .ok_or_else(|| CovenantError::ValidationError("Title is required".to_string()))?;
+
+        if !self.unknown_participants.is_empty() {
+            return Err(CovenantError::ValidationError(
+                format!("Unknown participants: {}", self.unknown_participants.join(", "))
+            ));
+        }
+
+        if self.participants.len() < 2 {
+            return Err(CovenantError::ValidationError("At least 2 participants required".to_string()));
+        }
+
+        if self.steps.is_empty() {
+            return Err(CovenantError::ValidationError("At least 1 step required".to_string()));
+        }
+
+        if let Some(index) = self.steps.iter().position(|step| step.description.trim().is_empty()) {
+            return Err(CovenantError::ValidationError(format!("Step {} must have a description", index + 1)));
+        }
+
+        if let Some((index, error)) = self.steps.iter().enumerate().find_map(|(index, step)| step.spell_error.as_ref().map(|e| (index, e))) {
+            return Err(CovenantError::ValidationError(format!("Step {}: {}", index + 1, error)));
+        }
+
+        if let Some(index) = self.steps.iter().position(|step| step.weight.is_some_and(|w| !w.is_finite() || w < 0.0)) {
+            return Err(CovenantError::ValidationError(format!("Step {} weight must be a non-negative number", index + 1)));
+        }
+
+        let unbound = variables::unbound(self.steps.iter().map(|step| step.description.as_str()), &self.variables);
+        if !unbound.is_empty() {
+            return Err(CovenantError::ValidationError(format!("Unbound step variables: {}", unbound.join(", "))));
+        }
+
+        self.limits.check_counts(self.participants.len(), self.steps.len())?;
+        if let Some(description) = &self.description {
+            self.limits.check_description("Contract", description)?;
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            self.limits.check_description(&format!("Step {}", index + 1), &step.description)?;
+            if step.checklist.iter().any(|text| text.trim().is_empty()) {
+                return Err(CovenantError::ValidationError(format!("Step {} has an empty checklist item", index + 1)));
+            }
+        }
+
+        for (index, name) in self.phases.iter().enumerate() {
+            if name.trim().is_empty() {
+                return Err(CovenantError::ValidationError(format!("Phase {} must have a name", index + 1)));
+            }
+            if self.phases[..index].contains(name) {
+                return Err(CovenantError::ValidationError(format!("Duplicate phase {}", name)));
+            }
+        }
+
+        if let Some(registry) = &self.spell_registry {
+            self.validate_spells(registry)?;
+        }
+
+        let orders = std::iter::once(&self.signing_order)
+            .chain(self.steps.iter().filter_map(|step| step.signing_order.as_ref()));
+        for order in orders {
+            if let SigningOrder::Custom(sequence) = order {
+                if let Some(unknown) = sequence.iter().find(|p| !self.participants.contains(p)) {
+                    return Err(CovenantError::ValidationError(
+                        format!("Signing order names {} who is not a participant", unknown)
+                    ));
+                }
+            }
+        }
+
+        Ok(self.payload(title))
+    }
+
+    /// The payload for an unfinished contract, without any validation
+    #[cfg(feature = "client")]
+    pub(crate) fn draft_payload(&self) -> serde_json::Value {
+        self.payload(self.title.as_deref().unwrap_or_default())
+    }
+
+    fn payload(&self, title: &str) -> serde_json::Value {
+        let steps: Vec<serde_json::Value> = self.steps.iter().enumerate().map(|(index, step)| {
+            serde_json::json!({
+                "id": format!("step-{}", index + 1),
+                "description": step.description,
+                "magicSpell": step.magic_spell,
+                "deadline": step.deadline.map(|d| d.timestamp_millis().to_string()),
+                "signingOrder": step.signing_order,
+                "phase": step.phase,
+                "weight": step.weight,
+                "checklist": step.checklist.iter().enumerate().map(|(item, text)| serde_json::json!({
+                    "id": format!("item-{}", item + 1),
+                    "text": text
+                })).collect::<Vec<_>>(),
+                "metadata": step.metadata
+            })
+        }).collect();
+
+        let phases: Vec<serde_json::Value> = self.phases.iter().map(|name| {
+            let step_ids: Vec<&serde_json::Value> = steps.iter()
+                .filter(|step| step["phase"].as_str() == Some(name.as_str()))
+                .map(|step| &step["id"])
+                .collect();
+            serde_json::json!({ "name": name, "stepIds": step_ids })
+        }).collect();
+
+        let mut payload = serde_json::json!({
+            "title": title,
+            "description": self.description.as_ref().unwrap_or(&String::new()),
+            "participants": self.participants,
+            "steps": steps,
+            "productUuid": self.product_uuid,
+            "bdoLocation": self.bdo_location,
+            "orgUuid": self.org_uuid,
+            "tags": self.tags,
+            "signingOrder": self.signing_order,
+            "visibility": self.visibility,
+            "review": self.review,
+            "recurrence": self.recurrence,
+            "seriesId": self.series_id,
+            "occurrence": self.occurrence,
+            "revocationWindow": self.revocation_window,
+            "publishActivity": self.publish_activity,
+            "phases": phases,
+            "variables": self.variables,
+            "metadata": self.metadata
+        });
+
+        if self.series_id.is_none() && self.recurrence.is_some() {
+            let series_id = uuid::Uuid::new_v5(&SERIES_NAMESPACE, canonical::canonical_json(&payload).as_bytes());
+            payload["seriesId"] = serde_json::json!(series_id.to_string());
+        }
+        payload
+    }
+
+    /// The contract this builder describes, without validating it. Service
+    /// assigned fields (uuid, timestamps, status) are left empty.
+    pub(crate) fn draft(&self) -> Contract {
+        let steps: Vec<ContractStep> = self.steps.iter().enumerate().map(|(index, step)| ContractStep {
+            id: format!("step-{}", index + 1),
+            description: step.description.clone(),
+            magic_spell: step.magic_spell.clone(),
+            order: index,
+            signatures: HashMap::new(),
+            completed: false,
+            created_at: String::new(),
+            completed_at: None,
+            deadline: step.deadline.map(|d| d.timestamp_millis().to_string()),
+            signing_order: step.signing_order.clone(),
+            phase: step.phase.clone(),
+            weight: step.weight,
+            proposals: Vec::new(),
+            checklist: step.checklist.iter().enumerate().map(|(item, text)| ChecklistItem {
+                id: format!("item-{}", item + 1),
+                text: text.clone(),
+                completed: false,
+                completed_by: None,
+                completed_at: None,
+            }).collect(),
+            metadata: step.metadata.clone(),
+        }).collect();
+
+        let phases = self.phases.iter().map(|name| Phase {
+            name: name.clone(),
+            step_ids: steps.iter()
+                .filter(|step| step.phase.as_deref() == Some(name.as_str()))
+                .map(|step| step.id.clone())
+                .collect(),
+        }).collect();
+
+        Contract {
+            uuid: String::new(),
+            reference: None,
+            title: self.title.clone().unwrap_or_default(),
+            description: self.description.clone().unwrap_or_default(),
+            participants: self.participants.clone(),
+            steps,
+            product_uuid: self.product_uuid.clone(),
+            bdo_location: self.bdo_location.clone(),
+            org_uuid: self.org_uuid.clone(),
+            tags: self.tags.clone(),
+            signing_order: self.signing_order.clone(),
+            phases,
+            visibility: self.visibility.unwrap_or_default(),
+            recurrence: self.recurrence.clone(),
+            review: self.review.clone(),
+            variables: self.variables.clone(),
+            series_id: self.payload(self.title.as_deref().unwrap_or_default())["seriesId"].as_str().map(str::to_string),
+            occurrence: self.occurrence,
+            revocation_window: self.revocation_window,
+            publish_activity: self.publish_activity,
+            read_grants: Vec::new(),
+            availability: HashMap::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            revision: None,
+            status: String::new(),
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl Default for ContractBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for a single contract step
+#[derive(Debug, Clone)]
+pub struct StepBuilder {
+    description: String,
+    magic_spell: Option<serde_json::Value>,
+    /// Why `spell_payload` couldn't produce a spell; fails the build
+    spell_error: Option<String>,
+    deadline: Option<DateTime<Utc>>,
+    signing_order: Option<SigningOrder>,
+    pub(crate) phase: Option<String>,
+    weight: Option<f32>,
+    checklist: Vec<String>,
+    metadata: HashMap<String, serde_json::Value>,
+}
+
+impl StepBuilder {
+    pub fn new<S: Into<String>>(description: S) -> Self {
+        Self {
+            description: description.into(),
+            magic_spell: None,
+            spell_error: None,
+            deadline: None,
+            signing_order: None,
+            phase: None,
+            weight: None,
+            checklist: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    pub fn magic_spell(mut self, magic_spell: serde_json::Value) -> Self {
+        self.magic_spell = Some(magic_spell);
+        self.spell_error = None;
+        self
+    }
+
+    /// Set the spell from a typed payload. A payload that fails its schema
+    /// fails the contract's `build`.
+    pub fn spell_payload<P: MagicSpellPayload>(mut self, payload: &P) -> Self {
+        match payload.to_spell() {
+            Ok(spell) => self.magic_spell(spell),
+            Err(e) => {
+                self.magic_spell = None;
+                self.spell_error = Some(match e {
+                    CovenantError::ValidationError(message) => message,
+                    e => e.to_string(),
+                });
+                self
+            }
+        }
+    }
+
+    pub fn deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Relative share of the contract's weighted progress (default 1.0)
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Signing order for this step, overriding the contract's
+    pub fn signing_order(mut self, signing_order: SigningOrder) -> Self {
+        self.signing_order = Some(signing_order);
+        self
+    }
+
+    /// Add an operational sub-task under this step
+    pub fn checklist_item<S: Into<String>>(mut self, text: S) -> Self {
+        self.checklist.push(text.into());
+        self
+    }
+
+    pub fn metadata<S: Into<String>>(mut self, key: S, value: serde_json::Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+}
@@ -0,0 +1,71 @@
+/*!
+ * Canonical forms shared with the other covenant SDKs
+ * Signing messages are plain concatenations; JSON payloads are serialized
+ * with sorted keys and no insignificant whitespace.
+ */
+
+/// Message signed for authenticated requests: timestamp + userUUID (+ contractUUID)
+pub fn auth_message(timestamp: i64, user_uuid: &str, contract_uuid: Option<&str>) -> String {
+    match contract_uuid {
+        Some(contract_uuid) => format!("{}{}{}", timestamp, user_uuid, contract_uuid),
+        None => format!("{}{}", timestamp, user_uuid),
+    }
+}
+
+/// Message signed for a step signature: timestamp + userUUID + contractUUID + stepId
+pub fn step_message(timestamp: i64, user_uuid: &str, contract_uuid: &str, step_id: &str) -> String {
+    format!("{}{}{}{}", timestamp, user_uuid, contract_uuid, step_id)
+}
+
+/// Message signed for a share link: expiry + userUUID + contractUUID + scope
+pub fn share_message(expires_at: i64, user_uuid: &str, contract_uuid: &str, scope: &str) -> String {
+    format!("{}{}{}{}", expires_at, user_uuid, contract_uuid, scope)
+}
+
+/// Namespace for content-addressed contract UUIDs
+pub const CONTRACT_NAMESPACE: uuid::Uuid = uuid::Uuid::from_u128(0x6c1f_4a2e_9b7d_5e30_8f14_c0a9_3d52_e7b8);
+
+/// Content-addressed contract UUID: v5 over the canonical payload (without
+/// auth fields) followed by the creator's public key
+pub fn contract_uuid(payload: &serde_json::Value, creator_pub_key: &str) -> String {
+    let name = format!("{}{}", canonical_json(payload), creator_pub_key);
+    uuid::Uuid::new_v5(&CONTRACT_NAMESPACE, name.as_bytes()).to_string()
+}
+
+/// Serialize JSON with object keys sorted recursively
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+
+            out.push('{');
+            for (i, key) in keys.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String((*key).clone()).to_string());
+                out.push(':');
+                write_canonical(&map[*key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
@@ -0,0 +1,67 @@
+/*!
+ * Service capability discovery
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{format, CovenantClient, CovenantError, SignatureScheme, WireFormat};
+
+/// Optional features advertised by a covenant service
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Capabilities {
+    pub version: Option<String>,
+    /// Supported wire formats by short name ("json", "cbor", "msgpack")
+    #[serde(default)]
+    pub formats: Vec<String>,
+    /// Named optional features, e.g. "dryRun"
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Key the service signs receipts with
+    #[serde(rename = "serverPubKey")]
+    pub server_pub_key: Option<String>,
+    /// Accepted signature schemes; empty means secp256k1 only
+    #[serde(rename = "signatureSchemes", default)]
+    pub signature_schemes: Vec<SignatureScheme>,
+}
+
+impl Capabilities {
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    pub fn supports_scheme(&self, scheme: SignatureScheme) -> bool {
+        match self.signature_schemes.is_empty() {
+            true => scheme == SignatureScheme::Secp256k1,
+            false => self.signature_schemes.contains(&scheme),
+        }
+    }
+
+    /// Most compact format both sides support
+    pub fn preferred_format(&self) -> WireFormat {
+        WireFormat::available().into_iter()
+            .find(|format| self.formats.iter().any(|name| name.eq_ignore_ascii_case(format.name())))
+            .unwrap_or_default()
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Fetch service capabilities; services without the endpoint report none
+    pub async fn get_capabilities(&self) -> Result<Capabilities, CovenantError> {
+        let url = format!("{}/capabilities", self.base_url);
+        let response = self.send(self.client.get(&url)).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Capabilities::default());
+        }
+
+        format::decode_response(response).await
+    }
+
+    /// Switch to the most compact wire format the service supports
+    pub async fn negotiate_wire_format(mut self) -> Result<Self, CovenantError> {
+        let capabilities = self.get_capabilities().await?;
+        self.wire_format = capabilities.preferred_format();
+        Ok(self)
+    }
+}
@@ -0,0 +1,307 @@
+/*!
+ * Completion certificates (requires the `certificate` feature)
+ * A signed, render-ready record that a covenant completed: participants,
+ * dates and a QR code carrying the hash of the contract's signature bundle,
+ * rendered to SVG or a single-page PDF.
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::digest::escape_html;
+use crate::{canonical, encoding, scheme, Contract, CovenantError, SignatureScheme, Signer};
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+
+/// Prefix of the text encoded in the verification QR code
+pub const QR_PREFIX: &str = "covenant:certificate";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CertificateParticipant {
+    pub participant: String,
+    #[serde(rename = "stepsSigned")]
+    pub steps_signed: usize,
+    #[serde(rename = "lastSignedAt")]
+    pub last_signed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Certificate {
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    pub title: String,
+    pub participants: Vec<CertificateParticipant>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Hex SHA-256 of the canonical signature bundle
+    #[serde(rename = "bundleHash")]
+    pub bundle_hash: String,
+    #[serde(rename = "issuedAt")]
+    pub issued_at: DateTime<Utc>,
+    #[serde(rename = "issuerUUID")]
+    pub issuer_uuid: Option<String>,
+    #[serde(rename = "issuerPubKey")]
+    pub issuer_pub_key: Option<String>,
+    /// Issuer's scheme; secp256k1 when not recorded
+    #[serde(rename = "issuerScheme", default, skip_serializing_if = "Option::is_none")]
+    pub issuer_scheme: Option<SignatureScheme>,
+    /// Issuer's signature over `message()`
+    pub signature: Option<String>,
+}
+
+/// Hex SHA-256 over every step's signatures, in canonical JSON
+pub fn signature_bundle_hash(contract: &Contract) -> String {
+    let steps: Vec<serde_json::Value> = contract.steps.iter().map(|step| serde_json::json!({
+        "id": step.id,
+        "signatures": step.signatures
+    })).collect();
+
+    let bundle = canonical::canonical_json(&serde_json::json!({
+        "contractUuid": contract.uuid,
+        "steps": steps
+    }));
+
+    encoding::to_hex(&Sha256::digest(bundle.as_bytes()))
+}
+
+impl Certificate {
+    /// Unsigned certificate for a fully completed contract, issued at `issued_at`
+    pub fn from_contract(contract: &Contract, issued_at: DateTime<Utc>) -> Result<Self, CovenantError> {
+        if contract.steps.is_empty() || contract.steps.iter().any(|step| !step.completed) {
+            return Err(CovenantError::ValidationError(format!("Contract {} is not complete", contract.uuid)));
+        }
+
+        let participants = contract.participants.iter().map(|participant| {
+            let signed: Vec<i64> = contract.steps.iter()
+                .filter_map(|step| step.signatures.get(participant).and_then(|s| s.as_ref()))
+                .map(|signature| signature.timestamp)
+                .collect();
+            CertificateParticipant {
+                participant: participant.clone(),
+                steps_signed: signed.len(),
+                last_signed_at: signed.iter().max().and_then(|ts| DateTime::from_timestamp_millis(*ts)),
+            }
+        }).collect();
+
+        Ok(Certificate {
+            contract_uuid: contract.uuid.clone(),
+            title: contract.title.clone(),
+            participants,
+            created_at: crate::parse_timestamp(&contract.created_at),
+            completed_at: contract.steps.iter().filter_map(|step| step.completed_time()).max(),
+            bundle_hash: signature_bundle_hash(contract),
+            issued_at,
+            issuer_uuid: None,
+            issuer_pub_key: None,
+            issuer_scheme: None,
+            signature: None,
+        })
+    }
+
+    /// Canonical message the issuer signs (everything but the signature)
+    pub fn message(&self) -> Result<String, CovenantError> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("signature");
+        }
+        Ok(canonical::canonical_json(&value))
+    }
+
+    /// Record `signer` as the issuer and sign
+    pub fn sign(&mut self, signer: &dyn Signer) -> Result<(), CovenantError> {
+        self.issuer_uuid = Some(signer.uuid().to_string());
+        self.issuer_pub_key = Some(signer.public_key().to_string());
+        self.issuer_scheme = Some(signer.scheme());
+        self.signature = Some(signer.sign(&self.message()?)?);
+        Ok(())
+    }
+
+    /// Check the issuer signature; unsigned certificates don't verify
+    pub fn verify(&self) -> Result<bool, CovenantError> {
+        match (&self.signature, &self.issuer_pub_key) {
+            (Some(signature), Some(pub_key)) => scheme::verify(self.issuer_scheme.unwrap_or_default(), signature, &self.message()?, pub_key),
+            _ => Ok(false),
+        }
+    }
+
+    /// Check the certificate still matches a contract's signatures
+    pub fn matches(&self, contract: &Contract) -> bool {
+        self.contract_uuid == contract.uuid && self.bundle_hash == signature_bundle_hash(contract)
+    }
+
+    /// Text encoded in the QR code
+    pub fn verification_payload(&self) -> String {
+        format!("{}:{}:{}", QR_PREFIX, self.contract_uuid, self.bundle_hash)
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let date = |time: Option<DateTime<Utc>>| time
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let mut lines = vec![
+            format!("Created {}  -  Completed {}", date(self.created_at), date(self.completed_at)),
+            String::new(),
+            "Participants".to_string(),
+        ];
+        lines.extend(self.participants.iter().map(|p| {
+            format!("{}  ({} steps, last signed {})", p.participant, p.steps_signed, date(p.last_signed_at))
+        }));
+        lines.push(String::new());
+        lines.push(format!("Contract {}", self.contract_uuid));
+        lines.push(format!("Signature bundle {}", self.bundle_hash));
+        lines.push(format!("Issued {}", self.issued_at.format("%Y-%m-%d %H:%M UTC")));
+        lines
+    }
+
+    /// Render as an 800x600 SVG
+    pub fn to_svg(&self) -> Result<String, CovenantError> {
+        let (width, modules) = encoding::qr_modules(&self.verification_payload())?;
+        let module_size = 160.0 / width as f64;
+
+        let mut svg = String::from(concat!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="600" viewBox="0 0 800 600">"##,
+            r##"<rect width="800" height="600" fill="#fffdf6"/>"##,
+            r##"<rect x="16" y="16" width="768" height="568" fill="none" stroke="#4b3f72" stroke-width="4"/>"##,
+            r##"<text x="400" y="80" text-anchor="middle" font-family="Georgia, serif" font-size="30" fill="#4b3f72">Certificate of Completion</text>"##,
+        ));
+        svg.push_str(&format!(
+            r##"<text x="400" y="125" text-anchor="middle" font-family="Georgia, serif" font-size="22">{}</text>"##,
+            escape_html(&self.title)
+        ));
+
+        for (index, line) in self.lines().iter().enumerate() {
+            svg.push_str(&format!(
+                r##"<text x="48" y="{}" font-family="Helvetica, sans-serif" font-size="13">{}</text>"##,
+                175 + index * 20,
+                escape_html(line)
+            ));
+        }
+
+        svg.push_str(r##"<g transform="translate(592,392)" fill="#000">"##);
+        svg.push_str(&encoding::qr_svg_rects(width, &modules, module_size, 0));
+        svg.push_str("</g></svg>");
+
+        Ok(svg)
+    }
+
+    /// Render as a single-page US Letter PDF
+    pub fn to_pdf(&self) -> Result<Vec<u8>, CovenantError> {
+        let (width, modules) = encoding::qr_modules(&self.verification_payload())?;
+        let module_size = 144.0 / width as f64;
+
+        let mut content = String::new();
+        content.push_str("0.29 0.25 0.45 RG 3 w 24 24 564 744 re S\n");
+        content.push_str(&format!("BT /F2 26 Tf 72 710 Td ({}) Tj ET\n", pdf_text("Certificate of Completion")));
+        content.push_str(&format!("BT /F2 18 Tf 72 660 Td ({}) Tj ET\n", pdf_text(&self.title)));
+        for (index, line) in self.lines().iter().enumerate() {
+            content.push_str(&format!("BT /F1 10 Tf 72 {} Td ({}) Tj ET\n", 620 - index * 16, pdf_text(line)));
+        }
+
+        content.push_str("0 0 0 rg\n");
+        for (index, _) in modules.iter().enumerate().filter(|(_, dark)| **dark) {
+            content.push_str(&format!(
+                "{:.2} {:.2} {:.2} {:.2} re f\n",
+                420.0 + (index % width) as f64 * module_size,
+                200.0 - (index / width) as f64 * module_size,
+                module_size,
+                module_size
+            ));
+        }
+
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> >>".to_string(),
+            format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Times-Roman >>".to_string(),
+        ];
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).into_bytes());
+        }
+
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).into_bytes());
+
+        Ok(pdf)
+    }
+}
+
+/// Escape a PDF string literal; the standard fonts only cover ASCII here
+fn pdf_text(text: &str) -> String {
+    text.chars().map(|c| match c {
+        '(' | ')' | '\\' => format!("\\{}", c),
+        c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+        _ => "?".to_string(),
+    }).collect()
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Completion certificate for a finished contract, signed by this client
+    /// when it has an identity
+    pub async fn generate_certificate(&self, uuid: &str) -> Result<Certificate, CovenantError> {
+        let contract = self.get_contract(uuid).await?;
+        let mut certificate = Certificate::from_contract(&contract, self.now())?;
+
+        if let Some(signer) = self.signer.as_deref() {
+            certificate.sign(signer)?;
+        }
+
+        Ok(certificate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractFixture;
+
+    fn issued_at() -> DateTime<Utc> {
+        "2026-01-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn only_complete_contracts_get_certificates() {
+        assert!(Certificate::from_contract(&Contract::fixture(), issued_at()).is_err());
+        let certificate = Certificate::from_contract(&ContractFixture::new().completed(3).build(), issued_at()).unwrap();
+        assert_eq!(certificate.issued_at, issued_at());
+    }
+
+    #[test]
+    fn unsigned_certificates_dont_verify() {
+        let certificate = Certificate::from_contract(&ContractFixture::new().completed(3).build(), issued_at()).unwrap();
+        assert!(!certificate.verify().unwrap());
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn ed25519_certificates_verify() {
+        let mut certificate = Certificate::from_contract(&ContractFixture::new().completed(3).build(), issued_at()).unwrap();
+        certificate.sign(&crate::Ed25519Signer::from_bytes("issuer", &[7; 32])).unwrap();
+
+        assert_eq!(certificate.issuer_scheme, Some(SignatureScheme::Ed25519));
+        assert!(certificate.verify().unwrap());
+
+        certificate.title = "Tampered".to_string();
+        assert!(!certificate.verify().unwrap());
+        certificate.title = "Fixture contract".to_string();
+
+        certificate.issuer_scheme = Some(SignatureScheme::Secp256k1);
+        assert!(!certificate.verify().unwrap_or(false));
+    }
+}
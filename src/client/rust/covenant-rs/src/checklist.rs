@@ -0,0 +1,70 @@
+/*!
+ * Step checklists
+ * Operational sub-tasks under a step. Ticking an item off needs no
+ * signature and doesn't complete the step, but every toggle is recorded and
+ * visible to all participants.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::ContractStep;
+#[cfg(feature = "client")]
+use crate::{Authenticated, Contract, CovenantClient, CovenantError, ServiceResponse};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ChecklistItem {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub completed: bool,
+    /// Participant who last ticked the item off
+    #[serde(rename = "completedBy")]
+    pub completed_by: Option<String>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: Option<i64>,
+}
+
+impl ContractStep {
+    pub fn checklist_item(&self, item_id: &str) -> Option<&ChecklistItem> {
+        self.checklist.iter().find(|item| item.id == item_id)
+    }
+
+    /// Completed and total checklist items
+    pub fn checklist_progress(&self) -> (usize, usize) {
+        let done = self.checklist.iter().filter(|item| item.completed).count();
+        (done, self.checklist.len())
+    }
+
+    /// Unticked checklist items
+    pub fn open_checklist_items(&self) -> Vec<&ChecklistItem> {
+        self.checklist.iter().filter(|item| !item.completed).collect()
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Tick a checklist item off (or back on). Only participants may toggle items.
+    pub async fn complete_checklist_item(
+        &self,
+        contract_uuid: &str,
+        step_id: &str,
+        item_id: &str,
+        completed: bool,
+    ) -> Result<Contract, CovenantError> {
+        let (contract_uuid, url) = self.contract_url(contract_uuid, &format!("step/{}/checklist/{}", step_id, item_id)).await?;
+        let payload = self.signed_payload(serde_json::json!({ "completed": completed }), Some(&contract_uuid))?;
+
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Update checklist item failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+}
@@ -0,0 +1,96 @@
+/*!
+ * Timestamp sources
+ * Every timestamp the client generates (signatures, expiries, certificates)
+ * comes from its `Clock`. The system clock is the default; a `MockClock`
+ * makes signing deterministic in tests and simulations.
+ */
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::skew::ClockSkew;
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The local system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to; clones share the same time
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Arc::new(Mutex::new(now)) }
+    }
+
+    /// Mock clock starting at a millisecond timestamp
+    pub fn at_millis(millis: i64) -> Self {
+        Self::new(DateTime::from_timestamp_millis(millis).unwrap_or_default())
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A clock shifted by a client's estimated server offset, read at each call.
+/// The client already corrects its own signing timestamps per its
+/// `ClockSkewPolicy`; this is for timestamps produced elsewhere.
+#[derive(Clone)]
+pub struct SkewCorrectedClock {
+    inner: Arc<dyn Clock>,
+    skew: ClockSkew,
+}
+
+impl Clock for SkewCorrectedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.inner.now() + self.skew.get().map(Duration::milliseconds).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Source for every timestamp the client generates
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// The client's clock corrected to the server's, as far as it's been observed
+    pub fn skew_corrected_clock(&self) -> SkewCorrectedClock {
+        SkewCorrectedClock {
+            inner: self.clock.clone(),
+            skew: self.skew.clone(),
+        }
+    }
+
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+}
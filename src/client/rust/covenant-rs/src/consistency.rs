@@ -0,0 +1,79 @@
+/*!
+ * Read-your-writes consistency
+ * Reads may be served by a replica that hasn't seen the caller's latest
+ * mutation yet. Mutation responses carry the contract's new revision; reading
+ * with it as a minimum retries (with backoff) until a replica catches up.
+ * The revision comes from the contract body, or the response's `ETag`.
+ */
+
+use std::time::Duration;
+
+use crate::{format, Contract, CovenantClient, CovenantError, ServiceResponse};
+
+/// Hint for services that can route or hold a read until a revision is applied
+pub const MIN_REVISION_HEADER: &str = "X-Min-Revision";
+
+const MAX_ATTEMPTS: u32 = 6;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Revision from an `ETag` like `"42"` or `W/"42"`
+fn etag_revision(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let etag = headers.get(reqwest::header::ETAG)?.to_str().ok()?;
+    etag.trim_start_matches("W/").trim_matches('"').parse().ok()
+}
+
+impl<A> CovenantClient<A> {
+    /// Get a contract at `min_revision` or later, retrying stale replica reads.
+    /// Fails with `StaleRead` if no replica catches up in time.
+    pub async fn get_contract_consistent(&self, uuid: &str, min_revision: u64) -> Result<Contract, CovenantError> {
+        let uuid = &self.resolve_uuid(uuid).await?;
+        let mut backoff = INITIAL_BACKOFF;
+        let mut seen = 0;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let contract = self.get_contract_revision(uuid, min_revision).await?;
+            seen = contract.revision.unwrap_or(0);
+            if seen >= min_revision {
+                return Ok(contract);
+            }
+
+            if attempt < MAX_ATTEMPTS {
+                self.call_options.cancellable(async {
+                    tokio::time::sleep(backoff).await;
+                    Ok(())
+                }).await?;
+                backoff *= 2;
+            }
+        }
+
+        Err(CovenantError::StaleRead(min_revision, seen))
+    }
+
+    async fn get_contract_revision(&self, uuid: &str, min_revision: u64) -> Result<Contract, CovenantError> {
+        let request = self.client.get(self.contract_route(uuid, "")).header(MIN_REVISION_HEADER, min_revision.to_string());
+        let response = self.send(self.signed_read(request, Some(uuid))?).await?;
+        let etag = etag_revision(response.headers());
+
+        let service_response: ServiceResponse<Contract> = format::decode_response(response).await?;
+        self.accept_contract(service_response, etag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(etag: &str) -> Option<u64> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::ETAG, etag.parse().unwrap());
+        etag_revision(&headers)
+    }
+
+    #[test]
+    fn etags_carry_the_revision() {
+        assert_eq!(revision("\"42\""), Some(42));
+        assert_eq!(revision("W/\"7\""), Some(7));
+        assert_eq!(revision("\"abc\""), None);
+        assert_eq!(etag_revision(&reqwest::header::HeaderMap::new()), None);
+    }
+}
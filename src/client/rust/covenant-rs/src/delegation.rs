@@ -0,0 +1,264 @@
+/*!
+ * Scoped delegation tokens
+ * Lets a backend service act for a user without holding the user's key. The
+ * user signs a token granting the service's own key a narrow scope (read
+ * only, signing one step, or full admin) until it expires; the service
+ * attaches it with `with_delegation` and keeps signing requests with its own
+ * identity. Every request then carries the token (in the body, or the
+ * `X-Covenant-Delegation` header for reads), and the client refuses calls the
+ * scope doesn't cover before anything is sent. Step signatures are recorded
+ * for the issuer, with the delegate's key sent alongside as `delegatePubKey`.
+ *
+ * The token signature covers the canonical JSON of every other field.
+ */
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{canonical, scheme, CovenantError, SignatureScheme, Signer};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient};
+
+/// Header carrying the token on reads, so it stays out of URLs and logs
+pub const DELEGATION_HEADER: &str = "X-Covenant-Delegation";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DelegationScope {
+    /// Read endpoints only
+    ReadOnly,
+    /// Reads, plus signing one step
+    SignStep {
+        #[serde(rename = "contractUuid")]
+        contract_uuid: String,
+        #[serde(rename = "stepId")]
+        step_id: String,
+    },
+    /// Everything the issuer can do
+    Admin,
+}
+
+impl DelegationScope {
+    pub fn allows_write(&self) -> bool {
+        matches!(self, DelegationScope::Admin)
+    }
+
+    pub fn allows_sign(&self, contract_uuid: &str, step_id: &str) -> bool {
+        match self {
+            DelegationScope::ReadOnly => false,
+            DelegationScope::SignStep { contract_uuid: scoped_contract, step_id: scoped_step } => {
+                scoped_contract == contract_uuid && scoped_step == step_id
+            }
+            DelegationScope::Admin => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DelegationToken {
+    #[serde(rename = "tokenId")]
+    pub token_id: String,
+    #[serde(rename = "issuerUUID")]
+    pub issuer_uuid: String,
+    #[serde(rename = "issuerPubKey")]
+    pub issuer_pub_key: String,
+    /// Key of the service the token is granted to
+    #[serde(rename = "delegatePubKey")]
+    pub delegate_pub_key: String,
+    pub scope: DelegationScope,
+    /// Milliseconds since the epoch
+    #[serde(rename = "issuedAt")]
+    pub issued_at: i64,
+    /// Milliseconds since the epoch
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+    /// Issuer's scheme; secp256k1 when not recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<SignatureScheme>,
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl DelegationToken {
+    /// Grant `delegate_pub_key` the scope from `issued_at` for `ttl`
+    pub fn issue(issuer: &dyn Signer, delegate_pub_key: &str, scope: DelegationScope, issued_at: DateTime<Utc>, ttl: Duration) -> Result<Self, CovenantError> {
+        if ttl <= Duration::zero() {
+            return Err(CovenantError::ValidationError("Delegation tokens need a positive lifetime".to_string()));
+        }
+
+        let mut token = DelegationToken {
+            token_id: uuid::Uuid::new_v4().to_string(),
+            issuer_uuid: issuer.uuid().to_string(),
+            issuer_pub_key: issuer.public_key().to_string(),
+            delegate_pub_key: delegate_pub_key.to_string(),
+            scope,
+            issued_at: issued_at.timestamp_millis(),
+            expires_at: (issued_at + ttl).timestamp_millis(),
+            scheme: Some(issuer.scheme()).filter(|scheme| *scheme != SignatureScheme::default()),
+            signature: String::new(),
+        };
+        token.signature = issuer.sign(&token.message()?)?;
+        Ok(token)
+    }
+
+    /// Canonical JSON of the token without its signature
+    pub fn message(&self) -> Result<String, CovenantError> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("signature");
+        }
+        Ok(canonical::canonical_json(&value))
+    }
+
+    /// Check the issuer's signature
+    pub fn verify(&self) -> Result<bool, CovenantError> {
+        scheme::verify(self.scheme.unwrap_or_default(), &self.signature, &self.message()?, &self.issuer_pub_key)
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_millis(self.expires_at)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now.timestamp_millis()
+    }
+}
+
+/// What a delegated client is about to do
+#[cfg(feature = "client")]
+pub(crate) enum DelegatedAction<'a> {
+    Write,
+    Sign { contract_uuid: &'a str, step_id: &'a str },
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Fail if the attached delegation token doesn't cover `action`
+    pub(crate) fn check_delegation(&self, action: DelegatedAction<'_>) -> Result<(), CovenantError> {
+        let Some(token) = self.delegation.as_deref() else {
+            return Ok(());
+        };
+
+        if token.is_expired(self.now()) {
+            return Err(CovenantError::ValidationError("Delegation token has expired".to_string()));
+        }
+
+        let allowed = match action {
+            DelegatedAction::Write => token.scope.allows_write(),
+            DelegatedAction::Sign { contract_uuid, step_id } => token.scope.allows_sign(contract_uuid, step_id),
+        };
+        if !allowed {
+            return Err(CovenantError::ValidationError("Delegation token scope doesn't allow this call".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Uuid and key this client acts for: the delegation's issuer, or its own identity
+    pub(crate) fn acting_as(&self) -> (&str, &str) {
+        match self.delegation.as_deref() {
+            Some(token) => (&token.issuer_uuid, &token.issuer_pub_key),
+            None => (self.identity().uuid(), self.identity().public_key()),
+        }
+    }
+
+    /// Grant another key (usually a backend service's) a scoped token
+    pub fn issue_delegation_token(&self, delegate_pub_key: &str, scope: DelegationScope, ttl: Duration) -> Result<DelegationToken, CovenantError> {
+        DelegationToken::issue(self.identity(), delegate_pub_key, scope, self.now(), ttl)
+    }
+
+    /// Act for the token's issuer within its scope. The token must be
+    /// granted to this client's own key, validly signed and unexpired.
+    pub fn with_delegation(mut self, token: DelegationToken) -> Result<Self, CovenantError> {
+        if token.delegate_pub_key != self.identity().public_key() {
+            return Err(CovenantError::ValidationError("Delegation token was granted to a different key".to_string()));
+        }
+        if !token.verify()? {
+            return Err(CovenantError::ValidationError("Delegation token signature is invalid".to_string()));
+        }
+        if token.is_expired(self.now()) {
+            return Err(CovenantError::ValidationError("Delegation token has expired".to_string()));
+        }
+
+        self.delegation = Some(std::sync::Arc::new(token));
+        Ok(self)
+    }
+
+    /// The attached delegation token, if any
+    pub fn delegation(&self) -> Option<&DelegationToken> {
+        self.delegation.as_deref()
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+
+    struct TestSigner;
+
+    impl Signer for TestSigner {
+        fn uuid(&self) -> &str {
+            "delegate"
+        }
+
+        fn public_key(&self) -> &str {
+            "delegate-key"
+        }
+
+        fn scheme(&self) -> SignatureScheme {
+            SignatureScheme::Secp256k1
+        }
+
+        fn sign(&self, message: &str) -> Result<String, CovenantError> {
+            Ok(message.to_string())
+        }
+    }
+
+    /// A delegate client holding `scope`, without the issuer signature check
+    fn delegate(scope: DelegationScope) -> CovenantClient<Authenticated> {
+        let mut client = CovenantClient::new("http://127.0.0.1:9".to_string()).unwrap().with_signer(TestSigner);
+        client.delegation = Some(std::sync::Arc::new(DelegationToken {
+            token_id: "token".to_string(),
+            issuer_uuid: "issuer".to_string(),
+            issuer_pub_key: "issuer-key".to_string(),
+            delegate_pub_key: "delegate-key".to_string(),
+            scope,
+            issued_at: 0,
+            expires_at: i64::MAX,
+            scheme: None,
+            signature: String::new(),
+        }));
+        client
+    }
+
+    fn is_scope_error(error: CovenantError) -> bool {
+        matches!(error, CovenantError::ValidationError(message) if message.contains("scope"))
+    }
+
+    #[tokio::test]
+    async fn read_only_delegate_cannot_delete() {
+        let client = delegate(DelegationScope::ReadOnly);
+        let uuid = "2b0cbe4e-3f8a-4e2b-9d35-1f8a4a6c1d2e";
+
+        assert!(is_scope_error(client.delete_contract(uuid).await.unwrap_err()));
+        assert!(is_scope_error(client.raw().delete::<serde_json::Value>(&format!("contract/{}", uuid)).await.unwrap_err()));
+    }
+
+    #[test]
+    fn sign_scope_covers_one_step() {
+        let client = delegate(DelegationScope::SignStep { contract_uuid: "c".to_string(), step_id: "s".to_string() });
+
+        assert!(client.check_delegation(DelegatedAction::Sign { contract_uuid: "c", step_id: "s" }).is_ok());
+        assert!(client.check_delegation(DelegatedAction::Sign { contract_uuid: "c", step_id: "other" }).is_err());
+        assert!(client.check_delegation(DelegatedAction::Write).is_err());
+    }
+
+    #[test]
+    fn acts_as_the_issuer() {
+        assert_eq!(delegate(DelegationScope::Admin).acting_as(), ("issuer", "issuer-key"));
+    }
+}
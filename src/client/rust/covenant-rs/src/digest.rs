@@ -0,0 +1,301 @@
+/*!
+ * Contract activity digests
+ * Aggregates recent signatures, completions, and pending work for one identity
+ */
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{Clock, Contract};
+#[cfg(feature = "client")]
+use crate::{CovenantClient, CovenantError};
+
+/// Options controlling the digest window
+#[derive(Debug, Clone)]
+pub struct DigestOptions {
+    /// Identity to build the digest for (defaults to the client's sessionless uuid)
+    pub identity: Option<String>,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// How far past `until` a deadline counts as approaching
+    pub deadline_horizon: Duration,
+}
+
+impl DigestOptions {
+    /// Digest covering the `hours` hours up to `clock`'s now
+    pub fn last_hours(clock: &dyn Clock, hours: i64) -> Self {
+        let until = clock.now();
+        Self {
+            identity: None,
+            since: until - Duration::hours(hours),
+            until,
+            deadline_horizon: Duration::days(3),
+        }
+    }
+
+    pub fn identity<S: Into<String>>(mut self, identity: S) -> Self {
+        self.identity = Some(identity.into());
+        self
+    }
+
+    pub fn window(mut self, since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    pub fn deadline_horizon(mut self, horizon: Duration) -> Self {
+        self.deadline_horizon = horizon;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DigestSignature {
+    pub contract_uuid: String,
+    pub contract_title: String,
+    pub step_id: String,
+    pub step_description: String,
+    pub signer: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DigestContract {
+    pub uuid: String,
+    pub title: String,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DigestStep {
+    pub contract_uuid: String,
+    pub contract_title: String,
+    pub step_id: String,
+    pub description: String,
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub identity: String,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub newly_signed: Vec<DigestSignature>,
+    pub completed_contracts: Vec<DigestContract>,
+    pub awaiting_action: Vec<DigestStep>,
+    pub approaching_deadlines: Vec<DigestStep>,
+    /// Contracts that couldn't be fetched, with the error; the rest of the
+    /// digest covers every other contract
+    pub failed: Vec<(String, String)>,
+}
+
+#[cfg(feature = "client")]
+/// Fetch the identity's contracts and build a digest over them
+pub async fn generate<A>(client: &CovenantClient<A>, options: DigestOptions) -> Result<Digest, CovenantError> {
+    let identity = match &options.identity {
+        Some(identity) => identity.clone(),
+        None => client.signer.as_deref()
+            .map(|signer| signer.uuid().to_string())
+            .ok_or_else(|| CovenantError::SessionlessError("Identity or sessionless instance required".to_string()))?,
+    };
+
+    let summaries = client.list_contracts(Some(&identity)).await?;
+    let uuids: Vec<&str> = summaries.iter().map(|summary| summary.uuid.as_str()).collect();
+
+    let mut contracts = Vec::new();
+    let mut failed = Vec::new();
+    for (uuid, result) in uuids.iter().zip(client.get_contracts(&uuids).await) {
+        match result {
+            Ok(contract) => contracts.push(contract),
+            Err(error) => failed.push((uuid.to_string(), error.to_string())),
+        }
+    }
+
+    let mut digest = from_contracts(&identity, &contracts, &options);
+    digest.failed = failed;
+    Ok(digest)
+}
+
+/// Build a digest from already-fetched contracts
+pub fn from_contracts(identity: &str, contracts: &[Contract], options: &DigestOptions) -> Digest {
+    let in_window = |time: DateTime<Utc>| time >= options.since && time < options.until;
+    let horizon = options.until + options.deadline_horizon;
+
+    let mut digest = Digest {
+        identity: identity.to_string(),
+        since: options.since,
+        until: options.until,
+        newly_signed: Vec::new(),
+        completed_contracts: Vec::new(),
+        awaiting_action: Vec::new(),
+        approaching_deadlines: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for contract in contracts {
+        for step in &contract.steps {
+            for (signer, signature) in &step.signatures {
+                let signed_at = signature.as_ref()
+                    .and_then(|s| DateTime::from_timestamp_millis(s.timestamp));
+                if let Some(signed_at) = signed_at.filter(|t| in_window(*t)) {
+                    digest.newly_signed.push(DigestSignature {
+                        contract_uuid: contract.uuid.clone(),
+                        contract_title: contract.title.clone(),
+                        step_id: step.id.clone(),
+                        step_description: step.description.clone(),
+                        signer: signer.clone(),
+                        signed_at,
+                    });
+                }
+            }
+
+            if step.completed {
+                continue;
+            }
+
+            let pending = DigestStep {
+                contract_uuid: contract.uuid.clone(),
+                contract_title: contract.title.clone(),
+                step_id: step.id.clone(),
+                description: step.description.clone(),
+                deadline: step.deadline_time(),
+            };
+
+            if pending.deadline.is_some_and(|deadline| deadline <= horizon) {
+                digest.approaching_deadlines.push(pending.clone());
+            }
+
+            if step.awaiting_signers(&contract.participants).iter().any(|signer| signer == identity) {
+                digest.awaiting_action.push(pending);
+            }
+        }
+
+        let all_completed = !contract.steps.is_empty() && contract.steps.iter().all(|step| step.completed);
+        let completed_at = contract.steps.iter().filter_map(|step| step.completed_time()).max();
+        if let Some(completed_at) = completed_at.filter(|t| all_completed && in_window(*t)) {
+            digest.completed_contracts.push(DigestContract {
+                uuid: contract.uuid.clone(),
+                title: contract.title.clone(),
+                completed_at,
+            });
+        }
+    }
+
+    digest.newly_signed.sort_by_key(|s| s.signed_at);
+    digest.completed_contracts.sort_by_key(|c| c.completed_at);
+    digest.approaching_deadlines.sort_by_key(|s| s.deadline);
+    digest
+}
+
+impl Digest {
+    pub fn is_empty(&self) -> bool {
+        self.newly_signed.is_empty()
+            && self.completed_contracts.is_empty()
+            && self.awaiting_action.is_empty()
+            && self.approaching_deadlines.is_empty()
+    }
+
+    /// Render as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Covenant digest\n\n{} to {}\n",
+            self.since.format("%Y-%m-%d %H:%M UTC"),
+            self.until.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        out.push_str("\n## Awaiting your signature\n\n");
+        push_markdown_items(&mut out, self.awaiting_action.iter().map(|step| self.step_line(step)));
+
+        out.push_str("\n## Approaching deadlines\n\n");
+        push_markdown_items(&mut out, self.approaching_deadlines.iter().map(|step| self.step_line(step)));
+
+        out.push_str("\n## Newly signed\n\n");
+        push_markdown_items(&mut out, self.newly_signed.iter().map(signature_line));
+
+        out.push_str("\n## Completed contracts\n\n");
+        push_markdown_items(&mut out, self.completed_contracts.iter().map(contract_line));
+
+        if !self.failed.is_empty() {
+            out.push_str(&format!("\n_{} contract(s) couldn't be loaded_\n", self.failed.len()));
+        }
+
+        out
+    }
+
+    /// Render as an HTML fragment suitable for email bodies
+    pub fn to_html(&self) -> String {
+        let mut out = format!(
+            "<h1>Covenant digest</h1>\n<p>{} to {}</p>\n",
+            self.since.format("%Y-%m-%d %H:%M UTC"),
+            self.until.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        push_html_section(&mut out, "Awaiting your signature", self.awaiting_action.iter().map(|step| self.step_line(step)));
+        push_html_section(&mut out, "Approaching deadlines", self.approaching_deadlines.iter().map(|step| self.step_line(step)));
+        push_html_section(&mut out, "Newly signed", self.newly_signed.iter().map(signature_line));
+        push_html_section(&mut out, "Completed contracts", self.completed_contracts.iter().map(contract_line));
+
+        if !self.failed.is_empty() {
+            out.push_str(&format!("<p><em>{} contract(s) couldn't be loaded</em></p>\n", self.failed.len()));
+        }
+
+        out
+    }
+
+    fn step_line(&self, step: &DigestStep) -> String {
+        match step.deadline {
+            Some(deadline) if deadline < self.until => format!(
+                "{}: {} (overdue since {})", step.contract_title, step.description, deadline.format("%Y-%m-%d")
+            ),
+            Some(deadline) => format!(
+                "{}: {} (due {})", step.contract_title, step.description, deadline.format("%Y-%m-%d")
+            ),
+            None => format!("{}: {}", step.contract_title, step.description),
+        }
+    }
+}
+
+fn signature_line(signature: &DigestSignature) -> String {
+    format!(
+        "{}: {} signed by {} at {}",
+        signature.contract_title,
+        signature.step_description,
+        signature.signer,
+        signature.signed_at.format("%Y-%m-%d %H:%M UTC")
+    )
+}
+
+fn contract_line(contract: &DigestContract) -> String {
+    format!("{} completed at {}", contract.title, contract.completed_at.format("%Y-%m-%d %H:%M UTC"))
+}
+
+fn push_markdown_items<I: Iterator<Item = String>>(out: &mut String, items: I) {
+    let mut empty = true;
+    for item in items {
+        out.push_str(&format!("- {}\n", item));
+        empty = false;
+    }
+    if empty {
+        out.push_str("_Nothing to report_\n");
+    }
+}
+
+fn push_html_section<I: Iterator<Item = String>>(out: &mut String, heading: &str, items: I) {
+    out.push_str(&format!("<h2>{}</h2>\n", heading));
+
+    let items: Vec<String> = items.map(|item| format!("  <li>{}</li>\n", escape_html(&item))).collect();
+    if items.is_empty() {
+        out.push_str("<p><em>Nothing to report</em></p>\n");
+    } else {
+        out.push_str("<ul>\n");
+        out.push_str(&items.concat());
+        out.push_str("</ul>\n");
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,122 @@
+/*!
+ * Participant directory resolution
+ * Maps the opaque uuids/pub keys on a contract to display names and
+ * avatars so UIs have something human to show.
+ */
+
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{format, Contract, CovenantError};
+
+/// Display details for one participant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ParticipantProfile {
+    /// The uuid or pub key as it appears on the contract
+    pub participant: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    #[serde(rename = "avatarUrl")]
+    pub avatar_url: Option<String>,
+}
+
+/// Looks up display details for participants
+pub trait ParticipantResolver: Send + Sync {
+    /// Resolve one participant, `Ok(None)` if the directory doesn't know them
+    fn resolve<'a>(&'a self, participant: &'a str) -> BoxFuture<'a, Result<Option<ParticipantProfile>, CovenantError>>;
+}
+
+/// Resolver backed by a Planet Nine profile service
+#[derive(Debug, Clone)]
+pub struct ProfileServiceResolver {
+    base_url: String,
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct ProfileRecord {
+    name: Option<String>,
+    #[serde(rename = "imageUri")]
+    image_uri: Option<String>,
+}
+
+impl ProfileServiceResolver {
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: Client::new(),
+        }
+    }
+}
+
+impl ParticipantResolver for ProfileServiceResolver {
+    fn resolve<'a>(&'a self, participant: &'a str) -> BoxFuture<'a, Result<Option<ParticipantProfile>, CovenantError>> {
+        Box::pin(async move {
+            let url = format!("{}/profile/{}", self.base_url, participant);
+            let response = self.client.get(&url).send().await?;
+
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            let record: ProfileRecord = format::decode_response(response).await?;
+            Ok(record.name.map(|display_name| ParticipantProfile {
+                participant: participant.to_string(),
+                display_name,
+                avatar_url: record.image_uri,
+            }))
+        })
+    }
+}
+
+/// A contract decorated with participant display details
+#[derive(Debug, Clone)]
+pub struct ResolvedContract {
+    pub contract: Contract,
+    /// Profiles keyed by participant, missing for unknown participants
+    pub profiles: HashMap<String, ParticipantProfile>,
+}
+
+impl ResolvedContract {
+    /// Display name for a participant, falling back to the raw identifier
+    pub fn display_name<'a>(&'a self, participant: &'a str) -> &'a str {
+        self.profiles.get(participant)
+            .map(|profile| profile.display_name.as_str())
+            .unwrap_or(participant)
+    }
+
+    pub fn avatar_url(&self, participant: &str) -> Option<&str> {
+        self.profiles.get(participant).and_then(|profile| profile.avatar_url.as_deref())
+    }
+}
+
+impl Contract {
+    /// Look up every participant once and attach their display details
+    pub async fn resolve_participants<R: ParticipantResolver + ?Sized>(&self, resolver: &R) -> Result<ResolvedContract, CovenantError> {
+        let mut participants = self.participants.clone();
+        participants.sort();
+        participants.dedup();
+
+        let results: Vec<_> = futures::stream::iter(participants.iter())
+            .map(|participant| resolver.resolve(participant))
+            .buffered(8)
+            .collect()
+            .await;
+
+        let mut profiles = HashMap::new();
+        for result in results {
+            if let Some(profile) = result? {
+                profiles.insert(profile.participant.clone(), profile);
+            }
+        }
+
+        Ok(ResolvedContract {
+            contract: self.clone(),
+            profiles,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+/*!
+ * Activity feed publishing (requires the `dolores` feature)
+ * Posts step completions to a Planet Nine Dolores feed service, so they show
+ * up alongside the rest of the ecosystem's activity. Only contracts built
+ * with `ContractBuilder::publish_activity(true)` are announced, and only by
+ * clients with a Dolores service attached; the post goes out under the
+ * identity whose signature completed the step. Posts are sent in the
+ * background with their own timeout, so a slow feed never holds up signing.
+ *
+ * Posts are JSON templates whose string values may use `{{name}}`
+ * placeholders: `contract.uuid`, `contract.title`, `contract.reference`,
+ * `step.id`, `step.description`, `signer` and `completedAt`.
+ */
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use reqwest::Client;
+
+use crate::{variables, Authenticated, Contract, CovenantClient, CovenantError, SignStepResponse};
+
+/// How long a post may take before it's abandoned
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Post sent when no template is set
+pub fn default_template() -> serde_json::Value {
+    serde_json::json!({
+        "type": "covenant.stepCompleted",
+        "title": "{{contract.title}}",
+        "text": "Completed \"{{step.description}}\"",
+        "contractUuid": "{{contract.uuid}}",
+        "stepId": "{{step.id}}",
+        "author": "{{signer}}",
+        "createdAt": "{{completedAt}}"
+    })
+}
+
+/// Connection to a Dolores service
+#[derive(Debug, Clone)]
+pub struct DoloresService {
+    base_url: String,
+    client: Client,
+    template: serde_json::Value,
+}
+
+impl DoloresService {
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: client(DEFAULT_TIMEOUT),
+            template: default_template(),
+        }
+    }
+
+    /// Abandon posts that take longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client = client(timeout);
+        self
+    }
+
+    /// Replace the post template
+    pub fn template(mut self, template: serde_json::Value) -> Self {
+        self.template = template;
+        self
+    }
+}
+
+fn client(timeout: Duration) -> Client {
+    Client::builder().timeout(timeout).build().unwrap_or_else(|_| Client::new())
+}
+
+/// Fill `template` for a completed step. Fails if the contract has no such step.
+pub fn render_activity(template: &serde_json::Value, contract: &Contract, step_id: &str, signer: &str, completed_at: &str) -> Result<serde_json::Value, CovenantError> {
+    let step = contract.rendered_steps().into_iter().find(|step| step.id == step_id)
+        .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+    let values: HashMap<String, serde_json::Value> = [
+        ("contract.uuid", contract.uuid.clone()),
+        ("contract.title", contract.title.clone()),
+        ("contract.reference", contract.contract_ref().map(|r| r.to_string()).unwrap_or_default()),
+        ("step.id", step.id),
+        ("step.description", step.description),
+        ("signer", signer.to_string()),
+        ("completedAt", completed_at.to_string()),
+    ].into_iter().map(|(name, value)| (name.to_string(), serde_json::Value::String(value))).collect();
+
+    Ok(fill(template, &values))
+}
+
+fn fill(template: &serde_json::Value, values: &HashMap<String, serde_json::Value>) -> serde_json::Value {
+    match template {
+        serde_json::Value::String(text) => serde_json::Value::String(variables::render(text, values)),
+        serde_json::Value::Array(items) => items.iter().map(|item| fill(item, values)).collect(),
+        serde_json::Value::Object(fields) => fields.iter()
+            .map(|(key, value)| (key.clone(), fill(value, values)))
+            .collect(),
+        other => other.clone(),
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Announce completions of opted-in contracts through a Dolores service
+    pub fn with_dolores_service(mut self, service: DoloresService) -> Self {
+        self.dolores = Some(service);
+        self
+    }
+}
+
+impl CovenantClient<Authenticated> {
+    /// Post a step completion of `contract` in the background if it opted
+    /// in. Failures are dropped, so a feed outage never fails a signature.
+    pub(crate) fn publish_step_completion(&self, contract: &Contract, response: &SignStepResponse) {
+        if !response.step_completed || !contract.publish_activity {
+            return;
+        }
+        let Some(service) = self.dolores.clone() else {
+            return;
+        };
+        let Ok(post) = render_activity(&service.template, contract, &response.step_id, self.identity().uuid(), &self.now().to_rfc3339()) else {
+            return;
+        };
+
+        let client = self.clone();
+        tokio::spawn(async move {
+            let _ = client.post_activity(&service, post).await;
+        });
+    }
+
+    async fn post_activity(&self, service: &DoloresService, post: serde_json::Value) -> Result<(), CovenantError> {
+        let signer = self.identity();
+        let timestamp = self.signing_timestamp()?;
+        let signature = signer.sign(&format!("{}{}", timestamp, signer.uuid()))?;
+
+        let url = format!("{}/user/{}/feed", service.base_url, signer.uuid());
+        let http_response = service.client.post(&url)
+            .json(&serde_json::json!({
+                "timestamp": timestamp.to_string(),
+                "uuid": signer.uuid(),
+                "post": post,
+                "signature": signature
+            }))
+            .send()
+            .await?;
+
+        if !http_response.status().is_success() {
+            return Err(CovenantError::ServiceError(format!("Dolores service returned {}", http_response.status())));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+/*!
+ * Server-side drafts
+ * A draft is an unfinished contract saved with none of the usual build
+ * checks, so a title alone is enough to save one. Drafts are private to the
+ * identity that saved them and never become signable; `finalize_draft` runs
+ * the full builder validation and turns the draft into a real contract.
+ */
+
+use crate::{Authenticated, Contract, ContractBuilder, ContractSummary, CovenantClient, CovenantError, ServiceResponse};
+
+impl CovenantClient<Authenticated> {
+    /// Save an unfinished contract. A builder loaded with `edit_draft`
+    /// updates its draft; any other builder saves a new one.
+    pub async fn save_draft(&self, draft: &ContractBuilder) -> Result<Contract, CovenantError> {
+        let request = match draft.draft_uuid() {
+            Some(uuid) => {
+                let url = format!("{}/drafts/{}", self.base_url, uuid);
+                self.client.put(&url).json(&self.signed_payload(draft.draft_payload(), Some(uuid))?)
+            }
+            None => {
+                let url = format!("{}/drafts", self.base_url);
+                self.client.post(&url).json(&self.signed_payload(draft.draft_payload(), None)?)
+            }
+        };
+
+        let service_response: ServiceResponse<Contract> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Save draft failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No draft data returned".to_string())
+        )
+    }
+
+    pub async fn list_drafts(&self) -> Result<Vec<ContractSummary>, CovenantError> {
+        let url = format!("{}/drafts", self.base_url);
+        let request = self.signed_read(self.client.get(&url), None)?;
+        let service_response: ServiceResponse<Vec<ContractSummary>> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "List drafts failed".to_string())
+            ));
+        }
+
+        Ok(service_response.data.unwrap_or_default())
+    }
+
+    pub async fn get_draft(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        let url = format!("{}/drafts/{}", self.base_url, uuid);
+        let request = self.signed_read(self.client.get(&url), Some(uuid))?;
+        let service_response: ServiceResponse<Contract> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Draft not found".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No draft data returned".to_string())
+        )
+    }
+
+    /// Load a draft to keep working on; pass the builder back to `save_draft`
+    pub async fn edit_draft(&self, uuid: &str) -> Result<ContractBuilder, CovenantError> {
+        let draft = self.get_draft(uuid).await?;
+        Ok(ContractBuilder::from_contract(&draft).with_draft_uuid(draft.uuid))
+    }
+
+    /// Validate a draft as a full contract and create it. The draft is
+    /// removed once the contract exists.
+    pub async fn finalize_draft(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        let builder = self.edit_draft(uuid).await?;
+        if let Some(registry) = &self.spell_registry {
+            builder.validate_spells(registry)?;
+        }
+        let contract = builder.build()?;
+
+        let url = format!("{}/drafts/{}/finalize", self.base_url, uuid);
+        let payload = self.signed_payload(contract, Some(uuid))?;
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Finalize draft failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+}
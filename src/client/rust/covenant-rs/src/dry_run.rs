@@ -0,0 +1,189 @@
+/*!
+ * Dry-run mode for mutating calls
+ * Performs local validation, canonicalization, and signing, then asks the
+ * service to evaluate the request with `?dry_run=true` (or skips sending).
+ * Payloads come from the same builders the real calls use. Offline, nothing
+ * is fetched either, so step signing takes the contract from the caller.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{canonical, Authenticated, Contract, ContractBuilder, CovenantClient, CovenantError, ServiceResponse};
+
+/// A MAGIC spell that would fire as a result of the operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TriggeredSpell {
+    #[serde(rename = "stepId")]
+    pub step_id: String,
+    pub spell: serde_json::Value,
+}
+
+/// What a mutating call would have sent and done
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DryRunReport {
+    pub method: String,
+    pub url: String,
+    /// Fully signed request body
+    pub payload: serde_json::Value,
+    /// Canonical serialization of `payload`
+    #[serde(rename = "canonicalPayload")]
+    pub canonical_payload: String,
+    #[serde(rename = "triggeredSpells")]
+    pub triggered_spells: Vec<TriggeredSpell>,
+    /// Server evaluation, when the request was sent
+    #[serde(rename = "serverResponse")]
+    pub server_response: Option<serde_json::Value>,
+}
+
+/// Wrapper whose mutating calls only report what would happen
+pub struct DryRun<'a> {
+    client: &'a CovenantClient<Authenticated>,
+    send: bool,
+}
+
+impl CovenantClient<Authenticated> {
+    /// Dry-run wrapper that sends `?dry_run=true` requests
+    pub fn dry_run(&self) -> DryRun<'_> {
+        DryRun { client: self, send: true }
+    }
+}
+
+impl DryRun<'_> {
+    /// Skip contacting the service entirely
+    pub fn offline(mut self) -> Self {
+        self.send = false;
+        self
+    }
+
+    /// Validate and sign a contract creation
+    pub async fn create_contract(&self, contract: &ContractBuilder) -> Result<DryRunReport, CovenantError> {
+        let url = format!("{}/contract", self.client.base_url);
+        let (payload, _) = self.client.contract_payload(contract)?;
+        let payload = self.client.signed_payload(payload, None)?;
+
+        let server_response = self.send_dry_run(reqwest::Method::POST, &url, &payload).await?;
+        Ok(report("POST", url, payload, Vec::new(), server_response))
+    }
+
+    /// Validate and sign a step signature, reporting spells that would
+    /// trigger. Offline dry runs can't fetch the contract; use
+    /// `sign_step_with_contract` there.
+    pub async fn sign_step(&self, contract_uuid: &str, step_id: &str) -> Result<DryRunReport, CovenantError> {
+        if !self.send {
+            return Err(CovenantError::ValidationError("Offline dry runs need the contract; use sign_step_with_contract".to_string()));
+        }
+
+        let contract = self.client.get_contract(contract_uuid).await?;
+        self.sign_step_with_contract(&contract, step_id).await
+    }
+
+    /// `sign_step` against a contract the caller already has
+    pub async fn sign_step_with_contract(&self, contract: &Contract, step_id: &str) -> Result<DryRunReport, CovenantError> {
+        let contract_uuid = contract.uuid.as_str();
+        let step = contract.steps.iter().find(|step| step.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+        if step.completed {
+            return Err(CovenantError::ValidationError(format!("Step {} is already completed", step_id)));
+        }
+
+        let request = self.client.build_sign_request(contract_uuid, step_id)?;
+        contract.check_signable()?;
+        contract.check_signing_order(step_id, &request.participant_uuid, &request.pub_key)?;
+        let signer = |participant: &str| participant == request.participant_uuid || participant == request.pub_key;
+
+        if !contract.participants.iter().any(|participant| signer(participant)) {
+            return Err(CovenantError::ValidationError("Signer is not a participant of this contract".to_string()));
+        }
+
+        let completes_step = step.awaiting_signers(&contract.participants).iter().all(|awaiting| signer(awaiting));
+
+        let triggered_spells = match (&step.magic_spell, completes_step) {
+            (Some(spell), true) => vec![TriggeredSpell { step_id: step.id.clone(), spell: spell.clone() }],
+            _ => Vec::new(),
+        };
+
+        let url = self.client.contract_route(contract_uuid, "sign");
+        let payload = serde_json::to_value(&request)?;
+
+        let server_response = self.send_dry_run(reqwest::Method::PUT, &url, &payload).await?;
+        Ok(report("PUT", url, payload, triggered_spells, server_response))
+    }
+
+    /// Validate and sign a contract update
+    pub async fn update_contract(&self, uuid: &str, updates: serde_json::Value) -> Result<DryRunReport, CovenantError> {
+        validate_updates(&updates)?;
+
+        let (uuid, url) = self.client.contract_url(uuid, "").await?;
+        let payload = self.client.signed_payload(updates, Some(&uuid))?;
+
+        let server_response = self.send_dry_run(reqwest::Method::PUT, &url, &payload).await?;
+        Ok(report("PUT", url, payload, Vec::new(), server_response))
+    }
+
+    async fn send_dry_run(&self, method: reqwest::Method, url: &str, payload: &serde_json::Value) -> Result<Option<serde_json::Value>, CovenantError> {
+        if !self.send {
+            return Ok(None);
+        }
+
+        let request = self.client.client
+            .request(method, url)
+            .query(&[("dry_run", "true")])
+            .json(payload);
+
+        let service_response: ServiceResponse<serde_json::Value> = self.client.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Dry run rejected".to_string())
+            ));
+        }
+
+        Ok(service_response.data)
+    }
+}
+
+fn report(method: &str, url: String, payload: serde_json::Value, triggered_spells: Vec<TriggeredSpell>, server_response: Option<serde_json::Value>) -> DryRunReport {
+    DryRunReport {
+        method: method.to_string(),
+        url,
+        canonical_payload: canonical::canonical_json(&payload),
+        payload,
+        triggered_spells,
+        server_response,
+    }
+}
+
+/// Mirror the service's contract validation for the fields an update may touch
+fn validate_updates(updates: &serde_json::Value) -> Result<(), CovenantError> {
+    let fields = updates.as_object()
+        .ok_or_else(|| CovenantError::ValidationError("Updates must be a JSON object".to_string()))?;
+
+    if let Some(title) = fields.get("title") {
+        if title.as_str().unwrap_or("").is_empty() {
+            return Err(CovenantError::ValidationError("Contract must have a title".to_string()));
+        }
+    }
+
+    if let Some(steps) = fields.get("steps") {
+        let steps = steps.as_array()
+            .filter(|steps| !steps.is_empty())
+            .ok_or_else(|| CovenantError::ValidationError("Contract must have at least one step".to_string()))?;
+
+        for (index, step) in steps.iter().enumerate() {
+            if step.get("description").and_then(|d| d.as_str()).unwrap_or("").is_empty() {
+                return Err(CovenantError::ValidationError(format!("Step {} must have a description", index + 1)));
+            }
+        }
+    }
+
+    if let Some(status) = fields.get("status") {
+        if !status.is_string() {
+            return Err(CovenantError::ValidationError("Status must be a string".to_string()));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+/*!
+ * Shared encodings
+ * Lowercase hex for keys, signatures, digests and sealed blobs, and the QR
+ * code rendering behind certificates and remote signing challenges.
+ */
+
+use crate::CovenantError;
+
+/// Lowercase hex, two digits per byte
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode hex of either case; fails on odd lengths and non-hex characters
+#[cfg(any(feature = "ed25519", feature = "vault"))]
+pub(crate) fn from_hex(text: &str) -> Result<Vec<u8>, CovenantError> {
+    let invalid = || CovenantError::EncodingError("Invalid hex string".to_string());
+    if !text.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+
+    text.as_bytes().chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16).ok_or_else(invalid)?;
+            let low = (pair[1] as char).to_digit(16).ok_or_else(invalid)?;
+            Ok((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+/// QR code for `payload`: its width in modules and whether each module is
+/// dark, row by row
+#[cfg(any(feature = "certificate", feature = "remote_sign"))]
+pub(crate) fn qr_modules(payload: &str) -> Result<(usize, Vec<bool>), CovenantError> {
+    let code = qrcode::QrCode::new(payload.as_bytes())
+        .map_err(|e| CovenantError::EncodingError(e.to_string()))?;
+    let modules = code.to_colors().into_iter().map(|c| c == qrcode::Color::Dark).collect();
+    Ok((code.width(), modules))
+}
+
+/// SVG `<rect>` per dark module, `module_size` units square, offset by
+/// `margin` modules on each axis
+#[cfg(any(feature = "certificate", feature = "remote_sign"))]
+pub(crate) fn qr_svg_rects(width: usize, modules: &[bool], module_size: f64, margin: usize) -> String {
+    let round = |value: f64| (value * 100.0).round() / 100.0;
+    let size = round(module_size);
+
+    let mut rects = String::new();
+    for (index, _) in modules.iter().enumerate().filter(|(_, dark)| **dark) {
+        rects.push_str(&format!(
+            r##"<rect x="{}" y="{}" width="{}" height="{}"/>"##,
+            round((index % width + margin) as f64 * module_size),
+            round((index / width + margin) as f64 * module_size),
+            size,
+            size
+        ));
+    }
+    rects
+}
@@ -0,0 +1,304 @@
+/*!
+ * Escrow contracts
+ * The deposit → delivery → release/refund pattern as a ready-made builder.
+ *
+ * 1. Deposit: the buyer pays the amount into escrow, then the seller acknowledges
+ * 2. Delivery: the seller marks the goods delivered, then the buyer confirms
+ * 3. Release: the buyer releases the escrowed amount to the seller
+ * 4. Refund: the seller agrees to return the amount to the buyer
+ *
+ * Release and refund are alternatives; whichever completes first settles the
+ * escrow, and a later completion of the other step doesn't change that. Each step's role is recorded under the `"escrowRole"` step
+ * metadata key so `EscrowState` can read a contract back.
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::summary::format_amount;
+use crate::{Contract, ContractBuilder, ContractStep, CovenantError, SigningOrder, StepBuilder};
+
+/// Step metadata key naming the step's part in the escrow
+pub const ESCROW_ROLE_KEY: &str = "escrowRole";
+
+/// Contract metadata key holding the escrow terms
+pub const ESCROW_TERMS_KEY: &str = "escrow";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum EscrowRole {
+    Deposit,
+    Delivery,
+    Release,
+    Refund,
+}
+
+/// Builder for a two-party escrow contract
+#[derive(Debug, Clone)]
+pub struct EscrowBuilder {
+    buyer: String,
+    seller: String,
+    /// In the currency's minor unit (e.g. cents)
+    amount: i64,
+    currency: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    escrow_agent: String,
+    deposit_deadline: Option<DateTime<Utc>>,
+    delivery_deadline: Option<DateTime<Utc>>,
+    release_deadline: Option<DateTime<Utc>>,
+}
+
+impl EscrowBuilder {
+    /// Escrow of `amount` (minor units) from `buyer` to `seller`
+    pub fn new<B: Into<String>, S: Into<String>>(buyer: B, seller: S, amount: i64) -> Self {
+        Self {
+            buyer: buyer.into(),
+            seller: seller.into(),
+            amount,
+            currency: None,
+            title: None,
+            description: None,
+            escrow_agent: "escrow".to_string(),
+            deposit_deadline: None,
+            delivery_deadline: None,
+            release_deadline: None,
+        }
+    }
+
+    pub fn currency<S: Into<String>>(mut self, currency: S) -> Self {
+        self.currency = Some(currency.into());
+        self
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Who holds the deposit (defaults to `"escrow"`)
+    pub fn escrow_agent<S: Into<String>>(mut self, escrow_agent: S) -> Self {
+        self.escrow_agent = escrow_agent.into();
+        self
+    }
+
+    pub fn deposit_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deposit_deadline = Some(deadline);
+        self
+    }
+
+    pub fn delivery_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.delivery_deadline = Some(deadline);
+        self
+    }
+
+    /// Deadline for both settlement steps (release and refund)
+    pub fn release_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.release_deadline = Some(deadline);
+        self
+    }
+
+    fn spell(&self, spell: &str, payee: &str) -> serde_json::Value {
+        serde_json::json!({
+            "spell": spell,
+            "amount": self.amount,
+            "currency": self.currency,
+            "payee": payee
+        })
+    }
+
+    /// The contract builder for this escrow, for further customization.
+    /// Unlike `build`, this doesn't check the escrow terms.
+    pub fn builder(&self) -> ContractBuilder {
+        let role = |role: EscrowRole| serde_json::to_value(role).unwrap_or_default();
+        let deadline = |step: StepBuilder, deadline: Option<DateTime<Utc>>| match deadline {
+            Some(deadline) => step.deadline(deadline),
+            None => step,
+        };
+        let amount = format_amount(self.amount, self.currency.as_deref());
+        let buyer_first = SigningOrder::Custom(vec![self.buyer.clone(), self.seller.clone()]);
+        let seller_first = SigningOrder::Custom(vec![self.seller.clone(), self.buyer.clone()]);
+
+        ContractBuilder::new()
+            .title(self.title.clone().unwrap_or_else(|| "Escrow".to_string()))
+            .description(self.description.clone().unwrap_or_default())
+            .participants([self.buyer.clone(), self.seller.clone()])
+            .metadata(ESCROW_TERMS_KEY, serde_json::json!({
+                "buyer": self.buyer,
+                "seller": self.seller,
+                "amount": self.amount,
+                "currency": self.currency,
+                "escrowAgent": self.escrow_agent
+            }))
+            .step_with(format!("Buyer deposits {} into escrow", amount), |step| {
+                deadline(step, self.deposit_deadline)
+                    .magic_spell(self.spell("escrowDeposit", &self.escrow_agent))
+                    .signing_order(buyer_first.clone())
+                    .metadata(ESCROW_ROLE_KEY, role(EscrowRole::Deposit))
+            })
+            .step_with("Seller delivers", |step| {
+                deadline(step, self.delivery_deadline)
+                    .signing_order(seller_first.clone())
+                    .metadata(ESCROW_ROLE_KEY, role(EscrowRole::Delivery))
+            })
+            .step_with(format!("Buyer releases {} to the seller", amount), |step| {
+                deadline(step, self.release_deadline)
+                    .magic_spell(self.spell("escrowRelease", &self.seller))
+                    .signing_order(buyer_first)
+                    .metadata(ESCROW_ROLE_KEY, role(EscrowRole::Release))
+            })
+            .step_with(format!("Seller refunds {} to the buyer", amount), |step| {
+                deadline(step, self.release_deadline)
+                    .magic_spell(self.spell("escrowRefund", &self.buyer))
+                    .signing_order(seller_first)
+                    .metadata(ESCROW_ROLE_KEY, role(EscrowRole::Refund))
+            })
+    }
+
+    /// Validate the terms and build the contract payload
+    pub fn build(&self) -> Result<serde_json::Value, CovenantError> {
+        if self.amount <= 0 {
+            return Err(CovenantError::ValidationError("Escrow amount must be positive".to_string()));
+        }
+        if self.buyer == self.seller {
+            return Err(CovenantError::ValidationError("Escrow buyer and seller must differ".to_string()));
+        }
+
+        self.builder().build()
+    }
+}
+
+impl From<EscrowBuilder> for ContractBuilder {
+    fn from(escrow: EscrowBuilder) -> Self {
+        escrow.builder()
+    }
+}
+
+/// Where an escrow contract stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum EscrowState {
+    AwaitingDeposit,
+    /// Deposit made, not yet delivered
+    Funded,
+    /// Delivered, awaiting release or refund
+    Delivered,
+    Released,
+    Refunded,
+    /// The contract has no escrow steps
+    NotEscrow,
+}
+
+impl EscrowState {
+    /// Whether the escrow has been settled one way or the other
+    pub fn is_settled(&self) -> bool {
+        matches!(self, EscrowState::Released | EscrowState::Refunded)
+    }
+}
+
+impl Contract {
+    /// The step playing `role` in an escrow, if any
+    pub fn escrow_step(&self, role: EscrowRole) -> Option<&ContractStep> {
+        let role = serde_json::to_value(role).ok()?;
+        self.steps.iter().find(|step| step.metadata.get(ESCROW_ROLE_KEY) == Some(&role))
+    }
+}
+
+impl From<&Contract> for EscrowState {
+    /// When both settlement steps are complete, the refund only counts if it
+    /// completed strictly before the release
+    fn from(contract: &Contract) -> Self {
+        let completed = |role: EscrowRole| contract.escrow_step(role).map(|step| step.completed);
+        let completed_at = |role: EscrowRole| contract.escrow_step(role).and_then(|step| step.completed_time());
+
+        match (
+            completed(EscrowRole::Deposit),
+            completed(EscrowRole::Delivery),
+            completed(EscrowRole::Release),
+            completed(EscrowRole::Refund),
+        ) {
+            (None, _, _, _) => EscrowState::NotEscrow,
+            (_, _, Some(true), Some(true)) => match (completed_at(EscrowRole::Release), completed_at(EscrowRole::Refund)) {
+                (Some(release), Some(refund)) if refund < release => EscrowState::Refunded,
+                _ => EscrowState::Released,
+            },
+            (_, _, Some(true), _) => EscrowState::Released,
+            (_, _, _, Some(true)) => EscrowState::Refunded,
+            (Some(false), _, _, _) => EscrowState::AwaitingDeposit,
+            (Some(true), Some(true), _, _) => EscrowState::Delivered,
+            (Some(true), _, _, _) => EscrowState::Funded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The fixture with a step per escrow role; `completed` lists the roles
+    /// done, with their completion times
+    fn escrow(completed: &[(EscrowRole, &str)]) -> Contract {
+        let mut contract = Contract::fixture();
+        let template = contract.steps[0].clone();
+        contract.steps = [EscrowRole::Deposit, EscrowRole::Delivery, EscrowRole::Release, EscrowRole::Refund]
+            .into_iter()
+            .enumerate()
+            .map(|(index, role)| {
+                let mut step = template.clone();
+                step.id = format!("step-{}", index + 1);
+                step.order = index;
+                step.metadata.insert(ESCROW_ROLE_KEY.to_string(), serde_json::to_value(role).unwrap());
+                let done = completed.iter().find(|(r, _)| *r == role);
+                step.completed = done.is_some();
+                step.completed_at = done.map(|(_, time)| time.to_string());
+                step
+            })
+            .collect();
+        contract
+    }
+
+    #[test]
+    fn state_follows_the_completed_steps() {
+        assert_eq!(EscrowState::from(&Contract::fixture()), EscrowState::NotEscrow);
+        assert_eq!(EscrowState::from(&escrow(&[])), EscrowState::AwaitingDeposit);
+
+        let deposit = (EscrowRole::Deposit, "2026-01-01T00:00:00Z");
+        let delivery = (EscrowRole::Delivery, "2026-01-02T00:00:00Z");
+        assert_eq!(EscrowState::from(&escrow(&[deposit])), EscrowState::Funded);
+        assert_eq!(EscrowState::from(&escrow(&[deposit, delivery])), EscrowState::Delivered);
+        assert_eq!(
+            EscrowState::from(&escrow(&[deposit, delivery, (EscrowRole::Release, "2026-01-03T00:00:00Z")])),
+            EscrowState::Released
+        );
+        assert_eq!(
+            EscrowState::from(&escrow(&[deposit, (EscrowRole::Refund, "2026-01-03T00:00:00Z")])),
+            EscrowState::Refunded
+        );
+    }
+
+    #[test]
+    fn first_settlement_wins() {
+        let release = (EscrowRole::Release, "2026-01-03T00:00:00Z");
+        let early_refund = (EscrowRole::Refund, "2026-01-02T23:59:59Z");
+        let late_refund = (EscrowRole::Refund, "2026-01-04T00:00:00Z");
+
+        assert_eq!(EscrowState::from(&escrow(&[release, early_refund])), EscrowState::Refunded);
+        assert_eq!(EscrowState::from(&escrow(&[release, late_refund])), EscrowState::Released);
+        assert_eq!(EscrowState::from(&escrow(&[release, (EscrowRole::Refund, "unknown")])), EscrowState::Released);
+        assert!(EscrowState::from(&escrow(&[release, early_refund])).is_settled());
+    }
+
+    #[test]
+    fn terms_are_checked() {
+        assert!(EscrowBuilder::new("alice", "bob", 0).build().is_err());
+        assert!(EscrowBuilder::new("alice", "alice", 100).build().is_err());
+        assert!(EscrowBuilder::new("alice", "bob", 100).currency("USD").build().is_ok());
+    }
+}
@@ -0,0 +1,272 @@
+/*!
+ * Event callbacks
+ * Async handlers run for matching watcher events, so apps don't each need a
+ * dispatch loop. Handlers see events from every `WatcherRegistry` built on
+ * the client or a clone of it, for as long as something is subscribed.
+ *
+ * Each handler gets its own bounded queue and worker task, so a slow handler
+ * only delays itself. A handler that panics is counted and skipped; the
+ * watcher and other handlers carry on.
+ */
+
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use tokio::sync::mpsc;
+
+use crate::{Contract, ContractEvent, ContractStep, CovenantClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Updated,
+    StepSigned,
+    StepCompleted,
+    ContractCompleted,
+    PollFailed,
+}
+
+impl ContractEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            ContractEvent::Updated(_) => EventKind::Updated,
+            ContractEvent::StepSigned { .. } => EventKind::StepSigned,
+            ContractEvent::StepCompleted { .. } => EventKind::StepCompleted,
+            ContractEvent::ContractCompleted { .. } => EventKind::ContractCompleted,
+            ContractEvent::PollFailed { .. } => EventKind::PollFailed,
+        }
+    }
+}
+
+/// Which events a handler runs for; every condition set must hold
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    kinds: Vec<EventKind>,
+    contracts: Vec<String>,
+    awaiting: Option<String>,
+}
+
+impl EventFilter {
+    /// Every event
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to an event kind; may be given more than once
+    pub fn kind(mut self, kind: EventKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Restrict to a contract; may be given more than once
+    pub fn contract<S: Into<String>>(mut self, contract_uuid: S) -> Self {
+        self.contracts.push(contract_uuid.into());
+        self
+    }
+
+    /// Only events on steps `identity` still has to sign, or contracts with
+    /// such a step, excluding its own signatures: e.g.
+    /// `.kind(EventKind::StepSigned).awaiting(me)` fires when someone else
+    /// signs a step I haven't. Completions pass for steps `identity` signs.
+    pub fn awaiting<S: Into<String>>(mut self, identity: S) -> Self {
+        self.awaiting = Some(identity.into());
+        self
+    }
+
+    /// Whether the event passes, given the contract state it was observed on
+    pub fn matches(&self, event: &ContractEvent, contract: Option<&Contract>) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&event.kind()) {
+            return false;
+        }
+
+        if !self.contracts.is_empty() && !self.contracts.iter().any(|uuid| uuid == event.contract_uuid()) {
+            return false;
+        }
+
+        let Some(identity) = &self.awaiting else { return true };
+        let contract = match event {
+            ContractEvent::Updated(contract) => Some(contract.as_ref()),
+            _ => contract,
+        };
+        let Some(contract) = contract else { return false };
+        let awaits = |step: &ContractStep| step.awaiting_signers(&contract.participants).iter().any(|signer| signer == identity);
+        let step = |step_id: &str| contract.steps.iter().find(|step| step.id == step_id);
+
+        match event {
+            ContractEvent::StepSigned { step_id, signer, .. } => signer != identity && step(step_id).is_some_and(awaits),
+            ContractEvent::StepCompleted { step_id, .. } => step(step_id).is_some_and(|step| {
+                contract.participants.contains(identity) || step.signatures.contains_key(identity)
+            }),
+            _ => contract.steps.iter().any(awaits),
+        }
+    }
+}
+
+/// What to do when a handler's queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backpressure {
+    /// Drop the new event and count it in `EventHandle::dropped`
+    #[default]
+    DropNewest,
+    /// Hold the watcher until the handler catches up. Slows every subscriber
+    /// of that contract, so only for handlers that must see every event.
+    Wait,
+}
+
+#[derive(Debug, Clone)]
+pub struct HandlerOptions {
+    /// Events buffered before backpressure applies
+    pub capacity: usize,
+    pub backpressure: Backpressure,
+}
+
+impl Default for HandlerOptions {
+    fn default() -> Self {
+        Self { capacity: 64, backpressure: Backpressure::default() }
+    }
+}
+
+impl HandlerOptions {
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct HandlerStats {
+    handled: AtomicU64,
+    dropped: AtomicU64,
+    panicked: AtomicU64,
+}
+
+struct Registered {
+    id: u64,
+    filter: EventFilter,
+    backpressure: Backpressure,
+    sender: mpsc::Sender<ContractEvent>,
+    stats: Arc<HandlerStats>,
+}
+
+/// Handlers shared by a client and its clones
+#[derive(Default)]
+pub(crate) struct EventHandlers {
+    next_id: AtomicU64,
+    handlers: Mutex<Vec<Registered>>,
+}
+
+impl EventHandlers {
+    /// Queue the event for every matching handler
+    pub(crate) async fn dispatch(&self, event: &ContractEvent, contract: Option<&Contract>) {
+        let targets: Vec<_> = self.handlers.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|handler| handler.filter.matches(event, contract))
+            .map(|handler| (handler.sender.clone(), handler.backpressure, handler.stats.clone()))
+            .collect();
+
+        for (sender, backpressure, stats) in targets {
+            match backpressure {
+                Backpressure::DropNewest => {
+                    if let Err(mpsc::error::TrySendError::Full(_)) = sender.try_send(event.clone()) {
+                        stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                Backpressure::Wait => {
+                    let _ = sender.send(event.clone()).await;
+                }
+            }
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        self.handlers.lock().unwrap_or_else(|e| e.into_inner()).retain(|handler| handler.id != id);
+    }
+}
+
+/// A registered handler. It stays registered until `unregister`, even if
+/// the handle is dropped.
+pub struct EventHandle {
+    id: u64,
+    handlers: Arc<EventHandlers>,
+    stats: Arc<HandlerStats>,
+}
+
+impl EventHandle {
+    /// Events the handler has finished, including ones it panicked on
+    pub fn handled(&self) -> u64 {
+        self.stats.handled.load(Ordering::Relaxed)
+    }
+
+    /// Events dropped because the handler's queue was full
+    pub fn dropped(&self) -> u64 {
+        self.stats.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Events the handler panicked on
+    pub fn panicked(&self) -> u64 {
+        self.stats.panicked.load(Ordering::Relaxed)
+    }
+
+    /// Stop delivering events; already queued events are still handled
+    pub fn unregister(self) {
+        self.handlers.remove(self.id);
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Run `handler` for every watcher event matching `filter`.
+    /// Must be called from within a Tokio runtime.
+    pub fn on_event<F, Fut>(&self, filter: EventFilter, handler: F) -> EventHandle
+    where
+        F: Fn(ContractEvent) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.on_event_with(filter, HandlerOptions::default(), handler)
+    }
+
+    /// `on_event` with a specific queue size and backpressure policy
+    pub fn on_event_with<F, Fut>(&self, filter: EventFilter, options: HandlerOptions, handler: F) -> EventHandle
+    where
+        F: Fn(ContractEvent) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let callback: Arc<dyn Fn(ContractEvent) -> BoxFuture<'static, ()> + Send + Sync> =
+            Arc::new(move |event| handler(event).boxed());
+        let (sender, mut receiver) = mpsc::channel(options.capacity.max(1));
+        let stats = Arc::new(HandlerStats::default());
+
+        let worker_stats = stats.clone();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                let callback = callback.clone();
+                let outcome = AssertUnwindSafe(async move { callback(event).await }).catch_unwind().await;
+                if outcome.is_err() {
+                    worker_stats.panicked.fetch_add(1, Ordering::Relaxed);
+                }
+                worker_stats.handled.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        let id = self.events.next_id.fetch_add(1, Ordering::Relaxed);
+        self.events.handlers.lock().unwrap_or_else(|e| e.into_inner()).push(Registered {
+            id,
+            filter,
+            backpressure: options.backpressure,
+            sender,
+            stats: stats.clone(),
+        });
+
+        EventHandle { id, handlers: self.events.clone(), stats }
+    }
+
+    pub(crate) async fn dispatch_event(&self, event: &ContractEvent, contract: Option<&Contract>) {
+        self.events.dispatch(event, contract).await;
+    }
+}
@@ -0,0 +1,292 @@
+/*!
+ * Fault injection (requires the `fault_injection` feature)
+ * A transport layer that makes the service misbehave on purpose, so
+ * applications can exercise their retry and rollback paths without a proxy:
+ *
+ * - latency spikes before a request is sent
+ * - dropped responses: the request reaches the service but the reply is lost
+ * - bursts of `500 Internal Server Error` that never reach the service
+ * - truncated JSON bodies
+ *
+ * Faults are drawn from a seeded generator, so a failing run can be replayed
+ * with the same seed.
+ *
+ * ```ignore
+ * let faults = FaultConfig::new().seed(7).server_error_bursts(0.05, 3).partial_json(0.02).layer();
+ * let client = client.with_fault_injection(&faults);
+ * // ... run the scenario ...
+ * assert!(faults.stats().server_errors() > 0);
+ * ```
+ */
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tower::{BoxError, Layer, Service};
+
+use crate::transport::CovenantRequest;
+use crate::{CovenantClient, CovenantError};
+
+/// Seed used when none is given
+const DEFAULT_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Which faults to inject and how often; probabilities are per request
+#[derive(Debug, Clone, Default)]
+pub struct FaultConfig {
+    seed: Option<u64>,
+    latency: Option<(f64, Duration)>,
+    drop_response: f64,
+    server_error: Option<(f64, u32)>,
+    partial_json: f64,
+}
+
+impl FaultConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Delay a request by `delay` before sending it
+    pub fn latency(mut self, probability: f64, delay: Duration) -> Self {
+        self.latency = Some((clamp(probability), delay));
+        self
+    }
+
+    /// Send the request but fail as if the connection dropped before the reply
+    pub fn drop_responses(mut self, probability: f64) -> Self {
+        self.drop_response = clamp(probability);
+        self
+    }
+
+    /// Start a run of `burst` consecutive 500 responses
+    pub fn server_error_bursts(mut self, probability: f64, burst: u32) -> Self {
+        self.server_error = Some((clamp(probability), burst.max(1)));
+        self
+    }
+
+    /// Cut the response body off halfway
+    pub fn partial_json(mut self, probability: f64) -> Self {
+        self.partial_json = clamp(probability);
+        self
+    }
+
+    pub fn layer(self) -> FaultLayer {
+        FaultLayer {
+            state: Arc::new(Mutex::new(FaultState {
+                rng: self.seed.unwrap_or(DEFAULT_SEED).max(1),
+                burst_remaining: 0,
+            })),
+            stats: Arc::new(FaultStats::default()),
+            config: Arc::new(self),
+        }
+    }
+}
+
+fn clamp(probability: f64) -> f64 {
+    probability.clamp(0.0, 1.0)
+}
+
+/// How many faults of each kind have been injected
+#[derive(Debug, Default)]
+pub struct FaultStats {
+    latency: AtomicU64,
+    dropped: AtomicU64,
+    server_errors: AtomicU64,
+    partial: AtomicU64,
+}
+
+impl FaultStats {
+    pub fn latency(&self) -> u64 {
+        self.latency.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn server_errors(&self) -> u64 {
+        self.server_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn partial(&self) -> u64 {
+        self.partial.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.latency() + self.dropped() + self.server_errors() + self.partial()
+    }
+}
+
+#[derive(Debug)]
+struct FaultState {
+    /// xorshift64* state; never zero
+    rng: u64,
+    burst_remaining: u32,
+}
+
+impl FaultState {
+    fn roll(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false;
+        }
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let sample = (self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64;
+        sample < probability
+    }
+}
+
+/// The faults chosen for one request
+#[derive(Debug, Default)]
+struct Plan {
+    delay: Option<Duration>,
+    server_error: bool,
+    drop_response: bool,
+    partial_json: bool,
+}
+
+/// Wraps a transport in a `FaultService`; clones share the generator and stats
+#[derive(Debug, Clone)]
+pub struct FaultLayer {
+    config: Arc<FaultConfig>,
+    state: Arc<Mutex<FaultState>>,
+    stats: Arc<FaultStats>,
+}
+
+impl FaultLayer {
+    pub fn stats(&self) -> Arc<FaultStats> {
+        self.stats.clone()
+    }
+}
+
+impl<S> Layer<S> for FaultLayer {
+    type Service = FaultService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FaultService { inner, layer: self.clone() }
+    }
+}
+
+/// A transport that injects faults into the requests passing through it
+#[derive(Debug, Clone)]
+pub struct FaultService<S> {
+    inner: S,
+    layer: FaultLayer,
+}
+
+impl<S> FaultService<S> {
+    fn plan(&self) -> Plan {
+        let config = &self.layer.config;
+        let mut state = self.layer.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut plan = Plan::default();
+
+        if let Some((probability, delay)) = config.latency {
+            if state.roll(probability) {
+                plan.delay = Some(delay);
+            }
+        }
+
+        if state.burst_remaining == 0 {
+            if let Some((probability, burst)) = config.server_error {
+                if state.roll(probability) {
+                    state.burst_remaining = burst;
+                }
+            }
+        }
+        if state.burst_remaining > 0 {
+            state.burst_remaining -= 1;
+            plan.server_error = true;
+            return plan;
+        }
+
+        plan.drop_response = state.roll(config.drop_response);
+        plan.partial_json = !plan.drop_response && state.roll(config.partial_json);
+        plan
+    }
+}
+
+impl<S> Service<CovenantRequest> for FaultService<S>
+where
+    S: Service<CovenantRequest, Response = reqwest::Response>,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    type Response = reqwest::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<reqwest::Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: CovenantRequest) -> Self::Future {
+        let plan = self.plan();
+        let stats = self.layer.stats.clone();
+        let forwarded = (!plan.server_error).then(|| self.inner.call(request));
+
+        Box::pin(async move {
+            if let Some(delay) = plan.delay {
+                stats.latency.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(delay).await;
+            }
+
+            let Some(forwarded) = forwarded else {
+                stats.server_errors.fetch_add(1, Ordering::Relaxed);
+                return Ok(server_error());
+            };
+            let response = forwarded.await.map_err(Into::into)?;
+
+            if plan.drop_response {
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+                drop(response);
+                return Err(CovenantError::TransportError("Injected fault: response dropped".to_string()).into());
+            }
+            if plan.partial_json {
+                stats.partial.fetch_add(1, Ordering::Relaxed);
+                return truncate(response).await;
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn server_error() -> reqwest::Response {
+    let body = serde_json::json!({ "success": false, "error": "Injected fault: internal server error" });
+    let response = http::Response::builder()
+        .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .unwrap_or_default();
+    reqwest::Response::from(response)
+}
+
+/// The same response with only the first half of its body
+async fn truncate(response: reqwest::Response) -> Result<reqwest::Response, BoxError> {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in headers.iter().filter(|(name, _)| *name != http::header::CONTENT_LENGTH) {
+        builder = builder.header(name, value);
+    }
+    Ok(reqwest::Response::from(builder.body(body.slice(..body.len() / 2))?))
+}
+
+impl<A> CovenantClient<A> {
+    /// Send every request through a `FaultLayer` over the plain HTTP
+    /// transport. Replaces any service installed with `with_service`.
+    pub fn with_fault_injection(self, faults: &FaultLayer) -> Self {
+        let service = faults.layer(self.http_service());
+        self.with_service(service)
+    }
+}
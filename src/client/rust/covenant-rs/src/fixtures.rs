@@ -0,0 +1,209 @@
+/*!
+ * Test fixtures
+ * Realistic contracts for downstream unit tests, without hand-written JSON.
+ * `Contract::fixture()` and `ContractStep::fixture()` are fixed values;
+ * `ContractFixture` varies the shape (participants, steps, how many steps
+ * are complete) and derives every uuid and timestamp from a seed, so the
+ * same parameters always give the same contract.
+ *
+ * Fixture signatures have the right shape and signing message but are not
+ * valid signatures; don't feed fixtures to `verify`. With the `proptest`
+ * feature, `Contract` and `ContractStep` implement `Arbitrary`.
+ */
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use crate::{canonical, Contract, ContractBuilder, ContractStep, StepSignature};
+
+/// Creation time of the seed-0 fixture, 2024-01-01T00:00:00Z
+pub const FIXTURE_EPOCH_MILLIS: i64 = 1_704_067_200_000;
+
+/// Parameterized fixture contract
+#[derive(Debug, Clone)]
+pub struct ContractFixture {
+    seed: u64,
+    title: String,
+    participants: usize,
+    steps: usize,
+    completed: usize,
+    tags: Vec<String>,
+}
+
+impl Default for ContractFixture {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            title: "Fixture contract".to_string(),
+            participants: 2,
+            steps: 3,
+            completed: 0,
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl ContractFixture {
+    /// Two participants, three open steps
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Vary uuids and timestamps; contracts with different seeds don't collide
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn participants(mut self, count: usize) -> Self {
+        self.participants = count;
+        self
+    }
+
+    pub fn steps(mut self, count: usize) -> Self {
+        self.steps = count;
+        self
+    }
+
+    /// Sign off the first `count` steps by every participant
+    pub fn completed(mut self, count: usize) -> Self {
+        self.completed = count;
+        self
+    }
+
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Uuid of the nth participant (from 0) of a fixture with this seed
+    pub fn participant_uuid(&self, index: usize) -> String {
+        self.uuid(&format!("participant-{}", index))
+    }
+
+    pub fn build(&self) -> Contract {
+        let participants: Vec<String> = (0..self.participants).map(|index| self.participant_uuid(index)).collect();
+        let builder = (0..self.steps).fold(
+            ContractBuilder::new()
+                .title(self.title.clone())
+                .description(format!("Fixture contract {}", self.seed))
+                .participants(participants.iter().cloned())
+                .tags(self.tags.iter().cloned()),
+            |builder, index| builder.step(format!("Fixture step {}", index + 1)),
+        );
+
+        let mut contract = builder.draft();
+        let created = self.created_at();
+        contract.uuid = self.uuid("contract");
+        contract.created_at = timestamp(created);
+        contract.updated_at = contract.created_at.clone();
+
+        let completed = self.completed.min(self.steps);
+        for (index, step) in contract.steps.iter_mut().enumerate() {
+            step.created_at = contract.created_at.clone();
+            step.signatures = participants.iter().map(|participant| (participant.clone(), None)).collect();
+            if index >= completed {
+                continue;
+            }
+
+            let signed_at = created + Duration::hours(index as i64 + 1);
+            for participant in &participants {
+                let signature = StepSignature {
+                    signature: format!("{}{}", self.uuid_simple(&format!("{}/{}/r", step.id, participant)), self.uuid_simple(&format!("{}/{}/s", step.id, participant))),
+                    timestamp: signed_at.timestamp_millis(),
+                    message: canonical::step_message(signed_at.timestamp_millis(), participant, &contract.uuid, &step.id),
+                    pub_key: None,
+                    scheme: None,
+                };
+                step.signatures.insert(participant.clone(), Some(signature));
+            }
+            step.completed = true;
+            step.completed_at = Some(timestamp(signed_at));
+            contract.updated_at = timestamp(signed_at);
+        }
+
+        contract.revision = Some((completed * participants.len()) as u64 + 1);
+        contract.status = if completed == self.steps && self.steps > 0 { "completed" } else { "active" }.to_string();
+        contract
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        let base = DateTime::from_timestamp_millis(FIXTURE_EPOCH_MILLIS).unwrap_or_default();
+        base + Duration::days((self.seed % 3650) as i64)
+    }
+
+    fn uuid(&self, name: &str) -> String {
+        Uuid::new_v5(&Uuid::NAMESPACE_OID, format!("covenant-fixture/{}/{}", self.seed, name).as_bytes()).to_string()
+    }
+
+    fn uuid_simple(&self, name: &str) -> String {
+        self.uuid(name).replace('-', "")
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+impl Contract {
+    /// Two participants, three open steps
+    pub fn fixture() -> Self {
+        ContractFixture::new().build()
+    }
+}
+
+impl ContractStep {
+    /// The first step of `Contract::fixture()`, unsigned
+    pub fn fixture() -> Self {
+        Contract::fixture().steps.remove(0)
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod arbitrary {
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    use super::ContractFixture;
+    use crate::{Contract, ContractStep};
+
+    impl Arbitrary for ContractFixture {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        /// 2-5 participants, 1-7 steps, any number of them complete
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (any::<u64>(), 2..6usize, 1..8usize)
+                .prop_flat_map(|(seed, participants, steps)| (Just(seed), Just(participants), Just(steps), 0..=steps))
+                .prop_map(|(seed, participants, steps, completed)| {
+                    ContractFixture::new().seed(seed).participants(participants).steps(steps).completed(completed)
+                })
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for Contract {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            any::<ContractFixture>().prop_map(|fixture| fixture.build()).boxed()
+        }
+    }
+
+    impl Arbitrary for ContractStep {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (any::<Contract>(), any::<Index>())
+                .prop_map(|(contract, index)| contract.steps[index.index(contract.steps.len())].clone())
+                .boxed()
+        }
+    }
+}
@@ -0,0 +1,111 @@
+/*!
+ * Wire serialization formats
+ * JSON is always available; CBOR and MessagePack sit behind the `cbor` and
+ * `msgpack` features for bandwidth-constrained links.
+ */
+
+use serde::de::DeserializeOwned;
+
+use crate::CovenantError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    #[cfg(feature = "cbor")]
+    Cbor,
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl WireFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            WireFormat::Json => "application/json",
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => "application/cbor",
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Short name used in capability discovery
+    pub fn name(&self) -> &'static str {
+        match self {
+            WireFormat::Json => "json",
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => "cbor",
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => "msgpack",
+        }
+    }
+
+    /// Formats compiled into this build, most compact first
+    #[allow(clippy::vec_init_then_push)]
+    pub fn available() -> Vec<WireFormat> {
+        let mut formats = Vec::new();
+        #[cfg(feature = "msgpack")]
+        formats.push(WireFormat::MessagePack);
+        #[cfg(feature = "cbor")]
+        formats.push(WireFormat::Cbor);
+        formats.push(WireFormat::Json);
+        formats
+    }
+
+    /// Look up a format by short name or content type
+    pub fn from_name(name: &str) -> Option<WireFormat> {
+        let name = name.split(';').next().unwrap_or("").trim();
+        WireFormat::available().into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name) || format.content_type().eq_ignore_ascii_case(name))
+    }
+
+    /// Format of a response, falling back to JSON for unknown content types
+    #[cfg(feature = "client")]
+    pub(crate) fn from_content_type(content_type: Option<&str>) -> WireFormat {
+        content_type.and_then(WireFormat::from_name).unwrap_or_default()
+    }
+
+    pub fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, CovenantError> {
+        match self {
+            WireFormat::Json => Ok(serde_json::to_vec(value)?),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(value, &mut buffer)
+                    .map_err(|e| CovenantError::EncodingError(e.to_string()))?;
+                Ok(buffer)
+            }
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => rmp_serde::to_vec_named(value)
+                .map_err(|e| CovenantError::EncodingError(e.to_string())),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CovenantError> {
+        match self {
+            WireFormat::Json => Ok(serde_json::from_slice(bytes)?),
+            #[cfg(feature = "cbor")]
+            WireFormat::Cbor => ciborium::from_reader(bytes)
+                .map_err(|e| CovenantError::EncodingError(e.to_string())),
+            #[cfg(feature = "msgpack")]
+            WireFormat::MessagePack => rmp_serde::from_slice(bytes)
+                .map_err(|e| CovenantError::EncodingError(e.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+/// Decode a response body according to its content type
+pub(crate) async fn decode_response<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, CovenantError> {
+    let format = response_format(&response);
+    let body = response.bytes().await?;
+    format.decode(&body)
+}
+
+#[cfg(feature = "client")]
+pub(crate) fn response_format(response: &reqwest::Response) -> WireFormat {
+    let content_type = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    WireFormat::from_content_type(content_type)
+}
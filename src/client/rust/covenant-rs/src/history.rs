@@ -0,0 +1,265 @@
+/*!
+ * Contract history (time travel)
+ * Reconstructs a contract as it stood at a past moment, from the service's
+ * history endpoint or by replaying its audit log, for disputes and reports.
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, ContractStep, CovenantError, StepSignature};
+#[cfg(feature = "client")]
+use crate::{format, CovenantClient, ServiceResponse};
+
+/// One entry in a contract's audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AuditEvent {
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    /// Milliseconds since the epoch
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub kind: AuditEventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AuditEventKind {
+    /// The contract as first created
+    Created { contract: Box<Contract> },
+    /// Top-level fields replaced by an update
+    Updated { changes: serde_json::Value },
+    StepSigned {
+        #[serde(rename = "stepId")]
+        step_id: String,
+        signer: String,
+        signature: StepSignature,
+    },
+    StepCompleted {
+        #[serde(rename = "stepId")]
+        step_id: String,
+    },
+    /// A signature withdrawn during the revocation window
+    SignatureRevoked {
+        #[serde(rename = "stepId")]
+        step_id: String,
+        signer: String,
+    },
+    ChecklistItemToggled {
+        #[serde(rename = "stepId")]
+        step_id: String,
+        #[serde(rename = "itemId")]
+        item_id: String,
+        completed: bool,
+        by: String,
+    },
+    Deleted,
+}
+
+impl AuditEvent {
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_millis(self.timestamp)
+    }
+}
+
+impl Contract {
+    /// Rebuild a contract from its audit log. Events are applied in
+    /// timestamp order and must start with `Created`.
+    pub fn replay_events(events: &[AuditEvent]) -> Result<Contract, CovenantError> {
+        let mut ordered: Vec<&AuditEvent> = events.iter().collect();
+        ordered.sort_by_key(|event| event.timestamp);
+
+        let mut ordered = ordered.into_iter();
+        let mut contract = match ordered.next().map(|event| &event.kind) {
+            Some(AuditEventKind::Created { contract }) => (**contract).clone(),
+            _ => return Err(CovenantError::ValidationError("Audit log must start with a Created event".to_string())),
+        };
+
+        for event in ordered {
+            contract.apply_event(event)?;
+        }
+
+        Ok(contract)
+    }
+
+    /// Apply one audit event on top of this state
+    pub fn apply_event(&mut self, event: &AuditEvent) -> Result<(), CovenantError> {
+        match &event.kind {
+            AuditEventKind::Created { contract } => *self = (**contract).clone(),
+            AuditEventKind::Updated { changes } => {
+                let mut state = serde_json::to_value(&*self)?;
+                if let (Some(fields), Some(changes)) = (state.as_object_mut(), changes.as_object()) {
+                    for (key, value) in changes {
+                        fields.insert(key.clone(), value.clone());
+                    }
+                }
+                *self = serde_json::from_value(state)?;
+            }
+            AuditEventKind::StepSigned { step_id, signer, signature } => {
+                self.step_mut(step_id)?.signatures.insert(signer.clone(), Some(signature.clone()));
+            }
+            AuditEventKind::SignatureRevoked { step_id, signer } => {
+                if let Some(signature) = self.step_mut(step_id)?.signatures.get_mut(signer) {
+                    *signature = None;
+                }
+            }
+            AuditEventKind::StepCompleted { step_id } => {
+                let step = self.step_mut(step_id)?;
+                step.completed = true;
+                step.completed_at = Some(event.timestamp.to_string());
+            }
+            AuditEventKind::ChecklistItemToggled { step_id, item_id, completed, by } => {
+                let item = self.step_mut(step_id)?.checklist.iter_mut().find(|item| &item.id == item_id)
+                    .ok_or_else(|| CovenantError::ValidationError(format!("Audit log references unknown checklist item {}", item_id)))?;
+                item.completed = *completed;
+                item.completed_by = completed.then(|| by.clone());
+                item.completed_at = completed.then_some(event.timestamp);
+            }
+            AuditEventKind::Deleted => self.status = "deleted".to_string(),
+        }
+
+        self.updated_at = event.timestamp.to_string();
+        Ok(())
+    }
+
+    fn step_mut(&mut self, step_id: &str) -> Result<&mut ContractStep, CovenantError> {
+        self.steps.iter_mut().find(|step| step.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Audit log references unknown step {}", step_id)))
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Get the contract's audit log
+    pub async fn get_audit_log(&self, uuid: &str) -> Result<Vec<AuditEvent>, CovenantError> {
+        let (uuid, url) = self.contract_url(uuid, "audit").await?;
+        let request = self.signed_read(self.client.get(&url), Some(&uuid))?;
+        let service_response: ServiceResponse<Vec<AuditEvent>> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Get audit log failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No audit log data returned".to_string())
+        )
+    }
+
+    /// Contract as it existed at `at`, from server history or the audit log
+    pub async fn get_contract_at(&self, uuid: &str, at: DateTime<Utc>) -> Result<Contract, CovenantError> {
+        let (uuid, url) = &self.contract_url(uuid, "history").await?;
+        let request = self.signed_read(self.client.get(url).query(&[("at", at.timestamp_millis().to_string())]), Some(uuid))?;
+        let response = self.send(request).await?;
+
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            let service_response: ServiceResponse<Contract> = format::decode_response(response).await?;
+
+            if !service_response.success {
+                return Err(CovenantError::ServiceError(
+                    service_response.error.unwrap_or_else(|| "Get contract history failed".to_string())
+                ));
+            }
+
+            return service_response.data.ok_or_else(||
+                CovenantError::ServiceError("No contract data returned".to_string())
+            );
+        }
+
+        let cutoff = at.timestamp_millis();
+        let events: Vec<AuditEvent> = self.get_audit_log(uuid).await?
+            .into_iter()
+            .filter(|event| event.timestamp <= cutoff)
+            .collect();
+
+        if events.is_empty() {
+            return Err(CovenantError::ValidationError(format!("Contract {} did not exist at {}", uuid, at)));
+        }
+
+        Contract::replay_events(&events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::FIXTURE_EPOCH_MILLIS;
+    use crate::ContractFixture;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn event(hours: i64, kind: AuditEventKind) -> AuditEvent {
+        AuditEvent { contract_uuid: Contract::fixture().uuid, timestamp: FIXTURE_EPOCH_MILLIS + hours * HOUR, kind }
+    }
+
+    /// The fixture's first step signed by everyone, as audit events after creation
+    fn log() -> Vec<AuditEvent> {
+        let signed = ContractFixture::new().completed(1).build();
+        let mut events = vec![event(0, AuditEventKind::Created { contract: Box::new(Contract::fixture()) })];
+        for (hour, participant) in signed.participants.iter().enumerate() {
+            events.push(event(hour as i64 + 1, AuditEventKind::StepSigned {
+                step_id: "step-1".to_string(),
+                signer: participant.clone(),
+                signature: signed.steps[0].signatures[participant].clone().unwrap(),
+            }));
+        }
+        events.push(event(3, AuditEventKind::StepCompleted { step_id: "step-1".to_string() }));
+        events
+    }
+
+    #[test]
+    fn replay_applies_events_in_timestamp_order() {
+        let mut events = log();
+        events.reverse();
+
+        let contract = Contract::replay_events(&events).unwrap();
+        assert!(contract.steps[0].completed);
+        assert!(contract.steps[0].signatures.values().all(Option::is_some));
+        assert_eq!(contract.steps[0].completed_time().unwrap().timestamp_millis(), FIXTURE_EPOCH_MILLIS + 3 * HOUR);
+        assert_eq!(contract.updated_at, (FIXTURE_EPOCH_MILLIS + 3 * HOUR).to_string());
+        assert!(!contract.steps[1].completed);
+    }
+
+    #[test]
+    fn replay_stops_where_the_log_does() {
+        let events = log();
+        let contract = Contract::replay_events(&events[..2]).unwrap();
+        assert_eq!(contract.steps[0].signatures.values().filter(|s| s.is_some()).count(), 1);
+        assert!(!contract.steps[0].completed);
+    }
+
+    #[test]
+    fn updates_revocations_and_deletion() {
+        let mut events = log();
+        let signer = Contract::fixture().participants[0].clone();
+        events.push(event(4, AuditEventKind::Updated { changes: serde_json::json!({ "title": "Renamed" }) }));
+        events.push(event(5, AuditEventKind::SignatureRevoked { step_id: "step-1".to_string(), signer: signer.clone() }));
+        events.push(event(6, AuditEventKind::Deleted));
+
+        let contract = Contract::replay_events(&events).unwrap();
+        assert_eq!(contract.title, "Renamed");
+        assert!(contract.steps[0].signatures[&signer].is_none());
+        assert_eq!(contract.status, "deleted");
+    }
+
+    #[test]
+    fn logs_must_start_with_creation() {
+        assert!(Contract::replay_events(&[]).is_err());
+        assert!(Contract::replay_events(&log()[1..]).is_err());
+
+        let mut events = log();
+        events.push(event(4, AuditEventKind::StepCompleted { step_id: "step-9".to_string() }));
+        assert!(matches!(Contract::replay_events(&events), Err(CovenantError::ValidationError(_))));
+    }
+
+    #[test]
+    fn events_are_tagged_by_type() {
+        let json = serde_json::json!({ "contractUuid": "c", "timestamp": 1, "type": "stepCompleted", "stepId": "step-1" });
+        let event: AuditEvent = serde_json::from_value(json.clone()).unwrap();
+        assert!(matches!(&event.kind, AuditEventKind::StepCompleted { step_id } if step_id == "step-1"));
+        assert_eq!(serde_json::to_value(&event).unwrap(), json);
+    }
+}
@@ -0,0 +1,246 @@
+/*!
+ * Contract import from structured Markdown outlines
+ *
+ * ```text
+ * # Contract title
+ *
+ * Free-form description paragraphs.
+ *
+ * ## Participants
+ * - 02a1b2...
+ * - 03b2c3...
+ *
+ * ## Steps
+ * 1. Deliver the lesson
+ * 2. Release payment [spell: purchaseLesson]
+ * 3. Confirm receipt `{"spell": "confirm", "amount": 500}`
+ *
+ * ## Tags
+ * - fy2025
+ * ```
+ *
+ * A step's spell is either `[spell: name]`, shorthand for `{"spell": "name"}`,
+ * or an inline JSON object in backticks. Lists may use `-`, `*` or `+`
+ * bullets, numbers and `[ ]`/`[x]` checkboxes. Anything before the title
+ * (front matter, a table of contents) is ignored.
+ */
+
+use crate::{ContractBuilder, CovenantError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Description,
+    Participants,
+    Steps,
+    Tags,
+    Ignored,
+}
+
+/// Parse a Markdown outline into a contract builder
+pub fn from_markdown(text: &str) -> Result<ContractBuilder, CovenantError> {
+    let mut builder = ContractBuilder::new();
+    let mut title = None;
+    let mut description: Vec<&str> = Vec::new();
+    let mut section = Section::Description;
+
+    for (line_number, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+
+        if let Some(heading) = line.strip_prefix("# ") {
+            if title.is_some() {
+                return Err(CovenantError::ValidationError(
+                    format!("Line {}: only one top-level title is allowed", line_number + 1)
+                ));
+            }
+            title = Some(heading.trim().to_string());
+            section = Section::Description;
+            continue;
+        }
+
+        if title.is_none() {
+            continue;
+        }
+
+        if let Some(heading) = line.strip_prefix("## ") {
+            section = match heading.trim().to_lowercase().as_str() {
+                "participants" | "parties" => Section::Participants,
+                "steps" | "obligations" => Section::Steps,
+                "tags" | "labels" => Section::Tags,
+                "description" => Section::Description,
+                _ => Section::Ignored,
+            };
+            continue;
+        }
+
+        match section {
+            Section::Description => description.push(line),
+            Section::Ignored => {}
+            _ => {
+                let Some(item) = list_item(line) else {
+                    if !line.is_empty() {
+                        return Err(CovenantError::ValidationError(
+                            format!("Line {}: expected a list item", line_number + 1)
+                        ));
+                    }
+                    continue;
+                };
+
+                builder = match section {
+                    Section::Participants => builder.participant(item),
+                    Section::Tags => builder.tag(item),
+                    _ => {
+                        let (text, spell) = parse_step(item)
+                            .map_err(|e| CovenantError::ValidationError(format!("Line {}: {}", line_number + 1, e)))?;
+                        match spell {
+                            Some(spell) => builder.step_with_magic(text, spell),
+                            None => builder.step(text),
+                        }
+                    }
+                };
+            }
+        }
+    }
+
+    let title = title.ok_or_else(|| CovenantError::ValidationError("Outline must start with a `# Title` heading".to_string()))?;
+    builder = builder.title(title);
+
+    let description = description.join("\n").trim().to_string();
+    if !description.is_empty() {
+        builder = builder.description(description);
+    }
+
+    Ok(builder)
+}
+
+/// Strip a bullet, numbered, or checkbox list marker
+fn list_item(line: &str) -> Option<&str> {
+    let item = if let Some(rest) = ["- ", "* ", "+ "].iter().find_map(|bullet| line.strip_prefix(bullet)) {
+        rest
+    } else {
+        let digits = line.find(|c: char| !c.is_ascii_digit())?;
+        if digits == 0 {
+            return None;
+        }
+        line[digits..].strip_prefix(". ").or_else(|| line[digits..].strip_prefix(") "))?
+    };
+
+    let item = ["[ ] ", "[x] ", "[X] "].iter()
+        .find_map(|checkbox| item.strip_prefix(checkbox))
+        .unwrap_or(item)
+        .trim();
+
+    if item.is_empty() { None } else { Some(item) }
+}
+
+/// Split a step line into its description and optional spell annotation
+fn parse_step(item: &str) -> Result<(String, Option<serde_json::Value>), String> {
+    if let Some(start) = item.find("[spell:") {
+        let end = item[start..].find(']')
+            .ok_or_else(|| "unterminated [spell: ...] annotation".to_string())? + start;
+        let name = item[start + "[spell:".len()..end].trim();
+        if name.is_empty() {
+            return Err("spell annotation is missing a name".to_string());
+        }
+
+        let text = format!("{}{}", &item[..start], &item[end + 1..]);
+        return Ok((collapse_whitespace(&text), Some(serde_json::json!({ "spell": name }))));
+    }
+
+    if let Some(start) = item.find("`{") {
+        let end = item[start + 1..].find('`')
+            .ok_or_else(|| "unterminated inline spell".to_string())? + start + 1;
+        let spell: serde_json::Value = serde_json::from_str(&item[start + 1..end])
+            .map_err(|e| format!("invalid spell JSON: {}", e))?;
+
+        let text = format!("{}{}", &item[..start], &item[end + 1..]);
+        return Ok((collapse_whitespace(&text), Some(spell)));
+    }
+
+    Ok((item.to_string(), None))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTLINE: &str = "\
+# Guitar lessons
+
+Ten lessons, paid on completion.
+
+## Participants
+- alice
+- bob
+
+## Steps
+1. Deliver the lesson
+2. Release payment [spell: purchaseLesson]
+3. Confirm receipt `{\"spell\": \"confirm\", \"amount\": 500}`
+
+## Tags
+- fy2025
+";
+
+    #[test]
+    fn parses_every_section() {
+        let contract = from_markdown(OUTLINE).unwrap().draft();
+
+        assert_eq!(contract.title, "Guitar lessons");
+        assert_eq!(contract.description, "Ten lessons, paid on completion.");
+        assert_eq!(contract.participants, ["alice", "bob"]);
+        assert_eq!(contract.tags, ["fy2025"]);
+
+        let steps: Vec<&str> = contract.steps.iter().map(|step| step.description.as_str()).collect();
+        assert_eq!(steps, ["Deliver the lesson", "Release payment", "Confirm receipt"]);
+        assert_eq!(contract.steps[0].magic_spell, None);
+        assert_eq!(contract.steps[1].magic_spell, Some(serde_json::json!({ "spell": "purchaseLesson" })));
+        assert_eq!(contract.steps[2].magic_spell, Some(serde_json::json!({ "spell": "confirm", "amount": 500 })));
+    }
+
+    #[test]
+    fn accepts_every_list_marker() {
+        let outline = "# T\n## Steps\n- one\n* two\n+ three\n4) four\n- [ ] five\n- [x] six\n+ [X] seven\n";
+        let contract = from_markdown(outline).unwrap().draft();
+
+        let steps: Vec<&str> = contract.steps.iter().map(|step| step.description.as_str()).collect();
+        assert_eq!(steps, ["one", "two", "three", "four", "five", "six", "seven"]);
+    }
+
+    #[test]
+    fn ignores_lines_before_the_title() {
+        let outline = "---\nauthor: someone\n---\n- [Contents](#t)\n\n# T\n\nBody\n";
+        let contract = from_markdown(outline).unwrap().draft();
+
+        assert_eq!(contract.title, "T");
+        assert_eq!(contract.description, "Body");
+    }
+
+    #[test]
+    fn ignores_unknown_sections() {
+        let outline = "# T\n## Notes\nAnything goes here\n## Parties\n- alice\n";
+        let contract = from_markdown(outline).unwrap().draft();
+
+        assert_eq!(contract.participants, ["alice"]);
+        assert_eq!(contract.description, "");
+    }
+
+    #[test]
+    fn rejects_malformed_outlines() {
+        assert!(from_markdown("## Steps\n- one\n").is_err());
+        assert!(from_markdown("# One\n# Two\n").is_err());
+        assert!(from_markdown("# T\n## Steps\nnot a list item\n").is_err());
+        assert!(from_markdown("# T\n## Steps\n- pay [spell: ]\n").is_err());
+        assert!(from_markdown("# T\n## Steps\n- pay [spell: purchase\n").is_err());
+        assert!(from_markdown("# T\n## Steps\n- pay `{not json}`\n").is_err());
+    }
+
+    #[test]
+    fn error_reports_line_number() {
+        let error = from_markdown("# T\n## Steps\n- fine\noops\n").unwrap_err();
+        assert!(error.to_string().contains("Line 4"));
+    }
+}
@@ -0,0 +1,126 @@
+/*!
+ * Participant invitation links
+ * One-time joining tokens let a counterparty join a contract without the
+ * inviter knowing their key in advance.
+ */
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "client")]
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use crate::{Authenticated, Contract, CovenantClient, CovenantError, ServiceResponse};
+
+/// Scheme used for app deep links
+pub const DEEP_LINK_SCHEME: &str = "covenant";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum InviteRole {
+    /// Joins as a participant who signs steps
+    Signer,
+    /// Can read the contract but not sign
+    Viewer,
+}
+
+/// A joining token issued by the service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct InviteToken {
+    pub token: String,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    pub role: InviteRole,
+    /// Milliseconds since the epoch
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+}
+
+/// A token formatted for sharing
+#[derive(Debug, Clone)]
+pub struct InviteLink {
+    pub invite: InviteToken,
+    /// `https://…/invite/<token>`
+    pub url: String,
+    /// `covenant://invite/<token>`
+    pub deep_link: String,
+}
+
+impl InviteToken {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_millis(self.expires_at)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at().is_none_or(|expiry| expiry <= now)
+    }
+}
+
+/// Pull the token out of an invite URL or deep link (bare tokens pass through)
+pub fn parse_invite_token(link: &str) -> Option<String> {
+    let link = link.trim();
+    let token = match link.rfind("/invite/") {
+        Some(start) => &link[start + "/invite/".len()..],
+        None if link.contains("://") => return None,
+        None => link,
+    };
+
+    let token = token.split(['?', '#', '/']).next().unwrap_or("");
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Ask the service for a one-time joining token and format it as a shareable link
+    pub async fn create_invite_link(&self, contract_uuid: &str, role: InviteRole, expiry: Duration) -> Result<InviteLink, CovenantError> {
+        let (contract_uuid, url) = self.contract_url(contract_uuid, "invite").await?;
+        let payload = self.signed_payload(serde_json::json!({
+            "role": role,
+            "expiresAt": (self.now() + expiry).timestamp_millis()
+        }), Some(&contract_uuid))?;
+
+        let service_response: ServiceResponse<InviteToken> = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Create invite failed".to_string())
+            ));
+        }
+
+        let invite = service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No invite data returned".to_string())
+        )?;
+
+        Ok(InviteLink {
+            url: format!("{}/invite/{}", self.base_url, invite.token),
+            deep_link: format!("{}://invite/{}", DEEP_LINK_SCHEME, invite.token),
+            invite,
+        })
+    }
+
+    /// Join a contract with an invite token or link
+    pub async fn accept_invite(&self, token: &str) -> Result<Contract, CovenantError> {
+        let token = parse_invite_token(token)
+            .ok_or_else(|| CovenantError::ValidationError("Invite token is missing".to_string()))?;
+
+        let url = format!("{}/invite/{}/accept", self.base_url, token);
+        let payload = self.signed_payload(serde_json::json!({ "token": token }), None)?;
+
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Accept invite failed".to_string())
+            ));
+        }
+
+        let contract = service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )?;
+        self.check_trust(&contract);
+
+        Ok(contract)
+    }
+}
@@ -3,14 +3,223 @@
  * For interacting with magical contract management service
  */
 
+#[cfg(feature = "client")]
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(feature = "client")]
+use std::marker::PhantomData;
+#[cfg(feature = "client")]
+use std::sync::Arc;
+#[cfg(feature = "client")]
 use reqwest::Client;
+#[cfg(feature = "client")]
 use sessionless::Sessionless;
+#[cfg(feature = "client")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+pub mod auth;
+pub mod activity;
+pub mod anonymize;
+pub mod availability;
+pub mod backup;
+pub mod builder;
+pub mod canonical;
+#[cfg(feature = "certificate")]
+pub mod certificate;
+pub mod checklist;
+pub mod clock;
+#[cfg(feature = "client")]
+pub mod consistency;
+#[cfg(feature = "client")]
+pub mod capabilities;
+pub mod delegation;
+pub mod digest;
+#[cfg(feature = "dolores")]
+pub mod dolores;
+#[cfg(feature = "client")]
+pub mod directory;
+#[cfg(feature = "client")]
+pub mod drafts;
+#[cfg(feature = "client")]
+pub mod dry_run;
+#[cfg(any(feature = "ed25519", feature = "vault", feature = "certificate", feature = "remote_sign"))]
+pub(crate) mod encoding;
+pub mod escrow;
+#[cfg(feature = "client")]
+pub mod events;
+#[cfg(feature = "fault_injection")]
+pub mod fault;
+pub mod fixtures;
+pub mod format;
+pub mod history;
+pub mod import;
+pub mod invite;
+pub mod limits;
+pub mod lint;
+#[cfg(feature = "client")]
+pub mod logging;
+pub mod merge;
+pub mod metadata;
+pub mod notes;
+#[cfg(feature = "client")]
+pub mod operation;
+#[cfg(feature = "client")]
+pub mod options;
+pub mod optimistic;
+pub mod ordering;
+pub mod participants;
+#[cfg(feature = "client")]
+pub mod organization;
+#[cfg(feature = "client")]
+pub mod pending;
+pub mod phase;
+pub mod portfolio;
+#[cfg(feature = "pref")]
+pub mod pref;
+pub mod prefetch;
+pub mod preview;
+pub mod projection;
+pub mod promote;
+pub mod proposal;
+#[cfg(feature = "client")]
+pub mod raw;
+pub mod receipt;
+pub mod recurrence;
+pub mod reference;
+#[cfg(feature = "remote_sign")]
+pub mod remote_sign;
+pub mod render;
+pub mod retention;
+pub mod review;
+pub mod revocation;
+pub mod rotation;
+#[cfg(feature = "schemars")]
+pub mod schema;
+pub mod scheme;
+#[cfg(feature = "search")]
+pub mod search;
+#[cfg(feature = "client")]
+pub mod selftest;
+pub mod share;
+pub mod skew;
+pub mod spells;
+pub mod stall;
+pub mod summary;
+#[cfg(feature = "client")]
+pub mod steps;
+pub mod testvectors;
+#[cfg(feature = "tower")]
+pub mod transport;
+pub mod trust;
+#[cfg(feature = "vault")]
+pub mod vault;
+pub mod variables;
+pub mod verify;
+pub mod visibility;
+pub mod watcher;
+
+pub use activity::{ActivityEvent, ActivityKind};
+#[cfg(feature = "client")]
+pub use activity::{ActivityFilter, ActivityTail};
+pub use anonymize::AnonymizeOptions;
+pub use availability::{AvailabilityWindow, SlotSuggestion};
+pub use backup::{BackupArchive, RestoreOutcome, RestoreReport};
+pub use auth::{Anonymous, Authenticated};
+pub use builder::{ContractBuilder, StepBuilder};
+#[cfg(feature = "client")]
+pub use capabilities::Capabilities;
+#[cfg(feature = "certificate")]
+pub use certificate::{Certificate, CertificateParticipant};
+pub use checklist::ChecklistItem;
+pub use clock::{Clock, MockClock, SkewCorrectedClock, SystemClock};
+pub use delegation::{DelegationScope, DelegationToken};
+#[cfg(feature = "client")]
+pub use directory::{ParticipantProfile, ParticipantResolver, ProfileServiceResolver, ResolvedContract};
+#[cfg(feature = "dolores")]
+pub use dolores::DoloresService;
+#[cfg(feature = "client")]
+pub use dry_run::{DryRun, DryRunReport, TriggeredSpell};
+#[cfg(feature = "client")]
+pub use events::{Backpressure, EventFilter, EventHandle, EventKind, HandlerOptions};
+pub use escrow::{EscrowBuilder, EscrowRole, EscrowState};
+#[cfg(feature = "fault_injection")]
+pub use fault::{FaultConfig, FaultLayer, FaultService, FaultStats};
+pub use fixtures::ContractFixture;
+pub use format::WireFormat;
+pub use history::{AuditEvent, AuditEventKind};
+pub use invite::{InviteLink, InviteRole, InviteToken};
+pub use limits::ContractLimits;
+pub use lint::{LintConfig, LintFinding, LintReport, LintRule};
+#[cfg(feature = "client")]
+pub use logging::{LoggedRequest, LoggedResponse, RequestLogger};
+pub use merge::{MergeConflict, MergeResult};
+#[cfg(feature = "client")]
+pub use operation::{BulkDeleteResult, ContractExport, Operation, OperationState, OperationStatus, PdfDocument};
+#[cfg(feature = "client")]
+pub use options::CallOptions;
+pub use optimistic::Rollback;
+pub use ordering::SigningOrder;
+pub use participants::{ParticipantBook, ParticipantEntry};
+#[cfg(feature = "client")]
+pub use organization::{OrgScope, Organization, OrganizationMember};
+#[cfg(feature = "client")]
+pub use pending::{PendingPage, PendingStep};
+pub use phase::{Phase, PhaseBuilder, PhaseProgress};
+pub use portfolio::{BurndownPoint, PortfolioProgress, ProgressRollup};
+#[cfg(feature = "pref")]
+pub use pref::{DigestFrequency, DisplayPreferences, PrefService};
+pub use prefetch::{ContentCache, PrefetchOptions, PrefetchReport};
+pub use preview::{PaymentPreview, PreviewSource, SpellPreview};
+pub use projection::{Field, PartialContract};
+pub use promote::PROMOTED_FROM_KEY;
+pub use proposal::SignatureProposal;
+#[cfg(feature = "client")]
+pub use raw::RawApi;
+pub use receipt::OperationReceipt;
+pub use recurrence::{Frequency, RecurrenceRule};
+pub use reference::ContractRef;
+#[cfg(feature = "remote_sign")]
+pub use remote_sign::{ChallengeState, ChallengeStatus, SigningChallenge};
+pub use render::RenderError;
+pub use retention::{RetentionAction, RetentionMatch, RetentionPolicy, RetentionReport};
+pub use review::{Review, ReviewDecision, ReviewStatus, ReviewVerdict, Reviewer};
+pub use scheme::{SignatureScheme, Signer};
+#[cfg(feature = "ed25519")]
+pub use scheme::Ed25519Signer;
+#[cfg(feature = "search")]
+pub use search::{SearchField, SearchIndex};
+#[cfg(feature = "client")]
+pub use selftest::{CheckOutcome, SelfTestCheck, SelfTestOptions, SelfTestReport};
+pub use share::{ShareLink, ShareScope};
+pub use skew::ClockSkewPolicy;
+#[cfg(feature = "client")]
+use skew::ClockSkew;
+pub use spells::{MagicSpellPayload, SpellRegistry, SpellWarning, SpellWarningKind};
+#[cfg(feature = "derive")]
+pub use covenant_derive::MagicSpellPayload;
+#[cfg(feature = "client")]
+pub use steps::{StepPage, StepPages};
+pub use summary::{BriefFormatter, ContractBrief, PlainText, Spoken};
+pub use rotation::{RotationChain, RotationProof, RotationResult};
+pub use trust::{PinSource, PinnedKey, TrustStore, TrustWarning, TrustWarningKind};
+pub use watcher::ContractEvent;
+#[cfg(feature = "client")]
+pub use watcher::{ContractSubscription, WatcherRegistry};
+pub use visibility::Visibility;
+#[cfg(feature = "client")]
+pub use tokio_util::sync::CancellationToken;
+#[cfg(feature = "tower")]
+pub use transport::{CovenantRequest, HttpService};
+#[cfg(feature = "vault")]
+pub use vault::{Vault, VaultEntry, VaultKey};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Contract {
     pub uuid: String,
+    /// Human-friendly number assigned by the service, e.g. `COV-2025-00042`
+    pub reference: Option<String>,
     pub title: String,
     pub description: String,
     pub participants: Vec<String>,
@@ -19,14 +228,51 @@ pub struct Contract {
     pub product_uuid: Option<String>,
     #[serde(rename = "bdoLocation")]
     pub bdo_location: Option<String>,
+    #[serde(rename = "orgUuid")]
+    pub org_uuid: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "signingOrder", default)]
+    pub signing_order: SigningOrder,
+    #[serde(default)]
+    pub phases: Vec<Phase>,
+    #[serde(default)]
+    pub visibility: Visibility,
+    pub recurrence: Option<RecurrenceRule>,
+    pub review: Option<Review>,
+    /// Values for `{{name}}` placeholders in step descriptions
+    #[serde(default)]
+    pub variables: HashMap<String, serde_json::Value>,
+    #[serde(rename = "seriesId")]
+    pub series_id: Option<String>,
+    /// Position in the series, starting at 1
+    pub occurrence: Option<u32>,
+    /// Seconds after signing that a signature can be revoked; the service
+    /// default when absent
+    #[serde(rename = "revocationWindow")]
+    pub revocation_window: Option<u64>,
+    /// Announce step completions on the participants' activity feeds
+    #[serde(rename = "publishActivity", default)]
+    pub publish_activity: bool,
+    /// Keys granted read access without being participants
+    #[serde(rename = "readGrants", default)]
+    pub read_grants: Vec<String>,
+    /// Windows each participant is free to sign in
+    #[serde(default)]
+    pub availability: HashMap<String, Vec<AvailabilityWindow>>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    /// Incremented by the service on every mutation
+    pub revision: Option<u64>,
     pub status: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ContractStep {
     pub id: String,
     pub description: String,
@@ -39,20 +285,100 @@ pub struct ContractStep {
     pub created_at: String,
     #[serde(rename = "completedAt")]
     pub completed_at: Option<String>,
+    pub deadline: Option<String>,
+    /// Overrides the contract's signing order for this step
+    #[serde(rename = "signingOrder")]
+    pub signing_order: Option<SigningOrder>,
+    /// Name of the phase this step belongs to
+    pub phase: Option<String>,
+    /// Relative weight for weighted progress (1.0 when unset)
+    pub weight: Option<f32>,
+    /// Staged signing intents awaiting confirmation (two-phase signing)
+    #[serde(default)]
+    pub proposals: Vec<SignatureProposal>,
+    /// Operational sub-tasks; toggled without signatures
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+impl ContractStep {
+    /// Deadline as a timestamp, if one is set
+    pub fn deadline_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.deadline.as_deref().and_then(parse_timestamp)
+    }
+
+    /// Weight used for weighted progress
+    pub fn effective_weight(&self) -> f64 {
+        self.weight.map(f64::from).unwrap_or(1.0)
+    }
+
+    /// Completion time as a timestamp, if the step is completed
+    pub fn completed_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.completed_at.as_deref().and_then(parse_timestamp)
+    }
+
+    /// Signers the step is still waiting on: participants who haven't
+    /// signed, whether or not the step lists them, in order, then any other
+    /// signer the step lists who hasn't signed, sorted
+    pub fn awaiting_signers(&self, participants: &[String]) -> Vec<String> {
+        let signed = |signer: &String| self.signatures.get(signer).is_some_and(|s| s.is_some());
+        let mut awaiting: Vec<String> = participants.iter()
+            .filter(|participant| !signed(participant))
+            .cloned()
+            .collect();
+        let mut others: Vec<String> = self.signatures.iter()
+            .filter(|(signer, signature)| signature.is_none() && !participants.contains(signer))
+            .map(|(signer, _)| signer.clone())
+            .collect();
+        others.sort();
+        awaiting.extend(others);
+        awaiting
+    }
+}
+
+/// Parse service timestamps, which are either millisecond strings or RFC 3339
+pub(crate) fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(millis) = value.parse::<i64>() {
+        return chrono::DateTime::from_timestamp_millis(millis);
+    }
+
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct StepSignature {
     pub signature: String,
     pub timestamp: i64,
     pub message: String,
+    #[serde(rename = "pubKey")]
+    pub pub_key: Option<String>,
+    /// Secp256k1 when not recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<SignatureScheme>,
+}
+
+impl StepSignature {
+    pub fn scheme(&self) -> SignatureScheme {
+        self.scheme.unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ContractSummary {
     pub uuid: String,
+    pub reference: Option<String>,
     pub title: String,
     pub participants: Vec<String>,
+    #[serde(rename = "orgUuid")]
+    pub org_uuid: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
     #[serde(rename = "updatedAt")]
@@ -61,24 +387,186 @@ pub struct ContractSummary {
     pub step_count: usize,
     #[serde(rename = "completedSteps")]
     pub completed_steps: usize,
+    #[serde(rename = "seriesId")]
+    pub series_id: Option<String>,
+    pub occurrence: Option<u32>,
+    /// The caller's private note, filled in from the local cache
+    #[serde(skip)]
+    pub private_note: Option<String>,
+}
+
+#[cfg(feature = "client")]
+/// Options for concurrent multi-contract fetches
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+    /// Maximum requests in flight at once
+    pub concurrency: usize,
+    /// Stop at the first failed fetch
+    pub fail_fast: bool,
+}
+
+#[cfg(feature = "client")]
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            fail_fast: false,
+        }
+    }
+}
+
+/// Filters for listing contracts
+#[derive(Debug, Clone, Default)]
+pub struct ContractQuery {
+    pub participant: Option<String>,
+    pub org_uuid: Option<String>,
+    /// Contracts must carry every one of these tags
+    pub tags: Vec<String>,
+    pub series_id: Option<String>,
+}
+
+impl ContractQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn participant<S: Into<String>>(mut self, participant: S) -> Self {
+        self.participant = Some(participant.into());
+        self
+    }
+
+    pub fn org_uuid<S: Into<String>>(mut self, org_uuid: S) -> Self {
+        self.org_uuid = Some(org_uuid.into());
+        self
+    }
+
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags.extend(tags.into_iter().map(|t| t.into()));
+        self
+    }
+
+    #[cfg(feature = "client")]
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(participant) = &self.participant {
+            params.push(("participant", participant.clone()));
+        }
+        if let Some(org_uuid) = &self.org_uuid {
+            params.push(("org", org_uuid.clone()));
+        }
+        if !self.tags.is_empty() {
+            params.push(("tags", self.tags.join(",")));
+        }
+        if let Some(series_id) = &self.series_id {
+            params.push(("series", series_id.clone()));
+        }
+        params
+    }
+
+    #[cfg(feature = "client")]
+    fn matches(&self, summary: &ContractSummary) -> bool {
+        self.tags.iter().all(|tag| summary.tags.contains(tag))
+            && self.series_id.as_ref().is_none_or(|series_id| summary.series_id.as_ref() == Some(series_id))
+            && self.org_uuid.as_ref().is_none_or(|org| summary.org_uuid.as_ref() == Some(org))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ServiceResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Server-signed receipt, when one was requested
+    pub receipt: Option<OperationReceipt>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HealthInfo {
     pub service: String,
     pub version: String,
     pub status: String,
     pub timestamp: String,
+    /// Key the service signs receipts with
+    #[serde(rename = "pubKey")]
+    pub pub_key: Option<String>,
+}
+
+/// Rendering options for contract SVGs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SvgOptions {
+    pub theme: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Retry with the service's suggested theme when `theme` is unknown
+    #[serde(rename = "fallbackTheme", default)]
+    pub fallback_theme: bool,
+}
+
+impl SvgOptions {
+    pub fn theme<S: Into<String>>(mut self, theme: S) -> Self {
+        self.theme = Some(theme.into());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn fallback_theme(mut self, fallback_theme: bool) -> Self {
+        self.fallback_theme = fallback_theme;
+        self
+    }
+
+    fn query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(theme) = &self.theme {
+            params.push(format!("theme={}", theme));
+        }
+        if let Some(width) = self.width {
+            params.push(format!("width={}", width));
+        }
+        if let Some(height) = self.height {
+            params.push(format!("height={}", height));
+        }
+
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+/// Progress of a streaming SVG download
+#[derive(Debug, Clone, Copy)]
+pub struct DownloadProgress {
+    /// Bytes written to the destination so far
+    pub downloaded: u64,
+    /// Total size reported by the server, if any
+    pub content_length: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SignStepRequest {
     #[serde(rename = "userUUID")]
     pub participant_uuid: String,
@@ -90,9 +578,29 @@ pub struct SignStepRequest {
     pub pub_key: String,
     #[serde(rename = "stepSignature")]
     pub step_signature: String,
+    /// Proposal being confirmed, when using two-phase signing
+    #[serde(rename = "proposalId", skip_serializing_if = "Option::is_none")]
+    pub proposal_id: Option<String>,
+    /// Only sent for schemes other than secp256k1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<SignatureScheme>,
+    /// Key that made the signatures, when a delegate signs for `participant_uuid`
+    #[serde(rename = "delegatePubKey", skip_serializing_if = "Option::is_none")]
+    pub delegate_pub_key: Option<String>,
+    /// Token the signer is acting under, when signing for someone else
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegation: Option<DelegationToken>,
+}
+
+impl SignStepRequest {
+    /// Key the signatures verify against
+    pub fn signing_key(&self) -> &str {
+        self.delegate_pub_key.as_deref().unwrap_or(&self.pub_key)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SignStepResponse {
     #[serde(rename = "contractUuid")]
     pub contract_uuid: String,
@@ -102,9 +610,12 @@ pub struct SignStepResponse {
     pub step_completed: bool,
     #[serde(rename = "magicTriggered")]
     pub magic_triggered: bool,
+    /// Contract revision including this signature, for `get_contract_consistent`
+    pub revision: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ContractProgress {
     #[serde(rename = "totalSteps")]
     pub total_steps: usize,
@@ -112,13 +623,19 @@ pub struct ContractProgress {
     pub completed_steps: usize,
     #[serde(rename = "progressPercent")]
     pub progress_percent: f64,
+    /// Progress with each step counted by its weight
+    #[serde(rename = "weightedProgressPercent", default)]
+    pub weighted_progress_percent: f64,
     #[serde(rename = "participantCount")]
     pub participant_count: usize,
     #[serde(rename = "isComplete")]
     pub is_complete: bool,
+    #[serde(default)]
+    pub phases: Vec<PhaseProgress>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UserSignatureStatus {
     #[serde(rename = "stepId")]
     pub step_id: String,
@@ -133,6 +650,7 @@ pub struct UserSignatureStatus {
 
 #[derive(Debug, thiserror::Error)]
 pub enum CovenantError {
+    #[cfg(feature = "client")]
     #[error("HTTP request failed: {0}")]
     RequestError(#[from] reqwest::Error),
     
@@ -147,17 +665,106 @@ pub enum CovenantError {
     
     #[error("Sessionless error: {0}")]
     SessionlessError(String),
+
+    #[error("Out of order signature: {0} must sign next")]
+    OutOfOrderSignature(String),
+
+    #[error("Encoding error: {0}")]
+    EncodingError(String),
+
+    #[error("Request cancelled")]
+    Cancelled,
+
+    #[error("Invalid receipt: {0}")]
+    InvalidReceipt(String),
+
+    #[error("Stale read: wanted revision {0}, replica is at {1}")]
+    StaleRead(u64, u64),
+
+    #[error("Render failed: {0}")]
+    RenderError(#[from] render::RenderError),
+
+    #[error("Transport error: {0}")]
+    TransportError(String),
+
+    #[error("Unsupported signature scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Clock skew of {0} ms exceeds the allowed {1} ms")]
+    ClockSkewExceeded(i64, i64),
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Operation {0} did not finish in time")]
+    OperationTimeout(String),
 }
 
-pub struct CovenantClient {
+#[cfg(feature = "client")]
+/// Covenant service client. `CovenantClient<Anonymous>` exposes the read
+/// endpoints; `with_sessionless` upgrades it to `CovenantClient<Authenticated>`,
+/// which adds the endpoints that need a signature.
+pub struct CovenantClient<A = Anonymous> {
     base_url: String,
     client: Client,
-    sessionless: Option<Sessionless>,
+    signer: Option<Arc<dyn Signer>>,
+    request_logger: Option<RequestLogger>,
+    wire_format: WireFormat,
+    call_options: CallOptions,
+    spell_registry: Option<Arc<SpellRegistry>>,
+    cache: ContentCache,
+    svg_options: SvgOptions,
+    server_pub_key: tokio::sync::OnceCell<String>,
+    skew_policy: ClockSkewPolicy,
+    skew: ClockSkew,
+    clock: Arc<dyn Clock>,
+    trust: Option<TrustStore>,
+    events: Arc<events::EventHandlers>,
+    delegation: Option<Arc<DelegationToken>>,
+    #[cfg(feature = "dolores")]
+    dolores: Option<dolores::DoloresService>,
+    #[cfg(feature = "pref")]
+    pref: Option<pref::PrefLink>,
+    #[cfg(feature = "tower")]
+    service: Option<transport::SharedService>,
+    state: PhantomData<A>,
+}
+
+#[cfg(feature = "client")]
+impl<A> Clone for CovenantClient<A> {
+    fn clone(&self) -> Self {
+        Self {
+            base_url: self.base_url.clone(),
+            client: self.client.clone(),
+            signer: self.signer.clone(),
+            request_logger: self.request_logger.clone(),
+            wire_format: self.wire_format,
+            call_options: self.call_options.clone(),
+            spell_registry: self.spell_registry.clone(),
+            cache: self.cache.clone(),
+            svg_options: self.svg_options.clone(),
+            server_pub_key: self.server_pub_key.clone(),
+            skew_policy: self.skew_policy.clone(),
+            skew: self.skew.clone(),
+            clock: self.clock.clone(),
+            trust: self.trust.clone(),
+            events: self.events.clone(),
+            delegation: self.delegation.clone(),
+            #[cfg(feature = "dolores")]
+            dolores: self.dolores.clone(),
+            #[cfg(feature = "pref")]
+            pref: self.pref.clone(),
+            #[cfg(feature = "tower")]
+            service: self.service.clone(),
+            state: PhantomData,
+        }
+    }
 }
 
-impl CovenantClient {
-    /// Create new CovenantClient
-    pub fn new(base_url: String, sessionless: Option<Sessionless>) -> Result<Self, CovenantError> {
+#[cfg(feature = "client")]
+impl CovenantClient<Anonymous> {
+    /// Create new CovenantClient (read-only until `with_sessionless`)
+    pub fn new(base_url: String) -> Result<Self, CovenantError> {
         let base_url = if base_url.ends_with('/') {
             base_url.trim_end_matches('/').to_string()
         } else {
@@ -169,30 +776,242 @@ impl CovenantClient {
         Ok(CovenantClient {
             base_url,
             client,
-            sessionless,
+            signer: None,
+            request_logger: None,
+            wire_format: WireFormat::Json,
+            call_options: CallOptions::default(),
+            spell_registry: None,
+            cache: ContentCache::default(),
+            svg_options: SvgOptions::default(),
+            server_pub_key: tokio::sync::OnceCell::new(),
+            skew_policy: ClockSkewPolicy::default(),
+            skew: ClockSkew::default(),
+            clock: Arc::new(SystemClock),
+            trust: None,
+            events: Arc::default(),
+            delegation: None,
+            #[cfg(feature = "dolores")]
+            dolores: None,
+            #[cfg(feature = "pref")]
+            pref: None,
+            #[cfg(feature = "tower")]
+            service: None,
+            state: PhantomData,
         })
     }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Attach a sessionless identity, enabling the signing endpoints
+    pub fn with_sessionless(self, sessionless: Sessionless) -> CovenantClient<Authenticated> {
+        self.with_signer(sessionless)
+    }
+
+    /// Attach an identity using any signature scheme
+    pub fn with_signer<S: Signer + 'static>(self, signer: S) -> CovenantClient<Authenticated> {
+        self.with_state(Some(Arc::new(signer)))
+    }
+
+    /// A read-only handle sharing this client's configuration
+    pub fn anonymous(&self) -> CovenantClient<Anonymous> {
+        self.clone().with_state(None)
+    }
+
+    fn with_state<B>(self, signer: Option<Arc<dyn Signer>>) -> CovenantClient<B> {
+        CovenantClient {
+            base_url: self.base_url,
+            client: self.client,
+            signer,
+            request_logger: self.request_logger,
+            wire_format: self.wire_format,
+            call_options: self.call_options,
+            spell_registry: self.spell_registry,
+            cache: self.cache,
+            svg_options: self.svg_options,
+            server_pub_key: self.server_pub_key,
+            skew_policy: self.skew_policy,
+            skew: self.skew,
+            clock: self.clock,
+            trust: self.trust,
+            events: self.events,
+            delegation: self.delegation,
+            #[cfg(feature = "dolores")]
+            dolores: self.dolores,
+            #[cfg(feature = "pref")]
+            pref: self.pref,
+            #[cfg(feature = "tower")]
+            service: self.service,
+            state: PhantomData,
+        }
+    }
+
+    /// Install request/response logging hooks
+    pub fn with_request_logger(mut self, logger: RequestLogger) -> Self {
+        self.request_logger = Some(logger);
+        self
+    }
+
+    /// Use a specific wire format instead of JSON
+    pub fn with_wire_format(mut self, format: WireFormat) -> Self {
+        self.wire_format = format;
+        self
+    }
+
+    /// Validate spells on created and fetched contracts against a registry
+    pub fn with_spell_registry(mut self, registry: SpellRegistry) -> Self {
+        self.spell_registry = Some(Arc::new(registry));
+        self
+    }
+
+    /// Send a request through the logging hooks
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, CovenantError> {
+        self.call_options.cancellable(self.send_inner(request)).await
+    }
+
+    async fn send_inner(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, CovenantError> {
+        let mut request = self.call_options.apply(request).build()?;
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+
+        if let Some(logger) = &self.request_logger {
+            logger.log_request(&method, &url, request.body().and_then(|b| b.as_bytes()));
+        }
+
+        self.encode_body(&mut request)?;
+
+        let started = std::time::Instant::now();
+        let sent_at = self.now();
+        let response = self.execute(request).await?;
+        self.skew.observe(response.headers(), sent_at, self.now());
+
+        if let Some(logger) = &self.request_logger {
+            logger.log_response(&method, &url, response.status().as_u16(), started.elapsed(), None);
+        }
+
+        Ok(response)
+    }
+
+    /// Send a request and decode its response, logging the buffered body
+    async fn send_json<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T, CovenantError> {
+        self.call_options.cancellable(self.send_json_inner(request)).await
+    }
+
+    async fn send_json_inner<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T, CovenantError> {
+        let mut request = self.call_options.apply(request).build()?;
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+
+        if let Some(logger) = &self.request_logger {
+            logger.log_request(&method, &url, request.body().and_then(|b| b.as_bytes()));
+        }
+
+        self.encode_body(&mut request)?;
+
+        let started = std::time::Instant::now();
+        let sent_at = self.now();
+        let response = self.execute(request).await?;
+        self.skew.observe(response.headers(), sent_at, self.now());
+        let status = response.status().as_u16();
+        let format = format::response_format(&response);
+        let body: serde_json::Value = format.decode(&response.bytes().await?)?;
+
+        if let Some(logger) = &self.request_logger {
+            logger.log_response(&method, &url, status, started.elapsed(), Some(&body));
+        }
+
+        Ok(serde_json::from_value(body)?)
+    }
+
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, CovenantError> {
+        #[cfg(feature = "tower")]
+        if let Some(service) = &self.service {
+            return service.call(request).await;
+        }
+
+        Ok(self.client.execute(request).await?)
+    }
+
+    /// Re-encode a JSON request body in the configured wire format
+    fn encode_body(&self, request: &mut reqwest::Request) -> Result<(), CovenantError> {
+        if self.wire_format == WireFormat::Json {
+            return Ok(());
+        }
+
+        let accept = reqwest::header::HeaderValue::from_static(self.wire_format.content_type());
+        if !request.headers().contains_key(reqwest::header::ACCEPT) {
+            request.headers_mut().insert(reqwest::header::ACCEPT, accept.clone());
+        }
+
+        let json_body = request.body().and_then(|b| b.as_bytes()).map(|b| b.to_vec());
+        if let Some(json_body) = json_body {
+            let value: serde_json::Value = serde_json::from_slice(&json_body)?;
+            *request.body_mut() = Some(self.wire_format.encode(&value)?.into());
+            request.headers_mut().insert(reqwest::header::CONTENT_TYPE, accept);
+        }
+
+        Ok(())
+    }
 
     /// Health check
     pub async fn health_check(&self) -> Result<HealthInfo, CovenantError> {
         let url = format!("{}/health", self.base_url);
-        let response = self.client.get(&url).send().await?;
-        let health_info: HealthInfo = response.json().await?;
+        let health_info: HealthInfo = self.send_json(self.client.get(&url)).await?;
         Ok(health_info)
     }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// The identity this client signs with
+    pub fn identity(&self) -> &dyn Signer {
+        self.signer.as_deref()
+            .expect("authenticated client always has an identity")
+    }
 
     /// Create new magical contract
     pub async fn create_contract(&self, contract: &ContractBuilder) -> Result<Contract, CovenantError> {
+        let (payload, content_uuid) = self.contract_payload(contract)?;
+
+        if let Some(uuid) = content_uuid {
+            if let Some(existing) = self.find_contract(&uuid).await? {
+                return Ok(existing);
+            }
+        }
+
+        self.submit_contract(payload).await
+    }
+
+    /// Unsigned creation payload: spells checked against the client's
+    /// registry, and the uuid set for content-addressed contracts (returned
+    /// as well, so callers can look for an existing copy)
+    pub(crate) fn contract_payload(&self, contract: &ContractBuilder) -> Result<(serde_json::Value, Option<String>), CovenantError> {
+        if let Some(registry) = &self.spell_registry {
+            contract.validate_spells(registry)?;
+        }
+        let mut payload = contract.build()?;
+
+        if !contract.is_content_addressed() {
+            return Ok((payload, None));
+        }
+
+        let uuid = canonical::contract_uuid(&payload, self.identity().public_key());
+        if let Some(fields) = payload.as_object_mut() {
+            fields.insert("uuid".to_string(), serde_json::Value::String(uuid.clone()));
+        }
+        Ok((payload, Some(uuid)))
+    }
+
+    /// Sign and POST a built contract payload
+    async fn submit_contract(&self, payload: serde_json::Value) -> Result<Contract, CovenantError> {
         let url = format!("{}/contract", self.base_url);
-        let payload = contract.build()?;
+        let payload = self.signed_payload(payload, None)?;
         
-        let response = self.client
+        let request = self.client
             .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
+            .json(&payload);
 
-        let service_response: ServiceResponse<Contract> = response.json().await?;
+        let service_response: ServiceResponse<Contract> = self.send_json(request).await?;
         
         if !service_response.success {
             return Err(CovenantError::ServiceError(
@@ -205,35 +1024,104 @@ impl CovenantClient {
         )
     }
 
+    /// The contract with this UUID, or `None` if the service has no such contract
+    async fn find_contract(&self, uuid: &str) -> Result<Option<Contract>, CovenantError> {
+        let response = self.send(self.signed_read(self.client.get(self.contract_route(uuid, "")), Some(uuid))?).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let service_response: ServiceResponse<Contract> = format::decode_response(response).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Contract lookup failed".to_string())
+            ));
+        }
+
+        Ok(service_response.data)
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
     /// Get contract by UUID
     pub async fn get_contract(&self, uuid: &str) -> Result<Contract, CovenantError> {
-        let url = format!("{}/contract/{}", self.base_url, uuid);
-        let response = self.client.get(&url).send().await?;
+        if ContractRef::is_reference(uuid) {
+            return self.get_contract_by_ref(uuid).await;
+        }
 
-        let service_response: ServiceResponse<Contract> = response.json().await?;
-        
+        let request = self.signed_read(self.client.get(self.contract_route(uuid, "")), Some(uuid))?;
+        self.fetch_contract(request).await
+    }
+
+    /// Send a contract read and check the contract that comes back
+    async fn fetch_contract(&self, request: reqwest::RequestBuilder) -> Result<Contract, CovenantError> {
+        let service_response: ServiceResponse<Contract> = self.send_json(request).await?;
+        self.accept_contract(service_response, None)
+    }
+
+    /// Check a fetched contract and remember its reference; `revision` fills
+    /// in a revision the body leaves out
+    fn accept_contract(&self, service_response: ServiceResponse<Contract>, revision: Option<u64>) -> Result<Contract, CovenantError> {
         if !service_response.success {
             return Err(CovenantError::ServiceError(
                 service_response.error.unwrap_or_else(|| "Contract not found".to_string())
             ));
         }
 
-        service_response.data.ok_or_else(|| 
+        let mut contract = service_response.data.ok_or_else(|| 
             CovenantError::ServiceError("No contract data returned".to_string())
-        )
+        )?;
+        contract.revision = contract.revision.or(revision);
+
+        if let Some(registry) = &self.spell_registry {
+            registry.validate_contract(&contract)?;
+        }
+        self.check_trust(&contract);
+        self.cache.put_ref(&contract)?;
+
+        Ok(contract)
+    }
+
+    /// Get many contracts concurrently; results are in input order
+    pub async fn get_contracts<S: AsRef<str>>(&self, uuids: &[S]) -> Vec<Result<Contract, CovenantError>> {
+        self.get_contracts_with(uuids, BatchOptions::default()).await
     }
 
+    /// Get many contracts with bounded parallelism. With `fail_fast`, fetching
+    /// stops at the first failure and the returned vector ends with that error.
+    pub async fn get_contracts_with<S: AsRef<str>>(&self, uuids: &[S], options: BatchOptions) -> Vec<Result<Contract, CovenantError>> {
+        let mut fetches = futures::stream::iter(uuids)
+            .map(|uuid| self.get_contract(uuid.as_ref()))
+            .buffered(options.concurrency.max(1));
+
+        let mut results = Vec::with_capacity(uuids.len());
+        while let Some(result) = fetches.next().await {
+            let failed = result.is_err();
+            results.push(result);
+            if failed && options.fail_fast {
+                break;
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
     /// Update contract
     pub async fn update_contract(&self, uuid: &str, updates: serde_json::Value) -> Result<Contract, CovenantError> {
-        let url = format!("{}/contract/{}", self.base_url, uuid);
+        let (uuid, url) = self.contract_url(uuid, "").await?;
+        let updates = self.signed_payload(updates, Some(&uuid))?;
         
-        let response = self.client
+        let request = self.client
             .put(&url)
-            .json(&updates)
-            .send()
-            .await?;
+            .json(&updates);
 
-        let service_response: ServiceResponse<Contract> = response.json().await?;
+        let service_response: ServiceResponse<Contract> = self.send_json(request).await?;
         
         if !service_response.success {
             return Err(CovenantError::ServiceError(
@@ -248,36 +1136,58 @@ impl CovenantClient {
 
     /// Sign a contract step
     pub async fn sign_step(&self, contract_uuid: &str, step_id: &str, message: Option<&str>) -> Result<SignStepResponse, CovenantError> {
-        let sessionless = self.sessionless.as_ref()
-            .ok_or_else(|| CovenantError::SessionlessError("Sessionless instance required for signing".to_string()))?;
+        let contract_uuid = &self.resolve_uuid(contract_uuid).await?;
+        let payload = self.build_sign_request(contract_uuid, step_id)?;
 
-        let timestamp = chrono::Utc::now().timestamp_millis();
+        let contract = self.get_contract(contract_uuid).await?;
+        contract.check_signable()?;
+        contract.check_signing_order(step_id, &payload.participant_uuid, &payload.pub_key)?;
+
+        self.submit_signature(&contract, &payload, false).await
+            .map(|(response, _)| response)
+    }
+
+    /// Produce the dual-signed payload for signing a step
+    fn build_sign_request(&self, contract_uuid: &str, step_id: &str) -> Result<SignStepRequest, CovenantError> {
+        self.check_delegation(delegation::DelegatedAction::Sign { contract_uuid, step_id })?;
+        let signer = self.identity();
+        let (participant_uuid, pub_key) = self.acting_as();
+        let timestamp = self.signing_timestamp()?;
         
-        let main_message = format!("{}{}{}", timestamp, sessionless.uuid, contract_uuid);
-        let main_signature = sessionless.sign(&main_message)
-            .map_err(|e| CovenantError::SessionlessError(e.to_string()))?;
+        let main_message = canonical::auth_message(timestamp, participant_uuid, Some(contract_uuid));
+        let main_signature = signer.sign(&main_message)?;
         
-        let step_message = format!("{}{}{}{}", timestamp, sessionless.uuid, contract_uuid, step_id);
-        let step_signature = sessionless.sign(&step_message)
-            .map_err(|e| CovenantError::SessionlessError(e.to_string()))?;
+        let step_message = canonical::step_message(timestamp, participant_uuid, contract_uuid, step_id);
+        let step_signature = signer.sign(&step_message)?;
 
         let payload = SignStepRequest {
-            participant_uuid: sessionless.uuid.clone(),
+            participant_uuid: participant_uuid.to_string(),
             step_id: step_id.to_string(),
             signature: main_signature,
             timestamp,
-            pub_key: sessionless.public_key.clone(),
+            pub_key: pub_key.to_string(),
             step_signature,
+            proposal_id: None,
+            scheme: Some(signer.scheme()).filter(|scheme| *scheme != SignatureScheme::default()),
+            delegate_pub_key: self.delegation.as_ref().map(|_| signer.public_key().to_string()),
+            delegation: self.delegation.as_deref().cloned(),
         };
 
-        let url = format!("{}/contract/{}/sign", self.base_url, contract_uuid);
-        let response = self.client
+        Ok(payload)
+    }
+
+    /// Sign a step of `contract`, as fetched for the signing checks
+    async fn submit_signature(&self, contract: &Contract, payload: &SignStepRequest, request_receipt: bool) -> Result<(SignStepResponse, Option<OperationReceipt>), CovenantError> {
+        let contract_uuid = &contract.uuid;
+        let url = self.contract_route(contract_uuid, "sign");
+        let mut request = self.client
             .put(&url)
-            .json(&payload)
-            .send()
-            .await?;
+            .json(payload);
+        if request_receipt {
+            request = request.header(receipt::RECEIPT_HEADER, "true");
+        }
 
-        let service_response: ServiceResponse<SignStepResponse> = response.json().await?;
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(request).await?;
         
         if !service_response.success {
             return Err(CovenantError::ServiceError(
@@ -285,21 +1195,80 @@ impl CovenantClient {
             ));
         }
 
-        service_response.data.ok_or_else(|| 
+        let data = service_response.data.ok_or_else(|| 
             CovenantError::ServiceError("No sign response data returned".to_string())
-        )
+        )?;
+
+        let receipt = match (request_receipt, service_response.receipt) {
+            (true, Some(receipt)) => {
+                self.verify_receipt(&receipt, crate::receipt::SIGN_STEP, contract_uuid, &data).await?;
+                Some(receipt)
+            }
+            (true, None) => return Err(CovenantError::InvalidReceipt("Service did not return a receipt".to_string())),
+            (false, _) => None,
+        };
+
+        let response: SignStepResponse = serde_json::from_value(data)?;
+        #[cfg(feature = "dolores")]
+        self.publish_step_completion(contract, &response);
+
+        Ok((response, receipt))
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Attach sessionless auth fields (signature, timestamp, userUUID, pubKey)
+    /// to a JSON payload when the client has an identity
+    fn signed_payload(&self, payload: serde_json::Value, contract_uuid: Option<&str>) -> Result<serde_json::Value, CovenantError> {
+        self.check_delegation(delegation::DelegatedAction::Write)?;
+        let mut payload = self.auth_fields(payload, contract_uuid)?;
+        if let (Some(token), Some(fields)) = (&self.delegation, payload.as_object_mut()) {
+            fields.insert("delegation".to_string(), serde_json::to_value(token.as_ref())?);
+        }
+        Ok(payload)
+    }
+
+    /// `signed_payload` without the delegation scope check, for reads
+    fn auth_fields(&self, mut payload: serde_json::Value, contract_uuid: Option<&str>) -> Result<serde_json::Value, CovenantError> {
+        let signer = match self.signer.as_deref() {
+            Some(signer) => signer,
+            None => return Ok(payload),
+        };
+
+        let timestamp = self.signing_timestamp()?;
+        let message = canonical::auth_message(timestamp, signer.uuid(), contract_uuid);
+        let signature = signer.sign(&message)?;
+
+        if let Some(fields) = payload.as_object_mut() {
+            fields.insert("signature".to_string(), serde_json::Value::String(signature));
+            fields.insert("timestamp".to_string(), serde_json::Value::from(timestamp));
+            fields.insert("userUUID".to_string(), serde_json::Value::String(signer.uuid().to_string()));
+            fields.insert("pubKey".to_string(), serde_json::Value::String(signer.public_key().to_string()));
+            if signer.scheme() != SignatureScheme::default() {
+                fields.insert("scheme".to_string(), serde_json::json!(signer.scheme()));
+            }
+        }
+
+        Ok(payload)
     }
 
     /// List contracts (optionally filtered by participant)
     pub async fn list_contracts(&self, participant_uuid: Option<&str>) -> Result<Vec<ContractSummary>, CovenantError> {
-        let mut url = format!("{}/contracts", self.base_url);
-        
+        let mut query = ContractQuery::new();
         if let Some(participant) = participant_uuid {
-            url.push_str(&format!("?participant={}", participant));
+            query = query.participant(participant);
         }
 
-        let response = self.client.get(&url).send().await?;
-        let service_response: ServiceResponse<Vec<ContractSummary>> = response.json().await?;
+        self.search_contracts(&query).await
+    }
+
+    /// List contracts matching participant, organization, and tag filters
+    pub async fn search_contracts(&self, query: &ContractQuery) -> Result<Vec<ContractSummary>, CovenantError> {
+        let url = format!("{}/contracts", self.base_url);
+
+        let request = self.signed_read(self.client.get(&url).query(&query.params()), None)?;
+        let service_response: ServiceResponse<Vec<ContractSummary>> = self.send_json(request).await?;
         
         if !service_response.success {
             return Err(CovenantError::ServiceError(
@@ -307,84 +1276,134 @@ impl CovenantClient {
             ));
         }
 
-        service_response.data.ok_or_else(|| 
+        let contracts = service_response.data.ok_or_else(|| 
             CovenantError::ServiceError("No contracts data returned".to_string())
-        )
+        )?;
+
+        // Older servers ignore the tags parameter, so filter locally as well
+        Ok(contracts.into_iter()
+            .filter(|summary| query.matches(summary))
+            .map(|summary| ContractSummary { private_note: self.cache.note(&summary.uuid), ..summary })
+            .collect())
     }
+}
 
-    /// Get contracts for current user (requires sessionless)
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Get contracts for current user
     pub async fn get_my_contracts(&self) -> Result<Vec<ContractSummary>, CovenantError> {
-        let sessionless = self.sessionless.as_ref()
-            .ok_or_else(|| CovenantError::SessionlessError("Sessionless instance required".to_string()))?;
-        
-        self.list_contracts(Some(&sessionless.uuid)).await
+        self.list_contracts(Some(self.identity().uuid())).await
+    }
+
+    /// Add tags to a contract (existing tags are kept)
+    pub async fn add_tags<I, S>(&self, uuid: &str, tags: I) -> Result<Contract, CovenantError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let contract = self.get_contract(uuid).await?;
+
+        let mut updated_tags = contract.tags;
+        for tag in tags {
+            let tag = tag.into();
+            if !updated_tags.contains(&tag) {
+                updated_tags.push(tag);
+            }
+        }
+
+        self.update_contract(uuid, serde_json::json!({ "tags": updated_tags })).await
+    }
+
+    /// Remove tags from a contract
+    pub async fn remove_tags<I, S>(&self, uuid: &str, tags: I) -> Result<Contract, CovenantError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let contract = self.get_contract(uuid).await?;
+
+        let removed: Vec<String> = tags.into_iter().map(|t| t.into()).collect();
+        let updated_tags: Vec<String> = contract.tags.into_iter()
+            .filter(|tag| !removed.contains(tag))
+            .collect();
+
+        self.update_contract(uuid, serde_json::json!({ "tags": updated_tags })).await
+    }
+
+    /// Replace a contract's metadata
+    pub async fn update_metadata(&self, uuid: &str, metadata: &HashMap<String, serde_json::Value>) -> Result<Contract, CovenantError> {
+        self.update_contract(uuid, serde_json::json!({ "metadata": metadata })).await
     }
 
     /// Delete contract
     pub async fn delete_contract(&self, uuid: &str) -> Result<String, CovenantError> {
-        let url = format!("{}/contract/{}", self.base_url, uuid);
-        let response = self.client.delete(&url).send().await?;
+        let (uuid, url) = self.contract_url(uuid, "").await?;
+        let payload = self.signed_payload(serde_json::json!({}), Some(&uuid))?;
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(self.client.delete(&url).json(&payload)).await?;
 
-        let service_response: ServiceResponse<serde_json::Value> = response.json().await?;
-        
         if !service_response.success {
             return Err(CovenantError::ServiceError(
                 service_response.error.unwrap_or_else(|| "Delete failed".to_string())
             ));
         }
 
-        Ok(uuid.to_string())
+        Ok(uuid)
     }
+}
 
-    /// Get contract as SVG
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Get contract as SVG. Theme fallback follows the client's SVG options.
     pub async fn get_contract_svg(&self, uuid: &str, theme: Option<&str>, width: Option<u32>, height: Option<u32>) -> Result<String, CovenantError> {
-        let mut url = format!("{}/contract/{}/svg", self.base_url, uuid);
-        
-        let mut params = Vec::new();
-        if let Some(theme) = theme {
-            params.push(format!("theme={}", theme));
-        }
-        if let Some(width) = width {
-            params.push(format!("width={}", width));
-        }
-        if let Some(height) = height {
-            params.push(format!("height={}", height));
-        }
-        
-        if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
-        }
+        let options = SvgOptions {
+            theme: theme.map(|t| t.to_string()),
+            width,
+            height,
+            fallback_theme: self.svg_options.fallback_theme,
+        };
+        #[cfg(feature = "pref")]
+        let options = self.with_preferred_theme(options).await;
 
-        let response = self.client.get(&url).send().await?;
-        
-        if !response.status().is_success() {
-            let error_response: ServiceResponse<serde_json::Value> = response.json().await?;
-            return Err(CovenantError::ServiceError(
-                error_response.error.unwrap_or_else(|| "SVG generation failed".to_string())
-            ));
+        let response = self.request_svg(uuid, &options).await?;
+        Ok(response.text().await?)
+    }
+
+    /// Stream contract SVG into a writer without buffering it in memory.
+    /// Returns the number of bytes written.
+    pub async fn download_contract_svg<W>(&self, uuid: &str, options: &SvgOptions, writer: &mut W) -> Result<u64, CovenantError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        self.download_contract_svg_with_progress(uuid, options, writer, |_| {}).await
+    }
+
+    /// Stream contract SVG into a writer, reporting progress after every chunk
+    pub async fn download_contract_svg_with_progress<W, F>(&self, uuid: &str, options: &SvgOptions, writer: &mut W, mut on_progress: F) -> Result<u64, CovenantError>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(DownloadProgress),
+    {
+        #[cfg(feature = "pref")]
+        let options = &self.with_preferred_theme(options.clone()).await;
+        let mut response = self.request_svg(uuid, options).await?;
+
+        let content_length = response.content_length();
+        let mut downloaded = 0u64;
+        on_progress(DownloadProgress { downloaded, content_length });
+
+        while let Some(chunk) = self.call_options.cancellable(async { Ok(response.chunk().await?) }).await? {
+            writer.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            on_progress(DownloadProgress { downloaded, content_length });
         }
 
-        Ok(response.text().await?)
+        writer.flush().await?;
+        Ok(downloaded)
     }
 
     /// Helper: Get contract progress
     pub fn get_contract_progress(&self, contract: &Contract) -> ContractProgress {
-        let total_steps = contract.steps.len();
-        let completed_steps = contract.steps.iter().filter(|step| step.completed).count();
-        let participant_count = contract.participants.len();
-
-        ContractProgress {
-            total_steps,
-            completed_steps,
-            progress_percent: if total_steps > 0 { 
-                (completed_steps as f64 / total_steps as f64) * 100.0 
-            } else { 
-                0.0 
-            },
-            participant_count,
-            is_complete: completed_steps == total_steps,
-        }
+        contract.progress()
     }
 
     /// Helper: Get user's signature status for contract
@@ -392,9 +1411,9 @@ impl CovenantClient {
         let user_uuid = match user_uuid {
             Some(uuid) => uuid,
             None => {
-                let sessionless = self.sessionless.as_ref()
+                let sessionless = self.signer.as_deref()
                     .ok_or_else(|| CovenantError::SessionlessError("User UUID required".to_string()))?;
-                &sessionless.uuid
+                sessionless.uuid()
             }
         };
 
@@ -413,107 +1432,3 @@ impl CovenantClient {
         Ok(status)
     }
 }
-
-/// Builder for creating contracts
-#[derive(Debug, Clone)]
-pub struct ContractBuilder {
-    title: Option<String>,
-    description: Option<String>,
-    participants: Vec<String>,
-    steps: Vec<(String, Option<serde_json::Value>)>, // (description, magic_spell)
-    product_uuid: Option<String>,
-    bdo_location: Option<String>,
-}
-
-impl ContractBuilder {
-    pub fn new() -> Self {
-        Self {
-            title: None,
-            description: None,
-            participants: Vec::new(),
-            steps: Vec::new(),
-            product_uuid: None,
-            bdo_location: None,
-        }
-    }
-
-    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
-        self.title = Some(title.into());
-        self
-    }
-
-    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
-        self.description = Some(description.into());
-        self
-    }
-
-    pub fn participant<S: Into<String>>(mut self, participant: S) -> Self {
-        self.participants.push(participant.into());
-        self
-    }
-
-    pub fn participants<I, S>(mut self, participants: I) -> Self 
-    where
-        I: IntoIterator<Item = S>,
-        S: Into<String>,
-    {
-        self.participants.extend(participants.into_iter().map(|p| p.into()));
-        self
-    }
-
-    pub fn step<S: Into<String>>(mut self, description: S) -> Self {
-        self.steps.push((description.into(), None));
-        self
-    }
-
-    pub fn step_with_magic<S: Into<String>>(mut self, description: S, magic_spell: serde_json::Value) -> Self {
-        self.steps.push((description.into(), Some(magic_spell)));
-        self
-    }
-
-    pub fn product_uuid<S: Into<String>>(mut self, product_uuid: S) -> Self {
-        self.product_uuid = Some(product_uuid.into());
-        self
-    }
-
-    pub fn bdo_location<S: Into<String>>(mut self, bdo_location: S) -> Self {
-        self.bdo_location = Some(bdo_location.into());
-        self
-    }
-
-    pub fn build(&self) -> Result<serde_json::Value, CovenantError> {
-        let title = self.title.as_ref()
-            .ok_or_else(|| CovenantError::ValidationError("Title is required".to_string()))?;
-
-        if self.participants.len() < 2 {
-            return Err(CovenantError::ValidationError("At least 2 participants required".to_string()));
-        }
-
-        if self.steps.is_empty() {
-            return Err(CovenantError::ValidationError("At least 1 step required".to_string()));
-        }
-
-        let steps: Vec<serde_json::Value> = self.steps.iter().enumerate().map(|(index, (description, magic_spell))| {
-            serde_json::json!({
-                "id": format!("step-{}", index + 1),
-                "description": description,
-                "magicSpell": magic_spell
-            })
-        }).collect();
-
-        Ok(serde_json::json!({
-            "title": title,
-            "description": self.description.as_ref().unwrap_or(&String::new()),
-            "participants": self.participants,
-            "steps": steps,
-            "productUuid": self.product_uuid,
-            "bdoLocation": self.bdo_location
-        }))
-    }
-}
-
-impl Default for ContractBuilder {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
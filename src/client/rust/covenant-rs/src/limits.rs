@@ -0,0 +1,101 @@
+/*!
+ * Contract size and complexity limits
+ * Client-side guardrails so oversized contracts fail in `build()` with a
+ * clear error instead of being rejected by the service.
+ */
+
+use crate::CovenantError;
+
+/// Rough size of the auth fields (signature, timestamp, userUUID, pubKey)
+/// added to a payload before it is sent
+pub const AUTH_FIELDS_ESTIMATE: usize = 320;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractLimits {
+    pub max_steps: usize,
+    pub max_participants: usize,
+    /// Applies to the contract and each step description, in characters
+    pub max_description_length: usize,
+    /// Serialized JSON size, including auth fields
+    pub max_payload_bytes: usize,
+}
+
+impl Default for ContractLimits {
+    fn default() -> Self {
+        Self {
+            max_steps: 1000,
+            max_participants: 100,
+            max_description_length: 10_000,
+            max_payload_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl ContractLimits {
+    /// No limits beyond the builder's own validation
+    pub fn unlimited() -> Self {
+        Self {
+            max_steps: usize::MAX,
+            max_participants: usize::MAX,
+            max_description_length: usize::MAX,
+            max_payload_bytes: usize::MAX,
+        }
+    }
+
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn max_participants(mut self, max_participants: usize) -> Self {
+        self.max_participants = max_participants;
+        self
+    }
+
+    pub fn max_description_length(mut self, max_description_length: usize) -> Self {
+        self.max_description_length = max_description_length;
+        self
+    }
+
+    pub fn max_payload_bytes(mut self, max_payload_bytes: usize) -> Self {
+        self.max_payload_bytes = max_payload_bytes;
+        self
+    }
+
+    pub(crate) fn check_counts(&self, participants: usize, steps: usize) -> Result<(), CovenantError> {
+        if participants > self.max_participants {
+            return Err(CovenantError::ValidationError(
+                format!("{} participants exceeds the limit of {}", participants, self.max_participants)
+            ));
+        }
+
+        if steps > self.max_steps {
+            return Err(CovenantError::ValidationError(
+                format!("{} steps exceeds the limit of {}", steps, self.max_steps)
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_description(&self, what: &str, description: &str) -> Result<(), CovenantError> {
+        let length = description.chars().count();
+        if length > self.max_description_length {
+            return Err(CovenantError::ValidationError(
+                format!("{} description is {} characters, over the limit of {}", what, length, self.max_description_length)
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_payload_size(&self, size: usize) -> Result<(), CovenantError> {
+        if size > self.max_payload_bytes {
+            return Err(CovenantError::ValidationError(
+                format!("Contract payload is about {} bytes, over the limit of {}", size, self.max_payload_bytes)
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+/*!
+ * Contract authoring lints
+ * Opinionated checks for contracts and templates that are valid but likely
+ * to cause trouble: steps nobody is responsible for signing, payments
+ * without amounts or deadlines, placeholder text left in. Each rule's
+ * severity is configurable; CI can gate templates on `LintReport::into_result`.
+ *
+ * A step is treated as a payment step when its spell carries an amount,
+ * currency or payee, or its spell name mentions paying, purchasing,
+ * transferring, depositing, releasing or refunding.
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::preview::AMOUNT_KEYS;
+use crate::{Contract, ContractBuilder, ContractStep, CovenantError, SigningOrder};
+
+const PAYMENT_SPELL_HINTS: &[&str] = &["pay", "purchase", "transfer", "deposit", "release", "refund"];
+const PAYMENT_FIELDS: &[&str] = &["currency", "payee", "recipient"];
+const PLACEHOLDERS: &[&str] = &["tbd", "tba", "todo", "fixme", "xxx", "to be determined", "to be confirmed"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintRule {
+    /// No participant is expected to sign the step
+    NoResponsibleSigner,
+    /// A payment spell that doesn't say how much
+    PaymentWithoutAmount,
+    /// Placeholder text such as "TBD" in the title or a description
+    AmbiguousText,
+    /// A payment step with no deadline
+    PaymentWithoutDeadline,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 4] = [
+        LintRule::NoResponsibleSigner,
+        LintRule::PaymentWithoutAmount,
+        LintRule::AmbiguousText,
+        LintRule::PaymentWithoutDeadline,
+    ];
+
+    fn default_severity(&self) -> Severity {
+        match self {
+            LintRule::NoResponsibleSigner | LintRule::PaymentWithoutAmount => Severity::Error,
+            LintRule::AmbiguousText | LintRule::PaymentWithoutDeadline => Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The rule doesn't run
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+/// Severity per rule; rules not configured use their default
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    severities: HashMap<LintRule, Severity>,
+}
+
+impl LintConfig {
+    pub fn severity(mut self, rule: LintRule, severity: Severity) -> Self {
+        self.severities.insert(rule, severity);
+        self
+    }
+
+    /// Raise every warning-level rule to an error
+    pub fn deny_warnings(mut self) -> Self {
+        for rule in LintRule::ALL {
+            if self.severity_of(rule) == Severity::Warning {
+                self.severities.insert(rule, Severity::Error);
+            }
+        }
+        self
+    }
+
+    pub fn severity_of(&self, rule: LintRule) -> Severity {
+        self.severities.get(&rule).copied().unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: Severity,
+    /// The step concerned; `None` for contract-level findings
+    #[serde(rename = "stepId")]
+    pub step_id: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match &self.step_id {
+            Some(step_id) => write!(f, "{} [{}]: {}", severity, step_id, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    pub fn at_least(&self, severity: Severity) -> impl Iterator<Item = &LintFinding> {
+        self.findings.iter().filter(move |f| f.severity >= severity)
+    }
+
+    /// Fail with a `ValidationError` listing the errors, if there are any
+    pub fn into_result(self) -> Result<LintReport, CovenantError> {
+        if !self.has_errors() {
+            return Ok(self);
+        }
+
+        let errors: Vec<String> = self.at_least(Severity::Error).map(|f| f.to_string()).collect();
+        Err(CovenantError::ValidationError(format!("Lint failed: {}", errors.join("; "))))
+    }
+}
+
+impl Contract {
+    /// Run the authoring lints
+    pub fn lint(&self, config: &LintConfig) -> LintReport {
+        let mut report = LintReport::default();
+        let mut push = |rule: LintRule, step_id: Option<&str>, message: String| {
+            let severity = config.severity_of(rule);
+            if severity != Severity::Off {
+                report.findings.push(LintFinding { rule, severity, step_id: step_id.map(|s| s.to_string()), message });
+            }
+        };
+
+        if let Some(placeholder) = placeholder_in(&self.title) {
+            push(LintRule::AmbiguousText, None, format!("Title contains \"{}\"", placeholder));
+        }
+        if let Some(placeholder) = placeholder_in(&self.description) {
+            push(LintRule::AmbiguousText, None, format!("Description contains \"{}\"", placeholder));
+        }
+
+        for step in &self.steps {
+            let id = Some(step.id.as_str());
+
+            if !has_responsible_signer(self, step) {
+                push(LintRule::NoResponsibleSigner, id, format!("Nobody is expected to sign \"{}\"", step.description));
+            }
+
+            if let Some(placeholder) = placeholder_in(&step.description) {
+                push(LintRule::AmbiguousText, id, format!("Description contains \"{}\"", placeholder));
+            }
+
+            if is_payment_step(step) {
+                if step.spell_preview().and_then(|preview| preview.payment).is_none() {
+                    push(LintRule::PaymentWithoutAmount, id, "Payment spell has no amount".to_string());
+                }
+                if step.deadline.is_none() {
+                    push(LintRule::PaymentWithoutDeadline, id, "Payment step has no deadline".to_string());
+                }
+            }
+        }
+
+        report
+    }
+}
+
+impl ContractBuilder {
+    /// Run the authoring lints on the contract this builder would create
+    pub fn lint(&self, config: &LintConfig) -> LintReport {
+        self.draft().lint(config)
+    }
+}
+
+/// Whether a step records signers, or its signing order names at least one
+fn has_responsible_signer(contract: &Contract, step: &ContractStep) -> bool {
+    if !step.signatures.is_empty() {
+        return true;
+    }
+
+    match contract.signing_order_for(step) {
+        SigningOrder::Custom(sequence) => !sequence.is_empty(),
+        SigningOrder::Any | SigningOrder::Sequential => !contract.participants.is_empty(),
+    }
+}
+
+fn is_payment_step(step: &ContractStep) -> bool {
+    let Some(spell) = &step.magic_spell else { return false };
+    let components = spell.get("components");
+    let has_field = |key: &&str| spell.get(*key).or_else(|| components.and_then(|c| c.get(*key))).is_some();
+
+    let named_payment = spell.get("spell")
+        .and_then(|name| name.as_str())
+        .map(|name| name.to_lowercase())
+        .is_some_and(|name| PAYMENT_SPELL_HINTS.iter().any(|hint| name.contains(hint)));
+
+    named_payment || AMOUNT_KEYS.iter().any(has_field) || PAYMENT_FIELDS.iter().any(has_field)
+}
+
+/// The first placeholder appearing as a whole word or phrase
+fn placeholder_in(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+    let normalized = format!(" {} ", words.join(" "));
+
+    PLACEHOLDERS.iter()
+        .find(|placeholder| normalized.contains(&format!(" {} ", placeholder)))
+        .copied()
+        .or_else(|| text.contains("???").then_some("???"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(contract: &Contract) -> Vec<(LintRule, Option<String>)> {
+        contract.lint(&LintConfig::default()).findings.into_iter().map(|f| (f.rule, f.step_id)).collect()
+    }
+
+    #[test]
+    fn fixtures_are_clean() {
+        assert!(Contract::fixture().lint(&LintConfig::default()).is_clean());
+    }
+
+    #[test]
+    fn no_responsible_signer() {
+        let mut contract = Contract::fixture();
+        contract.participants.clear();
+        contract.steps[1].signatures.clear();
+        assert_eq!(rules(&contract), [(LintRule::NoResponsibleSigner, Some("step-2".to_string()))]);
+
+        contract.steps[1].signing_order = Some(SigningOrder::Custom(vec!["notary".to_string()]));
+        assert!(rules(&contract).is_empty());
+    }
+
+    #[test]
+    fn payment_without_amount() {
+        let mut contract = Contract::fixture();
+        contract.steps[0].deadline = Some("2026-06-01T00:00:00Z".to_string());
+        contract.steps[0].magic_spell = Some(serde_json::json!({ "spell": "payDeposit" }));
+        assert_eq!(rules(&contract), [(LintRule::PaymentWithoutAmount, Some("step-1".to_string()))]);
+
+        contract.steps[0].magic_spell = Some(serde_json::json!({ "spell": "payDeposit", "amount": 500 }));
+        assert!(rules(&contract).is_empty());
+    }
+
+    #[test]
+    fn payment_without_deadline() {
+        let mut contract = Contract::fixture();
+        contract.steps[2].magic_spell = Some(serde_json::json!({ "spell": "settle", "amount": 500, "payee": "bob" }));
+        assert_eq!(rules(&contract), [(LintRule::PaymentWithoutDeadline, Some("step-3".to_string()))]);
+    }
+
+    #[test]
+    fn ambiguous_text() {
+        let mut contract = Contract::fixture();
+        contract.title = "Lease (TBD)".to_string();
+        contract.steps[0].description = "Pay rent ???".to_string();
+        contract.steps[1].description = "Deliver the tbdx".to_string();
+        assert_eq!(rules(&contract), [
+            (LintRule::AmbiguousText, None),
+            (LintRule::AmbiguousText, Some("step-1".to_string())),
+        ]);
+        assert_eq!(placeholder_in("Amount to be determined later"), Some("to be determined"));
+    }
+
+    #[test]
+    fn severities_are_configurable() {
+        let mut contract = Contract::fixture();
+        contract.title = "TODO".to_string();
+
+        let report = contract.lint(&LintConfig::default());
+        assert_eq!(report.findings[0].severity, Severity::Warning);
+        assert!(report.into_result().is_ok());
+
+        assert!(contract.lint(&LintConfig::default().deny_warnings()).into_result().is_err());
+        assert!(contract.lint(&LintConfig::default().severity(LintRule::AmbiguousText, Severity::Off)).is_clean());
+    }
+}
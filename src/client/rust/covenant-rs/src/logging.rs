@@ -0,0 +1,151 @@
+/*!
+ * Request/response logging hooks
+ * Captured bodies have signatures and keys redacted before callbacks see them.
+ */
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Field names whose values are never passed to log callbacks
+const REDACTED_FIELDS: &[&str] = &[
+    "signature",
+    "stepSignature",
+    "oldSignature",
+    "newSignature",
+    "pubKey",
+    "oldPubKey",
+    "newPubKey",
+    "publicKey",
+    "privateKey",
+    "token",
+    "delegation",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Outgoing request as seen by `on_request`
+#[derive(Debug, Clone)]
+pub struct LoggedRequest {
+    pub method: String,
+    pub url: String,
+    pub body: Option<serde_json::Value>,
+}
+
+/// Completed response as seen by `on_response`
+#[derive(Debug, Clone)]
+pub struct LoggedResponse {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub latency: Duration,
+    /// Response body, when it was buffered
+    pub body: Option<serde_json::Value>,
+}
+
+type RequestHook = Arc<dyn Fn(&LoggedRequest) + Send + Sync>;
+type ResponseHook = Arc<dyn Fn(&LoggedResponse) + Send + Sync>;
+
+/// Callbacks invoked around every HTTP call the client makes
+#[derive(Clone, Default)]
+pub struct RequestLogger {
+    on_request: Option<RequestHook>,
+    on_response: Option<ResponseHook>,
+}
+
+impl RequestLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_request<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&LoggedRequest) + Send + Sync + 'static,
+    {
+        self.on_request = Some(Arc::new(hook));
+        self
+    }
+
+    pub fn on_response<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&LoggedResponse) + Send + Sync + 'static,
+    {
+        self.on_response = Some(Arc::new(hook));
+        self
+    }
+
+    pub(crate) fn log_request(&self, method: &str, url: &str, body: Option<&[u8]>) {
+        if let Some(hook) = &self.on_request {
+            hook(&LoggedRequest {
+                method: method.to_string(),
+                url: redact_url(url),
+                body: body.and_then(parse_redacted),
+            });
+        }
+    }
+
+    pub(crate) fn log_response(&self, method: &str, url: &str, status: u16, latency: Duration, body: Option<&serde_json::Value>) {
+        if let Some(hook) = &self.on_response {
+            hook(&LoggedResponse {
+                method: method.to_string(),
+                url: redact_url(url),
+                status,
+                latency,
+                body: body.map(redact_json),
+            });
+        }
+    }
+}
+
+impl fmt::Debug for RequestLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestLogger")
+            .field("on_request", &self.on_request.is_some())
+            .field("on_response", &self.on_response.is_some())
+            .finish()
+    }
+}
+
+fn parse_redacted(body: &[u8]) -> Option<serde_json::Value> {
+    serde_json::from_slice(body).ok().map(|value| redact_json(&value))
+}
+
+fn is_redacted_field(name: &str) -> bool {
+    REDACTED_FIELDS.iter().any(|field| field.eq_ignore_ascii_case(name))
+}
+
+/// Copy of `value` with sensitive fields replaced by a placeholder
+pub fn redact_json(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if is_redacted_field(key) && !value.is_null() {
+                        serde_json::Value::String(REDACTED.to_string())
+                    } else {
+                        redact_json(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        ),
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(redact_json).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Redact sensitive query parameters from a URL
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+
+    let params: Vec<String> = query.split('&').map(|pair| {
+        match pair.split_once('=') {
+            Some((key, _)) if is_redacted_field(key) => format!("{}={}", key, REDACTED),
+            _ => pair.to_string(),
+        }
+    }).collect();
+
+    format!("{}?{}", base, params.join("&"))
+}
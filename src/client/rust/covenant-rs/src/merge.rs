@@ -0,0 +1,411 @@
+/*!
+ * Three-way contract merge
+ * Reconciles two copies of a contract that were edited separately (say one
+ * offline) against the version both started from. Title, description,
+ * participants, metadata and each step's editable fields (description,
+ * spell, deadline, phase, weight) are merged independently; a change on one
+ * side wins over no change on the other.
+ *
+ * When both sides changed the same thing differently the merge records a
+ * `MergeConflict` and keeps our value, except in text fields, which get
+ * git-style conflict markers for someone to resolve by hand. Everything the
+ * service owns (revision, status, signatures) comes from `theirs`.
+ */
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::{Contract, ContractStep};
+#[cfg(feature = "client")]
+use crate::{format, Authenticated, CovenantClient, CovenantError, ServiceResponse};
+
+pub const CONFLICT_OURS: &str = "<<<<<<< ours";
+pub const CONFLICT_SEPARATOR: &str = "=======";
+pub const CONFLICT_THEIRS: &str = ">>>>>>> theirs";
+
+/// Something both sides changed differently. `None` means absent on that side.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeConflict {
+    /// e.g. "title", "metadata.budget", "steps.step-2.description"
+    pub path: String,
+    pub base: Option<serde_json::Value>,
+    pub ours: Option<serde_json::Value>,
+    pub theirs: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub merged: Contract,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// True if `text` still contains unresolved conflict markers
+pub fn has_conflict_markers(text: &str) -> bool {
+    text.lines().any(|line| line == CONFLICT_OURS || line == CONFLICT_THEIRS)
+}
+
+/// Merge `ours` and `theirs`, both descended from `base`
+pub fn merge(base: &Contract, ours: &Contract, theirs: &Contract) -> MergeResult {
+    let mut conflicts = Vec::new();
+    let mut merged = theirs.clone();
+
+    merged.title = merge_text("title", &base.title, &ours.title, &theirs.title, &mut conflicts);
+    merged.description = merge_text("description", &base.description, &ours.description, &theirs.description, &mut conflicts);
+    merged.participants = merge_list(&base.participants, &ours.participants, &theirs.participants);
+    merged.metadata = merge_map("metadata", &base.metadata, &ours.metadata, &theirs.metadata, &mut conflicts);
+    merged.steps = merge_steps(&base.steps, &ours.steps, &theirs.steps, &mut conflicts);
+
+    MergeResult { merged, conflicts }
+}
+
+fn value<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+fn conflict<T: Serialize>(path: &str, base: Option<&T>, ours: Option<&T>, theirs: Option<&T>) -> MergeConflict {
+    MergeConflict {
+        path: path.to_string(),
+        base: base.map(value),
+        ours: ours.map(value),
+        theirs: theirs.map(value),
+    }
+}
+
+fn merge_value<T: PartialEq + Clone + Serialize>(path: &str, base: &T, ours: &T, theirs: &T, conflicts: &mut Vec<MergeConflict>) -> T {
+    if ours == theirs || ours == base {
+        theirs.clone()
+    } else if theirs == base {
+        ours.clone()
+    } else {
+        conflicts.push(conflict(path, Some(base), Some(ours), Some(theirs)));
+        ours.clone()
+    }
+}
+
+fn merge_text(path: &str, base: &str, ours: &str, theirs: &str, conflicts: &mut Vec<MergeConflict>) -> String {
+    if ours == theirs || ours == base {
+        theirs.to_string()
+    } else if theirs == base {
+        ours.to_string()
+    } else {
+        conflicts.push(conflict(path, Some(&base), Some(&ours), Some(&theirs)));
+        format!("{}\n{}\n{}\n{}\n{}", CONFLICT_OURS, ours, CONFLICT_SEPARATOR, theirs, CONFLICT_THEIRS)
+    }
+}
+
+/// Keeps entries neither side removed, in their order, then our additions
+fn merge_list(base: &[String], ours: &[String], theirs: &[String]) -> Vec<String> {
+    let removed = |item: &String| base.contains(item) && (!ours.contains(item) || !theirs.contains(item));
+    let mut merged: Vec<String> = theirs.iter().filter(|item| !removed(item)).cloned().collect();
+    for item in ours {
+        if !removed(item) && !merged.contains(item) {
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+fn merge_map(
+    path: &str,
+    base: &HashMap<String, serde_json::Value>,
+    ours: &HashMap<String, serde_json::Value>,
+    theirs: &HashMap<String, serde_json::Value>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> HashMap<String, serde_json::Value> {
+    let keys: BTreeSet<&String> = base.keys().chain(ours.keys()).chain(theirs.keys()).collect();
+    keys.into_iter().filter_map(|key| {
+        let (base, ours, theirs) = (base.get(key), ours.get(key), theirs.get(key));
+        let merged = if ours == theirs || ours == base {
+            theirs
+        } else if theirs == base {
+            ours
+        } else {
+            conflicts.push(conflict(&format!("{}.{}", path, key), base, ours, theirs));
+            ours
+        };
+        merged.map(|value| (key.clone(), value.clone()))
+    }).collect()
+}
+
+/// The parts of a step either side may edit
+#[derive(Debug, Clone, Default, PartialEq)]
+struct StepFields {
+    description: String,
+    magic_spell: Option<serde_json::Value>,
+    deadline: Option<String>,
+    phase: Option<String>,
+    weight: Option<f32>,
+}
+
+impl From<&ContractStep> for StepFields {
+    fn from(step: &ContractStep) -> Self {
+        Self {
+            description: step.description.clone(),
+            magic_spell: step.magic_spell.clone(),
+            deadline: step.deadline.clone(),
+            phase: step.phase.clone(),
+            weight: step.weight,
+        }
+    }
+}
+
+fn merge_step(base: Option<&ContractStep>, ours: &ContractStep, theirs: &ContractStep, conflicts: &mut Vec<MergeConflict>) -> ContractStep {
+    let base = base.map(StepFields::from).unwrap_or_default();
+    let (ours_fields, theirs_fields) = (StepFields::from(ours), StepFields::from(theirs));
+    let path = |field: &str| format!("steps.{}.{}", theirs.id, field);
+
+    ContractStep {
+        description: merge_text(&path("description"), &base.description, &ours_fields.description, &theirs_fields.description, conflicts),
+        magic_spell: merge_value(&path("magicSpell"), &base.magic_spell, &ours_fields.magic_spell, &theirs_fields.magic_spell, conflicts),
+        deadline: merge_value(&path("deadline"), &base.deadline, &ours_fields.deadline, &theirs_fields.deadline, conflicts),
+        phase: merge_value(&path("phase"), &base.phase, &ours_fields.phase, &theirs_fields.phase, conflicts),
+        weight: merge_value(&path("weight"), &base.weight, &ours_fields.weight, &theirs_fields.weight, conflicts),
+        ..theirs.clone()
+    }
+}
+
+/// Steps in their order, then steps only we added; a step one side removed
+/// and the other edited is kept and reported
+fn merge_steps(base: &[ContractStep], ours: &[ContractStep], theirs: &[ContractStep], conflicts: &mut Vec<MergeConflict>) -> Vec<ContractStep> {
+    let find = |steps: &[ContractStep], id: &str| steps.iter().find(|step| step.id == id).cloned();
+    let ids = theirs.iter().map(|step| &step.id)
+        .chain(ours.iter().map(|step| &step.id).filter(|id| !theirs.iter().any(|step| &step.id == *id)));
+
+    let mut merged = Vec::new();
+    for id in ids {
+        let (base_step, ours_step, theirs_step) = (find(base, id), find(ours, id), find(theirs, id));
+        let path = format!("steps.{}", id);
+        let step = match (&base_step, ours_step, theirs_step) {
+            (_, Some(ours_step), Some(theirs_step)) => Some(merge_step(base_step.as_ref(), &ours_step, &theirs_step, conflicts)),
+            (None, kept, None) | (None, None, kept) => kept,
+            (Some(base_step), Some(kept), None) | (Some(base_step), None, Some(kept)) => {
+                if StepFields::from(base_step) == StepFields::from(&kept) {
+                    None
+                } else {
+                    let edited = Some(&kept);
+                    let (ours_side, theirs_side) = if ours.iter().any(|step| &step.id == id) { (edited, None) } else { (None, edited) };
+                    conflicts.push(conflict(&path, Some(base_step), ours_side, theirs_side));
+                    Some(kept)
+                }
+            }
+            (Some(_), None, None) => None,
+        };
+        merged.extend(step);
+    }
+
+    for (order, step) in merged.iter_mut().enumerate() {
+        step.order = order;
+    }
+    merged
+}
+
+/// Times `push_merged` re-merges after the contract changed under it
+#[cfg(feature = "client")]
+const MAX_PUSH_ATTEMPTS: usize = 3;
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Merge our edits of `base` into the service's current copy and push
+    /// the result. With conflicts nothing is pushed; resolve them in
+    /// `merged` and push that instead.
+    ///
+    /// Only the fields the merge covers are sent, never signatures, and the
+    /// push is conditional on the revision it merged against. When the
+    /// contract changed in between, it's fetched and merged again.
+    pub async fn push_merged(&self, base: &Contract, ours: &Contract) -> Result<MergeResult, CovenantError> {
+        for _ in 0..MAX_PUSH_ATTEMPTS {
+            let theirs = self.get_contract(&base.uuid).await?;
+            let mut result = merge(base, ours, &theirs);
+            if !result.is_clean() {
+                return Ok(result);
+            }
+
+            let steps: Vec<serde_json::Value> = result.merged.steps.iter().map(|step| serde_json::json!({
+                "id": step.id,
+                "order": step.order,
+                "description": step.description,
+                "magicSpell": step.magic_spell,
+                "deadline": step.deadline,
+                "phase": step.phase,
+                "weight": step.weight
+            })).collect();
+            let url = self.contract_route(&theirs.uuid, "");
+            let payload = self.signed_payload(serde_json::json!({
+                "title": result.merged.title,
+                "description": result.merged.description,
+                "participants": result.merged.participants,
+                "steps": steps,
+                "metadata": result.merged.metadata,
+                "expectedRevision": theirs.revision
+            }), Some(&theirs.uuid))?;
+
+            let response = self.send(self.client.put(&url).json(&payload)).await?;
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                continue;
+            }
+
+            let service_response: ServiceResponse<Contract> = format::decode_response(response).await?;
+            if !service_response.success {
+                return Err(CovenantError::ServiceError(
+                    service_response.error.unwrap_or_else(|| "Update failed".to_string())
+                ));
+            }
+
+            result.merged = service_response.data.ok_or_else(||
+                CovenantError::ServiceError("No contract data returned".to_string())
+            )?;
+            return Ok(result);
+        }
+
+        Err(CovenantError::ServiceError(format!("Contract {} kept changing while pushing the merge", base.uuid)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step<'a>(contract: &'a mut Contract, id: &str) -> &'a mut ContractStep {
+        contract.steps.iter_mut().find(|step| step.id == id).unwrap()
+    }
+
+    #[test]
+    fn separate_edits_merge_cleanly() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.title = "Our title".to_string();
+        theirs.description = "Their description".to_string();
+        step(&mut theirs, "step-2").weight = Some(2.0);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert!(result.is_clean());
+        assert_eq!(result.merged.title, "Our title");
+        assert_eq!(result.merged.description, "Their description");
+        assert_eq!(result.merged.steps[1].weight, Some(2.0));
+    }
+
+    #[test]
+    fn same_edit_on_both_sides_is_not_a_conflict() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        ours.title = "Renamed".to_string();
+
+        let result = merge(&base, &ours, &ours.clone());
+
+        assert!(result.is_clean());
+        assert_eq!(result.merged.title, "Renamed");
+    }
+
+    #[test]
+    fn conflicting_text_gets_markers() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.title = "Ours".to_string();
+        theirs.title = "Theirs".to_string();
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "title");
+        assert_eq!(result.conflicts[0].ours, Some(serde_json::json!("Ours")));
+        assert_eq!(result.merged.title, "<<<<<<< ours\nOurs\n=======\nTheirs\n>>>>>>> theirs");
+        assert!(has_conflict_markers(&result.merged.title));
+    }
+
+    #[test]
+    fn conflicting_values_keep_ours() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.metadata.insert("budget".to_string(), serde_json::json!(100));
+        theirs.metadata.insert("budget".to_string(), serde_json::json!(200));
+        theirs.metadata.insert("owner".to_string(), serde_json::json!("finance"));
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "metadata.budget");
+        assert_eq!(result.conflicts[0].base, None);
+        assert_eq!(result.merged.metadata["budget"], serde_json::json!(100));
+        assert_eq!(result.merged.metadata["owner"], serde_json::json!("finance"));
+    }
+
+    #[test]
+    fn participant_lists_keep_additions_and_removals() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.participants.push("carol".to_string());
+        theirs.participants.remove(0);
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.merged.participants, vec![base.participants[1].clone(), "carol".to_string()]);
+    }
+
+    #[test]
+    fn added_steps_are_appended_and_renumbered() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        let mut added = ContractStep::fixture();
+        added.id = "step-extra".to_string();
+        added.order = 9;
+        ours.steps.insert(0, added);
+        let mut theirs = base.clone();
+        theirs.steps.remove(0);
+
+        let result = merge(&base, &ours, &theirs);
+
+        let ids: Vec<&str> = result.merged.steps.iter().map(|step| step.id.as_str()).collect();
+        assert_eq!(ids, ["step-2", "step-3", "step-extra"]);
+        let orders: Vec<usize> = result.merged.steps.iter().map(|step| step.order).collect();
+        assert_eq!(orders, [0, 1, 2]);
+        assert!(result.is_clean());
+    }
+
+    #[test]
+    fn edited_step_removed_on_the_other_side_is_kept_and_reported() {
+        let base = Contract::fixture();
+        let mut ours = base.clone();
+        step(&mut ours, "step-2").description = "Edited".to_string();
+        let mut theirs = base.clone();
+        theirs.steps.retain(|step| step.id != "step-2");
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "steps.step-2");
+        assert!(result.conflicts[0].ours.is_some());
+        assert!(result.conflicts[0].theirs.is_none());
+        assert!(result.merged.steps.iter().any(|step| step.description == "Edited"));
+    }
+
+    #[test]
+    fn service_state_comes_from_theirs() {
+        let base = Contract::fixture();
+        let ours = base.clone();
+        let theirs = crate::ContractFixture::new().completed(1).build();
+
+        let result = merge(&base, &ours, &theirs);
+
+        assert_eq!(result.merged.revision, theirs.revision);
+        assert!(result.merged.steps[0].completed);
+        assert!(result.merged.steps[0].signatures.values().all(Option::is_some));
+    }
+
+    #[test]
+    fn markers_must_be_whole_lines() {
+        assert!(!has_conflict_markers("a <<<<<<< ours b"));
+        assert!(has_conflict_markers("x\n>>>>>>> theirs\n"));
+    }
+}
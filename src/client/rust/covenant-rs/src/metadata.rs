@@ -0,0 +1,53 @@
+/*!
+ * Typed helpers for integrator-defined metadata on contracts and steps
+ */
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::{Contract, ContractStep, CovenantError};
+
+impl Contract {
+    /// Store a serializable value under `key`
+    pub fn set_metadata<S: Into<String>, V: Serialize>(&mut self, key: S, value: V) -> Result<(), CovenantError> {
+        set(&mut self.metadata, key.into(), value)
+    }
+
+    pub fn get_metadata(&self, key: &str) -> Option<&serde_json::Value> {
+        self.metadata.get(key)
+    }
+
+    /// Deserialize the value stored under `key`, if any
+    pub fn get_metadata_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, CovenantError> {
+        get_as(&self.metadata, key)
+    }
+}
+
+impl ContractStep {
+    /// Store a serializable value under `key`
+    pub fn set_metadata<S: Into<String>, V: Serialize>(&mut self, key: S, value: V) -> Result<(), CovenantError> {
+        set(&mut self.metadata, key.into(), value)
+    }
+
+    pub fn get_metadata(&self, key: &str) -> Option<&serde_json::Value> {
+        self.metadata.get(key)
+    }
+
+    /// Deserialize the value stored under `key`, if any
+    pub fn get_metadata_as<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, CovenantError> {
+        get_as(&self.metadata, key)
+    }
+}
+
+fn set<V: Serialize>(metadata: &mut HashMap<String, serde_json::Value>, key: String, value: V) -> Result<(), CovenantError> {
+    metadata.insert(key, serde_json::to_value(value)?);
+    Ok(())
+}
+
+fn get_as<T: DeserializeOwned>(metadata: &HashMap<String, serde_json::Value>, key: &str) -> Result<Option<T>, CovenantError> {
+    metadata.get(key)
+        .map(|value| serde_json::from_value(value.clone()))
+        .transpose()
+        .map_err(CovenantError::from)
+}
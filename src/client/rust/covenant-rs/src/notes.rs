@@ -0,0 +1,102 @@
+/*!
+ * Private contract notes
+ * Per-user annotations on a contract that other participants never see.
+ * Notes live in the client's `ContentCache`; with the `vault` feature they
+ * can also be synced as a blob encrypted with the user's vault key, so the
+ * service only ever stores ciphertext. Notes show up on listed summaries and
+ * (with the `search` feature) in local search under `note:`. Clearing the
+ * cache clears local notes too.
+ */
+
+use crate::prefetch::ContentCache;
+use crate::CovenantError;
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+#[cfg(all(feature = "client", feature = "vault"))]
+use crate::{encoding, Authenticated, ServiceResponse, VaultKey};
+
+fn note_key(uuid: &str) -> String {
+    format!("note/{}", uuid)
+}
+
+impl ContentCache {
+    pub fn note(&self, contract_uuid: &str) -> Option<String> {
+        self.get(&note_key(contract_uuid)).and_then(|bytes| String::from_utf8(bytes).ok())
+    }
+
+    /// Store a note; an empty note removes it
+    pub fn put_note(&self, contract_uuid: &str, text: &str) -> Result<(), CovenantError> {
+        if text.is_empty() {
+            self.remove(&note_key(contract_uuid))?;
+        } else {
+            self.put(note_key(contract_uuid), text.as_bytes().to_vec())?;
+        }
+
+        #[cfg(feature = "search")]
+        self.search_index().index_note(contract_uuid, text);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Attach a private note to a contract; an empty note clears it
+    pub fn set_private_note(&self, contract_uuid: &str, text: &str) -> Result<(), CovenantError> {
+        self.cache.put_note(&self.cached_uuid(contract_uuid), text)
+    }
+
+    pub fn private_note(&self, contract_uuid: &str) -> Option<String> {
+        self.cache.note(&self.cached_uuid(contract_uuid))
+    }
+}
+
+#[cfg(all(feature = "client", feature = "vault"))]
+impl CovenantClient<Authenticated> {
+    /// Upload the local note, encrypted with `key`
+    pub async fn sync_private_note(&self, contract_uuid: &str, key: &VaultKey) -> Result<(), CovenantError> {
+        let contract_uuid = &self.resolve_uuid(contract_uuid).await?;
+        let note = self.private_note(contract_uuid).unwrap_or_default();
+        let url = self.note_url(contract_uuid);
+        let payload = self.signed_payload(serde_json::json!({
+            "note": encoding::to_hex(&key.seal(note.as_bytes())?)
+        }), None)?;
+
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Sync note failed".to_string())
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Download and decrypt the synced note into the local cache
+    pub async fn fetch_private_note(&self, contract_uuid: &str, key: &VaultKey) -> Result<Option<String>, CovenantError> {
+        let contract_uuid = &self.resolve_uuid(contract_uuid).await?;
+        let url = self.note_url(contract_uuid);
+        let request = self.signed_read(self.client.get(&url), None)?;
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Fetch note failed".to_string())
+            ));
+        }
+
+        let sealed = match service_response.data.as_ref().and_then(|data| data.get("note")).and_then(|note| note.as_str()) {
+            Some(sealed) => sealed,
+            None => return Ok(None),
+        };
+        let note = String::from_utf8(key.open(&encoding::from_hex(sealed)?)?)
+            .map_err(|e| CovenantError::EncodingError(e.to_string()))?;
+
+        self.cache.put_note(contract_uuid, &note)?;
+        Ok(Some(note).filter(|note| !note.is_empty()))
+    }
+
+    fn note_url(&self, contract_uuid: &str) -> String {
+        format!("{}/user/{}/note/{}", self.base_url, self.identity().uuid(), contract_uuid)
+    }
+}
@@ -0,0 +1,237 @@
+/*!
+ * Long-running server operations
+ * Bulk exports, PDF generation and bulk deletes run in the background on the
+ * service. The calls that start them return an `Operation<T>` straight away;
+ * `poll` refreshes its status once and `await_complete` polls until the
+ * result is ready, reporting progress along the way. Cancelling through
+ * `CallOptions` stops waiting but leaves the operation running.
+ */
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{Authenticated, ContractQuery, CovenantClient, CovenantError, ServiceResponse};
+
+/// Poll interval when the service doesn't suggest one
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum OperationState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OperationStatus {
+    #[serde(rename = "operationId")]
+    pub operation_id: String,
+    pub state: OperationState,
+    /// Fraction done, 0.0 to 1.0, when the service can tell
+    pub progress: Option<f64>,
+    pub message: Option<String>,
+    /// Milliseconds the service suggests waiting before the next poll
+    #[serde(rename = "retryAfter")]
+    pub retry_after: Option<u64>,
+    /// Set once succeeded
+    pub result: Option<serde_json::Value>,
+    /// Set once failed
+    pub error: Option<String>,
+}
+
+impl OperationStatus {
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, OperationState::Succeeded | OperationState::Failed)
+    }
+}
+
+/// Result of `export_contracts`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ContractExport {
+    /// Where to download the archive
+    pub url: String,
+    #[serde(rename = "contractCount")]
+    pub contract_count: usize,
+    pub bytes: Option<u64>,
+    /// Milliseconds since the epoch
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<i64>,
+}
+
+/// Result of `generate_contract_pdf`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PdfDocument {
+    pub url: String,
+    pub pages: Option<u32>,
+    /// Milliseconds since the epoch
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<i64>,
+}
+
+/// Result of `delete_contracts`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BulkDeleteResult {
+    #[serde(default)]
+    pub deleted: Vec<String>,
+    /// Reason per contract that couldn't be deleted
+    #[serde(default)]
+    pub failed: HashMap<String, String>,
+}
+
+type ProgressCallback = Box<dyn FnMut(&OperationStatus) + Send>;
+
+/// Handle to a running server operation producing a `T`
+pub struct Operation<T> {
+    client: CovenantClient<Authenticated>,
+    status: OperationStatus,
+    on_progress: Option<ProgressCallback>,
+    result: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Operation<T> {
+    pub fn id(&self) -> &str {
+        &self.status.operation_id
+    }
+
+    /// Status as of the last poll
+    pub fn status(&self) -> &OperationStatus {
+        &self.status
+    }
+
+    pub fn progress(&self) -> Option<f64> {
+        self.status.progress
+    }
+
+    /// Call `callback` with each status `poll` fetches
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&OperationStatus) + Send + 'static,
+    {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Refresh the status once. `Some` with the result once the operation
+    /// succeeded; an error if it failed.
+    pub async fn poll(&mut self) -> Result<Option<T>, CovenantError> {
+        if !self.status.is_done() {
+            self.status = self.client.get_operation_status(&self.status.operation_id).await?;
+            if let Some(callback) = &mut self.on_progress {
+                callback(&self.status);
+            }
+        }
+        self.outcome()
+    }
+
+    /// Poll until the operation finishes or `timeout` passes
+    pub async fn await_complete(mut self, timeout: Duration) -> Result<T, CovenantError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(result) = self.poll().await? {
+                return Ok(result);
+            }
+
+            let wait = self.status.retry_after.map(Duration::from_millis).unwrap_or(DEFAULT_POLL_INTERVAL);
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(CovenantError::OperationTimeout(self.status.operation_id.clone()));
+            }
+
+            self.client.call_options.cancellable(async {
+                tokio::time::sleep(wait.min(deadline - now)).await;
+                Ok(())
+            }).await?;
+        }
+    }
+
+    fn outcome(&self) -> Result<Option<T>, CovenantError> {
+        match self.status.state {
+            OperationState::Pending | OperationState::Running => Ok(None),
+            OperationState::Failed => Err(CovenantError::ServiceError(
+                self.status.error.clone().unwrap_or_else(|| format!("Operation {} failed", self.status.operation_id))
+            )),
+            OperationState::Succeeded => {
+                let result = self.status.result.clone().ok_or_else(||
+                    CovenantError::ServiceError(format!("Operation {} returned no result", self.status.operation_id))
+                )?;
+                Ok(Some(serde_json::from_value(result)?))
+            }
+        }
+    }
+}
+
+impl CovenantClient<Authenticated> {
+    pub async fn get_operation_status(&self, operation_id: &str) -> Result<OperationStatus, CovenantError> {
+        let url = format!("{}/operation/{}", self.base_url, operation_id);
+        let request = self.signed_read(self.client.get(&url), None)?;
+        let service_response: ServiceResponse<OperationStatus> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Operation not found".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No operation status returned".to_string())
+        )
+    }
+
+    /// Start exporting every contract matching `query` to a downloadable archive
+    pub async fn export_contracts(&self, query: &ContractQuery) -> Result<Operation<ContractExport>, CovenantError> {
+        let url = format!("{}/contracts/export", self.base_url);
+        let payload = self.signed_payload(serde_json::json!({
+            "participant": query.participant,
+            "orgUuid": query.org_uuid,
+            "tags": query.tags,
+            "seriesId": query.series_id
+        }), None)?;
+        self.start_operation(url, payload, "Export failed").await
+    }
+
+    /// Start rendering a contract to PDF
+    pub async fn generate_contract_pdf(&self, uuid: &str) -> Result<Operation<PdfDocument>, CovenantError> {
+        let (uuid, url) = self.contract_url(uuid, "pdf").await?;
+        let payload = self.signed_payload(serde_json::json!({}), Some(&uuid))?;
+        self.start_operation(url, payload, "PDF generation failed").await
+    }
+
+    /// Start deleting many contracts at once
+    pub async fn delete_contracts(&self, uuids: &[&str]) -> Result<Operation<BulkDeleteResult>, CovenantError> {
+        let url = format!("{}/contracts/delete", self.base_url);
+        let payload = self.signed_payload(serde_json::json!({ "uuids": uuids }), None)?;
+        self.start_operation(url, payload, "Bulk delete failed").await
+    }
+
+    async fn start_operation<T: DeserializeOwned>(&self, url: String, payload: serde_json::Value, failure: &str) -> Result<Operation<T>, CovenantError> {
+        let service_response: ServiceResponse<OperationStatus> = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| failure.to_string())
+            ));
+        }
+
+        let status = service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No operation status returned".to_string())
+        )?;
+
+        Ok(Operation {
+            client: self.clone(),
+            status,
+            on_progress: None,
+            result: PhantomData,
+        })
+    }
+}
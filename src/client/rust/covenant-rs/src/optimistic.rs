@@ -0,0 +1,201 @@
+/*!
+ * Optimistic local updates
+ * Applies a mutation to a locally held contract before (or instead of)
+ * refetching it, so UIs can show the result immediately. Every apply returns
+ * a `Rollback` that restores what it changed if the service later rejects
+ * the mutation. Rolling back a step-level update restores only that step,
+ * so later updates to other steps survive.
+ */
+
+use std::mem;
+
+use crate::{Contract, ContractEvent, ContractStep, CovenantError, SignStepResponse, StepSignature};
+#[cfg(feature = "client")]
+use std::sync::Mutex;
+#[cfg(feature = "client")]
+use crate::{canonical, Authenticated, CovenantClient};
+
+/// Undoes one optimistic update
+#[must_use = "keep the rollback until the service confirms the update"]
+#[derive(Debug, Clone)]
+pub struct Rollback {
+    contract_uuid: String,
+    undo: Undo,
+}
+
+#[derive(Debug, Clone)]
+enum Undo {
+    Nothing,
+    Step {
+        step: Box<ContractStep>,
+        revision: Option<u64>,
+        updated_at: String,
+    },
+    Contract(Box<Contract>),
+}
+
+impl Rollback {
+    fn nothing(contract: &Contract) -> Self {
+        Self { contract_uuid: contract.uuid.clone(), undo: Undo::Nothing }
+    }
+
+    pub fn contract_uuid(&self) -> &str {
+        &self.contract_uuid
+    }
+
+    /// Restore the state from before the update. Does nothing if `contract`
+    /// is a different contract.
+    pub fn rollback(self, contract: &mut Contract) {
+        if contract.uuid != self.contract_uuid {
+            return;
+        }
+
+        match self.undo {
+            Undo::Nothing => {}
+            Undo::Step { step, revision, updated_at } => {
+                if let Some(current) = contract.steps.iter_mut().find(|s| s.id == step.id) {
+                    *current = *step;
+                }
+                contract.revision = revision;
+                contract.updated_at = updated_at;
+            }
+            Undo::Contract(previous) => *contract = *previous,
+        }
+    }
+}
+
+impl Contract {
+    /// Snapshot a step, then let `update` change it
+    fn update_step<F>(&mut self, step_id: &str, update: F) -> Result<Rollback, CovenantError>
+    where
+        F: FnOnce(&mut ContractStep),
+    {
+        let revision = self.revision;
+        let updated_at = self.updated_at.clone();
+        let step = self.steps.iter_mut().find(|s| s.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+        let previous = step.clone();
+        update(step);
+
+        Ok(Rollback {
+            contract_uuid: self.uuid.clone(),
+            undo: Undo::Step { step: Box::new(previous), revision, updated_at },
+        })
+    }
+
+    /// Apply the outcome of a `sign_step` call without refetching
+    pub fn apply(&mut self, response: &SignStepResponse) -> Result<Rollback, CovenantError> {
+        if response.contract_uuid != self.uuid {
+            return Err(CovenantError::ValidationError(
+                format!("Response is for contract {}, not {}", response.contract_uuid, self.uuid)
+            ));
+        }
+
+        let rollback = self.update_step(&response.step_id, |step| {
+            if response.step_completed {
+                step.completed = true;
+            }
+        })?;
+
+        if response.revision > self.revision {
+            self.revision = response.revision;
+        }
+        Ok(rollback)
+    }
+
+    /// Record a signature on a step, completing it when no signer is left
+    pub fn apply_signature(&mut self, step_id: &str, signer: &str, signature: StepSignature) -> Result<Rollback, CovenantError> {
+        let participants = self.participants.clone();
+        self.update_step(step_id, |step| {
+            step.signatures.insert(signer.to_string(), Some(signature));
+            if step.awaiting_signers(&participants).is_empty() {
+                step.completed = true;
+            }
+        })
+    }
+
+    /// Apply a watcher event (`apply_event` applies audit events). `StepSigned`
+    /// records a placeholder signature, with an empty signature and message,
+    /// until the contract is next fetched.
+    pub fn apply_contract_event(&mut self, event: &ContractEvent) -> Result<Rollback, CovenantError> {
+        if event.contract_uuid() != self.uuid {
+            return Err(CovenantError::ValidationError(
+                format!("Event is for contract {}, not {}", event.contract_uuid(), self.uuid)
+            ));
+        }
+
+        match event {
+            ContractEvent::Updated(contract) => {
+                let previous = mem::replace(self, contract.as_ref().clone());
+                Ok(Rollback { contract_uuid: self.uuid.clone(), undo: Undo::Contract(Box::new(previous)) })
+            }
+            ContractEvent::StepSigned { step_id, signer, timestamp, .. } => {
+                if self.steps.iter().any(|s| s.id == *step_id && s.signatures.get(signer).is_some_and(|s| s.is_some())) {
+                    return Ok(Rollback::nothing(self));
+                }
+                self.apply_signature(step_id, signer, StepSignature {
+                    signature: String::new(),
+                    timestamp: *timestamp,
+                    message: String::new(),
+                    pub_key: None,
+                    scheme: None,
+                })
+            }
+            ContractEvent::StepCompleted { step_id, .. } => self.update_step(step_id, |step| step.completed = true),
+            ContractEvent::ContractCompleted { .. } => {
+                let previous = self.clone();
+                for step in &mut self.steps {
+                    step.completed = true;
+                }
+                Ok(Rollback { contract_uuid: self.uuid.clone(), undo: Undo::Contract(Box::new(previous)) })
+            }
+            ContractEvent::PollFailed { .. } => Ok(Rollback::nothing(self)),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Sign a step, recording the signature on `contract` before the request
+    /// is sent so readers of the mutex see it straight away. Signing order is
+    /// checked against the local copy rather than a fresh fetch. The local
+    /// copy is updated from the response, or rolled back if signing fails.
+    pub async fn sign_step_optimistic(&self, contract: &Mutex<Contract>, step_id: &str) -> Result<SignStepResponse, CovenantError> {
+        let (snapshot, payload, rollback) = {
+            let mut local = contract.lock().unwrap_or_else(|e| e.into_inner());
+            let payload = self.build_sign_request(&local.uuid, step_id)?;
+            local.check_signable()?;
+            local.check_signing_order(step_id, &payload.participant_uuid, &payload.pub_key)?;
+
+            let signer = local.steps.iter()
+                .find(|s| s.id == step_id)
+                .filter(|s| !s.signatures.contains_key(&payload.participant_uuid) && s.signatures.contains_key(&payload.pub_key))
+                .map(|_| payload.pub_key.clone())
+                .unwrap_or_else(|| payload.participant_uuid.clone());
+            let signature = StepSignature {
+                signature: payload.step_signature.clone(),
+                timestamp: payload.timestamp,
+                message: canonical::step_message(payload.timestamp, &payload.participant_uuid, &local.uuid, step_id),
+                pub_key: Some(payload.signing_key().to_string()),
+                scheme: payload.scheme,
+            };
+
+            let snapshot = local.clone();
+            let rollback = local.apply_signature(step_id, &signer, signature)?;
+            (snapshot, payload, rollback)
+        };
+
+        match self.submit_signature(&snapshot, &payload, false).await {
+            Ok((response, _)) => {
+                let mut local = contract.lock().unwrap_or_else(|e| e.into_inner());
+                let _ = local.apply(&response);
+                Ok(response)
+            }
+            Err(e) => {
+                rollback.rollback(&mut contract.lock().unwrap_or_else(|e| e.into_inner()));
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+/*!
+ * Per-call options
+ * Timeouts, cancellation and extra headers for individual requests, so UI
+ * code can abandon in-flight calls when a screen goes away.
+ */
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use crate::{CovenantClient, CovenantError};
+
+/// Options applied to every request made through `CovenantClient::with_call_options`
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    pub timeout: Option<Duration>,
+    pub cancellation_token: Option<CancellationToken>,
+    pub headers: HashMap<String, String>,
+}
+
+impl CallOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub(crate) fn apply(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        request
+    }
+
+    /// Run `future` unless the token is cancelled first
+    pub(crate) async fn cancellable<T, F>(&self, future: F) -> Result<T, CovenantError>
+    where
+        F: Future<Output = Result<T, CovenantError>>,
+    {
+        match &self.cancellation_token {
+            Some(token) => tokio::select! {
+                result = future => result,
+                _ = token.cancelled() => Err(CovenantError::Cancelled),
+            },
+            None => future.await,
+        }
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// A handle to this client whose calls all use `options`
+    pub fn with_call_options(&self, options: CallOptions) -> CovenantClient<A> {
+        let mut client = self.clone();
+        client.call_options = options;
+        client
+    }
+}
@@ -0,0 +1,138 @@
+/*!
+ * Step signing order enforcement
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, ContractStep, CovenantError};
+
+/// Who may sign a step, and when
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "sequence", rename_all = "camelCase")]
+pub enum SigningOrder {
+    /// Participants sign in any order
+    #[default]
+    Any,
+    /// Participants sign in the order they are listed on the contract
+    Sequential,
+    /// Participants sign in the given order
+    Custom(Vec<String>),
+}
+
+impl Contract {
+    /// Signing order in effect for a step
+    pub fn signing_order_for<'a>(&'a self, step: &'a ContractStep) -> &'a SigningOrder {
+        step.signing_order.as_ref().unwrap_or(&self.signing_order)
+    }
+
+    /// Participant whose turn it is to sign the step. Returns `None` when the
+    /// step is completed or unordered (any remaining participant may sign).
+    pub fn next_signer<'a>(&'a self, step: &'a ContractStep) -> Option<&'a str> {
+        if step.completed {
+            return None;
+        }
+
+        let sequence = match self.signing_order_for(step) {
+            SigningOrder::Any => return None,
+            SigningOrder::Sequential => &self.participants,
+            SigningOrder::Custom(sequence) => sequence,
+        };
+
+        sequence.iter()
+            .find(|participant| !step.signatures.get(*participant).is_some_and(|s| s.is_some()))
+            .map(|participant| participant.as_str())
+    }
+
+    /// Fail with `OutOfOrderSignature` unless the signer (by uuid or pub key) may sign now
+    pub fn check_signing_order(&self, step_id: &str, signer_uuid: &str, signer_pub_key: &str) -> Result<(), CovenantError> {
+        let step = self.steps.iter().find(|step| step.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+        match self.next_signer(step) {
+            Some(next) if next != signer_uuid && next != signer_pub_key => {
+                Err(CovenantError::OutOfOrderSignature(next.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractFixture;
+
+    fn participants() -> (String, String, String) {
+        let fixture = ContractFixture::new();
+        (fixture.participant_uuid(0), fixture.participant_uuid(1), fixture.participant_uuid(2))
+    }
+
+    /// Three-participant fixture where `signed` have signed the first step
+    fn contract(order: SigningOrder, signed: &[&str]) -> Contract {
+        let mut contract = ContractFixture::new().participants(3).build();
+        let signature = ContractFixture::new().participants(3).completed(1).build().steps[0].signatures.clone();
+        contract.signing_order = order;
+        for participant in signed {
+            contract.steps[0].signatures.insert(participant.to_string(), signature[*participant].clone());
+        }
+        contract
+    }
+
+    #[test]
+    fn unordered_steps_have_no_next_signer() {
+        let contract = contract(SigningOrder::Any, &[]);
+        assert_eq!(contract.next_signer(&contract.steps[0]), None);
+        let (_, second, _) = participants();
+        assert!(contract.check_signing_order("step-1", &second, "").is_ok());
+    }
+
+    #[test]
+    fn sequential_follows_the_participant_list() {
+        let (first, second, third) = participants();
+        let contract = contract(SigningOrder::Sequential, &[&first]);
+        assert_eq!(contract.next_signer(&contract.steps[0]), Some(second.as_str()));
+
+        assert!(contract.check_signing_order("step-1", &second, "").is_ok());
+        assert!(matches!(
+            contract.check_signing_order("step-1", &third, ""),
+            Err(CovenantError::OutOfOrderSignature(next)) if next == second
+        ));
+    }
+
+    #[test]
+    fn custom_order_and_pub_keys() {
+        let (first, second, third) = participants();
+        let contract = contract(SigningOrder::Custom(vec![third.clone(), first.clone(), second.clone()]), &[]);
+        assert_eq!(contract.next_signer(&contract.steps[0]), Some(third.as_str()));
+        assert!(contract.check_signing_order("step-1", "someone", &third).is_ok());
+        assert!(contract.check_signing_order("step-1", &first, "").is_err());
+    }
+
+    #[test]
+    fn step_orders_override_the_contract() {
+        let (first, second, _) = participants();
+        let mut contract = contract(SigningOrder::Sequential, &[]);
+        contract.steps[0].signing_order = Some(SigningOrder::Custom(vec![second.clone(), first]));
+
+        assert_eq!(contract.next_signer(&contract.steps[0]), Some(second.as_str()));
+        assert_eq!(contract.signing_order_for(&contract.steps[1]), &SigningOrder::Sequential);
+    }
+
+    #[test]
+    fn completed_and_missing_steps() {
+        let mut contract = contract(SigningOrder::Sequential, &[]);
+        contract.steps[0].completed = true;
+        assert_eq!(contract.next_signer(&contract.steps[0]), None);
+        assert!(matches!(contract.check_signing_order("step-9", "anyone", ""), Err(CovenantError::ValidationError(_))));
+    }
+
+    #[test]
+    fn orders_serialize_tagged() {
+        let order = SigningOrder::Custom(vec!["a".to_string()]);
+        let json = serde_json::to_value(&order).unwrap();
+        assert_eq!(json, serde_json::json!({ "type": "custom", "sequence": ["a"] }));
+        assert_eq!(serde_json::from_value::<SigningOrder>(json).unwrap(), order);
+        assert_eq!(serde_json::to_value(SigningOrder::Any).unwrap(), serde_json::json!({ "type": "any" }));
+    }
+}
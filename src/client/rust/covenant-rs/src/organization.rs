@@ -0,0 +1,154 @@
+/*!
+ * Organization scoping
+ * Keeps each tenant's contracts isolated for multi-tenant integrators
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Anonymous, Authenticated, Contract, ContractBuilder, ContractQuery, ContractSummary, CovenantClient, CovenantError, ServiceResponse};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Organization {
+    pub uuid: String,
+    pub name: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OrganizationMember {
+    #[serde(rename = "userUUID")]
+    pub participant_uuid: String,
+    #[serde(rename = "pubKey")]
+    pub pub_key: Option<String>,
+    pub role: String,
+    #[serde(rename = "joinedAt")]
+    pub joined_at: String,
+}
+
+/// Client view restricted to a single organization
+pub struct OrgScope<'a, A = Anonymous> {
+    client: &'a CovenantClient<A>,
+    org_uuid: String,
+}
+
+impl<A> CovenantClient<A> {
+    /// Scope listing, creation, and membership queries to an organization
+    pub fn for_org<S: Into<String>>(&self, org_uuid: S) -> OrgScope<'_, A> {
+        OrgScope {
+            client: self,
+            org_uuid: org_uuid.into(),
+        }
+    }
+
+    /// List organizations a participant belongs to
+    pub async fn list_organizations(&self, participant_uuid: &str) -> Result<Vec<Organization>, CovenantError> {
+        let url = format!("{}/orgs", self.base_url);
+        let service_response: ServiceResponse<Vec<Organization>> = self.send_json(self.signed_read(self.client.get(&url).query(&[("member", participant_uuid)]), None)?).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "List organizations failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No organizations data returned".to_string())
+        )
+    }
+}
+
+impl CovenantClient<Authenticated> {
+    /// Get organizations for current user
+    pub async fn get_my_organizations(&self) -> Result<Vec<Organization>, CovenantError> {
+        self.list_organizations(self.identity().uuid()).await
+    }
+}
+
+impl<A> OrgScope<'_, A> {
+    pub fn org_uuid(&self) -> &str {
+        &self.org_uuid
+    }
+
+    /// Get organization details
+    pub async fn get_organization(&self) -> Result<Organization, CovenantError> {
+        let url = format!("{}/org/{}", self.client.base_url, self.org_uuid);
+        let request = self.client.signed_read(self.client.client.get(&url), None)?;
+        let service_response: ServiceResponse<Organization> = self.client.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Organization not found".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No organization data returned".to_string())
+        )
+    }
+
+    /// Get contract by UUID, rejecting contracts owned by other organizations
+    pub async fn get_contract(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        let contract = self.client.get_contract(uuid).await?;
+
+        if contract.org_uuid.as_deref() != Some(self.org_uuid.as_str()) {
+            return Err(CovenantError::ValidationError(
+                format!("Contract {} does not belong to organization {}", uuid, self.org_uuid)
+            ));
+        }
+
+        Ok(contract)
+    }
+
+    /// List the organization's contracts (optionally filtered by participant)
+    pub async fn list_contracts(&self, participant_uuid: Option<&str>) -> Result<Vec<ContractSummary>, CovenantError> {
+        let mut query = ContractQuery::new();
+        if let Some(participant) = participant_uuid {
+            query = query.participant(participant);
+        }
+
+        self.search_contracts(&query).await
+    }
+
+    /// Search the organization's contracts; any org filter on the query is overridden
+    pub async fn search_contracts(&self, query: &ContractQuery) -> Result<Vec<ContractSummary>, CovenantError> {
+        let query = query.clone().org_uuid(self.org_uuid.clone());
+        self.client.search_contracts(&query).await
+    }
+
+    /// List organization members
+    pub async fn members(&self) -> Result<Vec<OrganizationMember>, CovenantError> {
+        let url = format!("{}/org/{}/members", self.client.base_url, self.org_uuid);
+        let request = self.client.signed_read(self.client.client.get(&url), None)?;
+        let service_response: ServiceResponse<Vec<OrganizationMember>> = self.client.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "List members failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No members data returned".to_string())
+        )
+    }
+
+    /// Check whether a participant (uuid or pub key) belongs to the organization
+    pub async fn is_member(&self, participant: &str) -> Result<bool, CovenantError> {
+        let members = self.members().await?;
+
+        Ok(members.iter().any(|member| {
+            member.participant_uuid == participant || member.pub_key.as_deref() == Some(participant)
+        }))
+    }
+}
+
+impl OrgScope<'_, Authenticated> {
+    /// Create contract owned by this organization
+    pub async fn create_contract(&self, contract: &ContractBuilder) -> Result<Contract, CovenantError> {
+        let contract = contract.clone().org_uuid(self.org_uuid.clone());
+        self.client.create_contract(&contract).await
+    }
+}
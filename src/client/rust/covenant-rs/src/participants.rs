@@ -0,0 +1,300 @@
+/*!
+ * Participant key books
+ * Named public keys imported from and exported to CSV, so onboarding
+ * spreadsheets can feed `ContractBuilder::participants_from`.
+ *
+ * ```text
+ * name,pubKey,scheme
+ * alice,02a1b2...,secp256k1
+ * bob,9f3c...,ed25519
+ * ```
+ *
+ * `scheme` is optional and defaults to secp256k1. Headers are matched
+ * case-insensitively (`pub_key` and `publicKey` are accepted too) and other
+ * columns are ignored. Fields may be double-quoted.
+ */
+
+use std::io::{Read, Write};
+
+use crate::{CovenantError, SignatureScheme};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticipantEntry {
+    pub name: String,
+    pub pub_key: String,
+    pub scheme: SignatureScheme,
+}
+
+/// Participants by name, in insertion order
+#[derive(Debug, Clone, Default)]
+pub struct ParticipantBook {
+    entries: Vec<ParticipantEntry>,
+}
+
+impl ParticipantBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a participant, rejecting malformed keys and duplicate names or keys
+    pub fn insert<N: Into<String>, K: Into<String>>(&mut self, name: N, pub_key: K, scheme: SignatureScheme) -> Result<(), CovenantError> {
+        let name = name.into().trim().to_string();
+        let pub_key = pub_key.into().trim().to_lowercase();
+
+        if name.is_empty() {
+            return Err(CovenantError::ValidationError("Participant name is required".to_string()));
+        }
+        validate_pub_key(&pub_key, scheme)
+            .map_err(|e| CovenantError::ValidationError(format!("{}: {}", name, e)))?;
+        if self.get(&name).is_some() {
+            return Err(CovenantError::ValidationError(format!("Duplicate participant name {}", name)));
+        }
+        if let Some(existing) = self.entries.iter().find(|entry| entry.pub_key == pub_key) {
+            return Err(CovenantError::ValidationError(format!("{} has the same key as {}", name, existing.name)));
+        }
+
+        self.entries.push(ParticipantEntry { name, pub_key, scheme });
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ParticipantEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn key(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|entry| entry.pub_key.as_str())
+    }
+
+    /// Name recorded for a key
+    pub fn name_for(&self, pub_key: &str) -> Option<&str> {
+        let pub_key = pub_key.to_lowercase();
+        self.entries.iter().find(|entry| entry.pub_key == pub_key).map(|entry| entry.name.as_str())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<ParticipantEntry> {
+        let index = self.entries.iter().position(|entry| entry.name == name)?;
+        Some(self.entries.remove(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ParticipantEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Keys for the given names, failing on the first unknown name
+    pub fn resolve<I, S>(&self, names: I) -> Result<Vec<String>, CovenantError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        names.into_iter()
+            .map(|name| self.key(name.as_ref()).map(|key| key.to_string()).ok_or_else(||
+                CovenantError::ValidationError(format!("Unknown participant {}", name.as_ref()))
+            ))
+            .collect()
+    }
+}
+
+/// Check a public key's encoding for its scheme: compressed secp256k1 keys
+/// are 66 hex characters starting `02` or `03`, Ed25519 keys 64 hex characters
+pub fn validate_pub_key(pub_key: &str, scheme: SignatureScheme) -> Result<(), String> {
+    if !pub_key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("public key is not hex".to_string());
+    }
+
+    match scheme {
+        SignatureScheme::Secp256k1 if pub_key.len() != 66 => {
+            Err(format!("secp256k1 public keys are 66 hex characters, got {}", pub_key.len()))
+        }
+        SignatureScheme::Secp256k1 if !pub_key.starts_with("02") && !pub_key.starts_with("03") => {
+            Err("secp256k1 public keys must be compressed (02 or 03 prefix)".to_string())
+        }
+        SignatureScheme::Ed25519 if pub_key.len() != 64 => {
+            Err(format!("ed25519 public keys are 64 hex characters, got {}", pub_key.len()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Read a participant book from CSV. Every malformed row is reported, not just the first.
+pub fn import_csv<R: Read>(mut reader: R) -> Result<ParticipantBook, CovenantError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+
+    let mut rows = parse_csv(&text)?.into_iter()
+        .enumerate()
+        .filter(|(_, row)| row.iter().any(|field| !field.trim().is_empty()));
+
+    let (_, header) = rows.next()
+        .ok_or_else(|| CovenantError::ValidationError("CSV has no header row".to_string()))?;
+    let column = |names: &[&str]| header.iter()
+        .position(|h| names.iter().any(|name| h.trim().eq_ignore_ascii_case(name)));
+
+    let name_column = column(&["name"])
+        .ok_or_else(|| CovenantError::ValidationError("CSV has no name column".to_string()))?;
+    let key_column = column(&["pubKey", "pub_key", "publicKey", "public_key"])
+        .ok_or_else(|| CovenantError::ValidationError("CSV has no pubKey column".to_string()))?;
+    let scheme_column = column(&["scheme"]);
+
+    let mut book = ParticipantBook::new();
+    let mut problems = Vec::new();
+
+    for (index, row) in rows {
+        let field = |column: usize| row.get(column).map(|f| f.trim()).unwrap_or("");
+        let scheme = match scheme_column.map(field).unwrap_or("") {
+            "" => Ok(SignatureScheme::Secp256k1),
+            name => parse_scheme(name),
+        };
+
+        let result = scheme.and_then(|scheme| book.insert(field(name_column), field(key_column), scheme));
+        if let Err(e) = result {
+            let message = match e {
+                CovenantError::ValidationError(message) => message,
+                other => other.to_string(),
+            };
+            problems.push(format!("row {}: {}", index + 1, message));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(CovenantError::ValidationError(problems.join("; ")));
+    }
+
+    Ok(book)
+}
+
+/// Write a participant book as CSV with a `name,pubKey,scheme` header
+pub fn export_csv<W: Write>(book: &ParticipantBook, mut writer: W) -> Result<(), CovenantError> {
+    writeln!(writer, "name,pubKey,scheme")?;
+    for entry in book.iter() {
+        writeln!(writer, "{},{},{}", quote(&entry.name), entry.pub_key, entry.scheme.name())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn parse_scheme(name: &str) -> Result<SignatureScheme, CovenantError> {
+    [SignatureScheme::Secp256k1, SignatureScheme::Ed25519].into_iter()
+        .find(|scheme| scheme.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| CovenantError::ValidationError(format!("Unknown signature scheme {}", name)))
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split CSV text into rows of fields, honouring double-quoted fields
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, CovenantError> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(CovenantError::ValidationError("CSV has an unterminated quoted field".to_string()));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "02a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+    const BOB: &str = "9f3c1d2e3f405162738495a6b7c8d9eaf0102132435465768798a9bacbdcedfe";
+
+    fn book() -> ParticipantBook {
+        let mut book = ParticipantBook::new();
+        book.insert("alice", ALICE, SignatureScheme::Secp256k1).unwrap();
+        book.insert("Bob \"the builder\", Jr", BOB, SignatureScheme::Ed25519).unwrap();
+        book
+    }
+
+    #[test]
+    fn export_quotes_and_import_round_trips() {
+        let mut csv = Vec::new();
+        export_csv(&book(), &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv, format!(
+            "name,pubKey,scheme\nalice,{},secp256k1\n\"Bob \"\"the builder\"\", Jr\",{},ed25519\n", ALICE, BOB
+        ));
+
+        let imported = import_csv(csv.as_bytes()).unwrap();
+        assert_eq!(imported.iter().collect::<Vec<_>>(), book().iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn headers_are_flexible() {
+        let csv = format!("\u{feff}Email,NAME,Public_Key\r\na@example.com,alice,{}\r\n\r\n,,\n", ALICE.to_uppercase());
+        let book = import_csv(csv.as_bytes()).unwrap();
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.key("alice"), Some(ALICE));
+        assert_eq!(book.get("alice").unwrap().scheme, SignatureScheme::Secp256k1);
+        assert_eq!(book.name_for(&ALICE.to_uppercase()), Some("alice"));
+    }
+
+    #[test]
+    fn missing_headers_are_rejected() {
+        assert!(import_csv("".as_bytes()).is_err());
+        assert!(import_csv("name,scheme\nalice,secp256k1\n".as_bytes()).is_err());
+        assert!(import_csv(format!("pubKey\n{}\n", ALICE).as_bytes()).is_err());
+        assert!(import_csv("name,pubKey\n\"alice,".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn every_bad_row_is_reported() {
+        let csv = format!("name,pubKey,scheme\nalice,{ALICE},\nalice,{BOB},ed25519\ncarol,abc,\ndave,{BOB},rsa\n");
+        let Err(CovenantError::ValidationError(message)) = import_csv(csv.as_bytes()) else { panic!("import should fail") };
+        assert!(message.contains("row 3: Duplicate participant name alice"), "{}", message);
+        assert!(message.contains("row 4: carol"), "{}", message);
+        assert!(message.contains("row 5: Unknown signature scheme rsa"), "{}", message);
+    }
+
+    #[test]
+    fn keys_are_validated_per_scheme() {
+        assert!(validate_pub_key(ALICE, SignatureScheme::Secp256k1).is_ok());
+        assert!(validate_pub_key(BOB, SignatureScheme::Ed25519).is_ok());
+        assert!(validate_pub_key(BOB, SignatureScheme::Secp256k1).is_err());
+        assert!(validate_pub_key(&format!("04{}", &ALICE[2..]), SignatureScheme::Secp256k1).is_err());
+        assert!(validate_pub_key("zz", SignatureScheme::Ed25519).is_err());
+
+        let book = book();
+        assert_eq!(book.resolve(["alice"]).unwrap(), [ALICE]);
+        assert!(book.resolve(["alice", "mallory"]).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+/*!
+ * Steps awaiting the current identity's signature
+ * Uses the service's paged pending endpoint when it has one, and otherwise
+ * scans the caller's open contracts concurrently.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Authenticated, Contract, ContractStep, CovenantClient, CovenantError, ServiceResponse};
+
+const PAGE_SIZE: usize = 100;
+
+/// A step awaiting the caller's signature, with its contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PendingStep {
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    #[serde(rename = "contractTitle")]
+    pub contract_title: String,
+    pub step: ContractStep,
+}
+
+/// One page of the service's pending endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PendingPage {
+    #[serde(default)]
+    pub pending: Vec<PendingStep>,
+    pub offset: usize,
+    pub total: usize,
+}
+
+impl CovenantClient<Authenticated> {
+    /// Every step awaiting the current identity, soonest deadline first, then oldest
+    pub async fn get_my_pending_steps(&self) -> Result<Vec<PendingStep>, CovenantError> {
+        let sessionless = self.identity();
+        let mut pending = match self.fetch_pending_pages(sessionless.uuid()).await? {
+            Some(pending) => pending,
+            None => self.scan_pending(sessionless.uuid(), sessionless.public_key()).await?,
+        };
+
+        pending.sort_by(|a, b| {
+            let deadline = |p: &PendingStep| p.step.deadline_time();
+            match (deadline(a), deadline(b)) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| crate::parse_timestamp(&a.step.created_at).cmp(&crate::parse_timestamp(&b.step.created_at)))
+        });
+
+        Ok(pending)
+    }
+
+    /// Walk the pending endpoint; `None` if the service doesn't have one
+    async fn fetch_pending_pages(&self, user_uuid: &str) -> Result<Option<Vec<PendingStep>>, CovenantError> {
+        let url = format!("{}/user/{}/pending", self.base_url, user_uuid);
+        let mut pending = Vec::new();
+
+        loop {
+            let params = [
+                ("offset", pending.len().to_string()),
+                ("limit", PAGE_SIZE.to_string()),
+            ];
+            let response = self.send(self.signed_read(self.client.get(&url).query(&params), None)?).await?;
+
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            let service_response: ServiceResponse<PendingPage> = crate::format::decode_response(response).await?;
+
+            if !service_response.success {
+                return Err(CovenantError::ServiceError(
+                    service_response.error.unwrap_or_else(|| "Get pending steps failed".to_string())
+                ));
+            }
+
+            let page = service_response.data.ok_or_else(||
+                CovenantError::ServiceError("No pending data returned".to_string())
+            )?;
+
+            let exhausted = page.pending.is_empty();
+            pending.extend(page.pending);
+            if exhausted || pending.len() >= page.total {
+                return Ok(Some(pending));
+            }
+        }
+    }
+
+    /// Fetch every open contract concurrently and pick out steps awaiting us
+    async fn scan_pending(&self, user_uuid: &str, pub_key: &str) -> Result<Vec<PendingStep>, CovenantError> {
+        let summaries = self.list_contracts(Some(user_uuid)).await?;
+        let open: Vec<&str> = summaries.iter()
+            .filter(|summary| summary.completed_steps < summary.step_count)
+            .map(|summary| summary.uuid.as_str())
+            .collect();
+
+        let contracts = self.get_contracts(&open).await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(contracts.iter()
+            .flat_map(|contract| pending_for(contract, user_uuid, pub_key))
+            .collect())
+    }
+}
+
+/// Steps of `contract` the identity can sign right now
+fn pending_for(contract: &Contract, user_uuid: &str, pub_key: &str) -> Vec<PendingStep> {
+    contract.steps.iter()
+        .filter(|step| !step.completed)
+        .filter(|step| {
+            step.awaiting_signers(&contract.participants).iter().any(|signer| signer == user_uuid || signer == pub_key)
+        })
+        .filter(|step| match contract.next_signer(step) {
+            Some(next) => next == user_uuid || next == pub_key,
+            None => true,
+        })
+        .map(|step| PendingStep {
+            contract_uuid: contract.uuid.clone(),
+            contract_title: contract.title.clone(),
+            step: step.clone(),
+        })
+        .collect()
+}
@@ -0,0 +1,128 @@
+/*!
+ * Step groups (phases)
+ * Long contracts read better as a handful of named phases than as one
+ * flat list of steps.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, ContractProgress, ContractStep, StepBuilder};
+
+/// A named group of consecutive steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Phase {
+    pub name: String,
+    #[serde(rename = "stepIds", default)]
+    pub step_ids: Vec<String>,
+}
+
+/// Completion of a single phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PhaseProgress {
+    pub name: String,
+    #[serde(rename = "totalSteps")]
+    pub total_steps: usize,
+    #[serde(rename = "completedSteps")]
+    pub completed_steps: usize,
+    #[serde(rename = "progressPercent")]
+    pub progress_percent: f64,
+    #[serde(rename = "weightedProgressPercent", default)]
+    pub weighted_progress_percent: f64,
+    #[serde(rename = "isComplete")]
+    pub is_complete: bool,
+}
+
+/// Collects the steps of one phase for `ContractBuilder::phase`
+#[derive(Debug, Clone, Default)]
+pub struct PhaseBuilder {
+    pub(crate) steps: Vec<StepBuilder>,
+}
+
+impl PhaseBuilder {
+    pub fn step<S: Into<String>>(mut self, description: S) -> Self {
+        self.steps.push(StepBuilder::new(description));
+        self
+    }
+
+    pub fn step_with_magic<S: Into<String>>(mut self, description: S, magic_spell: serde_json::Value) -> Self {
+        self.steps.push(StepBuilder::new(description).magic_spell(magic_spell));
+        self
+    }
+
+    /// Add a step configured through a `StepBuilder`
+    pub fn step_with<S, F>(mut self, description: S, configure: F) -> Self
+    where
+        S: Into<String>,
+        F: FnOnce(StepBuilder) -> StepBuilder,
+    {
+        self.steps.push(configure(StepBuilder::new(description)));
+        self
+    }
+}
+
+impl Contract {
+    /// Steps belonging to the named phase, in contract order
+    pub fn phase_steps(&self, name: &str) -> Vec<&ContractStep> {
+        self.steps.iter()
+            .filter(|step| step.phase.as_deref() == Some(name))
+            .collect()
+    }
+
+    /// Progress of every phase, in the order the phases were declared
+    pub fn phase_progress(&self) -> Vec<PhaseProgress> {
+        self.phases.iter().map(|phase| {
+            let steps = self.phase_steps(&phase.name);
+            let total_steps = steps.len();
+            let completed_steps = steps.iter().filter(|step| step.completed).count();
+
+            PhaseProgress {
+                name: phase.name.clone(),
+                total_steps,
+                completed_steps,
+                progress_percent: if total_steps > 0 {
+                    (completed_steps as f64 / total_steps as f64) * 100.0
+                } else {
+                    0.0
+                },
+                weighted_progress_percent: weighted_percent(&steps),
+                is_complete: completed_steps == total_steps,
+            }
+        }).collect()
+    }
+
+    /// Overall progress, with a breakdown by phase
+    pub fn progress(&self) -> ContractProgress {
+        let total_steps = self.steps.len();
+        let completed_steps = self.steps.iter().filter(|step| step.completed).count();
+
+        ContractProgress {
+            total_steps,
+            completed_steps,
+            progress_percent: if total_steps > 0 {
+                (completed_steps as f64 / total_steps as f64) * 100.0
+            } else {
+                0.0
+            },
+            weighted_progress_percent: weighted_percent(&self.steps.iter().collect::<Vec<_>>()),
+            participant_count: self.participants.len(),
+            is_complete: completed_steps == total_steps,
+            phases: self.phase_progress(),
+        }
+    }
+}
+
+/// Share of total step weight that is completed, as a percentage
+pub(crate) fn weighted_percent(steps: &[&ContractStep]) -> f64 {
+    let total: f64 = steps.iter().map(|step| step.effective_weight()).sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let completed: f64 = steps.iter()
+        .filter(|step| step.completed)
+        .map(|step| step.effective_weight())
+        .sum();
+    (completed / total) * 100.0
+}
@@ -0,0 +1,270 @@
+/*!
+ * Portfolio progress
+ * Rolls many contracts up into one picture: overall step completion, the
+ * same broken down by tag, product and participant, and a daily burndown of
+ * remaining steps. A step counts as completed when its last signature landed,
+ * falling back to its `completedAt`; completed steps with neither are counted
+ * from the first day of the burndown.
+ */
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::digest::escape_html;
+use crate::{parse_timestamp, Contract, ContractStep};
+
+/// Tags shown in the SVG overview, largest first
+const SVG_MAX_ROWS: usize = 10;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProgressRollup {
+    pub contracts: usize,
+    #[serde(rename = "completedContracts")]
+    pub completed_contracts: usize,
+    #[serde(rename = "totalSteps")]
+    pub total_steps: usize,
+    #[serde(rename = "completedSteps")]
+    pub completed_steps: usize,
+}
+
+impl ProgressRollup {
+    pub fn progress_percent(&self) -> f64 {
+        if self.total_steps == 0 {
+            return 0.0;
+        }
+        self.completed_steps as f64 / self.total_steps as f64 * 100.0
+    }
+
+    fn add(&mut self, contract: &Contract) {
+        let completed = contract.steps.iter().filter(|step| step.completed).count();
+        self.contracts += 1;
+        self.total_steps += contract.steps.len();
+        self.completed_steps += completed;
+        if completed == contract.steps.len() {
+            self.completed_contracts += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    /// Steps completed by the end of the day
+    pub completed: usize,
+    pub remaining: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PortfolioProgress {
+    pub overall: ProgressRollup,
+    #[serde(rename = "byTag")]
+    pub by_tag: BTreeMap<String, ProgressRollup>,
+    #[serde(rename = "byProduct")]
+    pub by_product: BTreeMap<String, ProgressRollup>,
+    #[serde(rename = "byParticipant")]
+    pub by_participant: BTreeMap<String, ProgressRollup>,
+    /// One point per day, from the first contract's creation to the latest completion
+    pub burndown: Vec<BurndownPoint>,
+}
+
+/// When a completed step was completed
+fn completion_time(step: &ContractStep) -> Option<DateTime<Utc>> {
+    step.signatures.values()
+        .flatten()
+        .filter_map(|signature| DateTime::from_timestamp_millis(signature.timestamp))
+        .max()
+        .or_else(|| step.completed_time())
+}
+
+/// Roll up progress across `contracts`
+pub fn progress(contracts: &[Contract]) -> PortfolioProgress {
+    let mut portfolio = PortfolioProgress::default();
+
+    for contract in contracts {
+        portfolio.overall.add(contract);
+        for tag in &contract.tags {
+            portfolio.by_tag.entry(tag.clone()).or_default().add(contract);
+        }
+        if let Some(product_uuid) = &contract.product_uuid {
+            portfolio.by_product.entry(product_uuid.clone()).or_default().add(contract);
+        }
+        for participant in &contract.participants {
+            portfolio.by_participant.entry(participant.clone()).or_default().add(contract);
+        }
+    }
+
+    portfolio.burndown = burndown(contracts, portfolio.overall.total_steps);
+    portfolio
+}
+
+fn burndown(contracts: &[Contract], total_steps: usize) -> Vec<BurndownPoint> {
+    let completions: Vec<Option<NaiveDate>> = contracts.iter()
+        .flat_map(|contract| contract.steps.iter())
+        .filter(|step| step.completed)
+        .map(|step| completion_time(step).map(|time| time.date_naive()))
+        .collect();
+
+    let created = contracts.iter()
+        .filter_map(|contract| parse_timestamp(&contract.created_at))
+        .map(|time| time.date_naive());
+    let Some(first) = created.chain(completions.iter().flatten().copied()).min() else {
+        return Vec::new();
+    };
+    let last = completions.iter().flatten().copied().max().unwrap_or(first).max(first);
+
+    let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for date in &completions {
+        *per_day.entry(date.unwrap_or(first)).or_default() += 1;
+    }
+
+    let mut points = Vec::new();
+    let mut completed = 0;
+    let mut date = first;
+    while date <= last {
+        completed += per_day.get(&date).copied().unwrap_or(0);
+        points.push(BurndownPoint { date, completed, remaining: total_steps.saturating_sub(completed) });
+        match date.checked_add_days(Days::new(1)) {
+            Some(next) => date = next,
+            None => break,
+        }
+    }
+    points
+}
+
+/// One-page SVG overview: totals, the burndown and the largest tags
+pub fn render_portfolio_svg(portfolio: &PortfolioProgress) -> String {
+    let mut tags: Vec<(&String, &ProgressRollup)> = portfolio.by_tag.iter().collect();
+    tags.sort_by(|a, b| b.1.total_steps.cmp(&a.1.total_steps).then_with(|| a.0.cmp(b.0)));
+    tags.truncate(SVG_MAX_ROWS);
+
+    let (chart_x, chart_y, chart_width, chart_height) = (40.0, 80.0, 720.0, 200.0);
+    let rows_y = chart_y + chart_height + 50.0;
+    let height = rows_y + tags.len() as f64 * 28.0 + 20.0;
+
+    let overall = &portfolio.overall;
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="{height}" viewBox="0 0 800 {height}" font-family="sans-serif">"##
+    );
+    svg.push_str(&format!(r##"<rect width="800" height="{}" fill="#fffdf6"/>"##, height));
+    svg.push_str(&format!(
+        r##"<text x="40" y="40" font-size="20" fill="#4b3f72">{} contracts ({} complete)</text>"##,
+        overall.contracts, overall.completed_contracts
+    ));
+    svg.push_str(&format!(
+        r##"<text x="40" y="64" font-size="14" fill="#555">{} of {} steps signed off ({:.0}%)</text>"##,
+        overall.completed_steps, overall.total_steps, overall.progress_percent()
+    ));
+
+    svg.push_str(&format!(
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#ccc"/>"##,
+        chart_x, chart_y, chart_width, chart_height
+    ));
+    if let (Some(first), Some(last)) = (portfolio.burndown.first(), portfolio.burndown.last()) {
+        let days = (portfolio.burndown.len().max(2) - 1) as f64;
+        let scale = overall.total_steps.max(1) as f64;
+        let points: Vec<String> = portfolio.burndown.iter().enumerate().map(|(day, point)| {
+            let x = chart_x + day as f64 / days * chart_width;
+            let y = chart_y + chart_height - point.remaining as f64 / scale * chart_height;
+            format!("{:.1},{:.1}", x, y)
+        }).collect();
+
+        svg.push_str(&format!(
+            r##"<polyline points="{}" fill="none" stroke="#4b3f72" stroke-width="2"/>"##,
+            points.join(" ")
+        ));
+        svg.push_str(&format!(
+            r##"<text x="{}" y="{}" font-size="11" fill="#555">{}</text>"##,
+            chart_x, chart_y + chart_height + 16.0, first.date
+        ));
+        svg.push_str(&format!(
+            r##"<text x="{}" y="{}" font-size="11" fill="#555" text-anchor="end">{} ({} remaining)</text>"##,
+            chart_x + chart_width, chart_y + chart_height + 16.0, last.date, last.remaining
+        ));
+    }
+
+    for (row, (tag, rollup)) in tags.iter().enumerate() {
+        let y = rows_y + row as f64 * 28.0;
+        let bar = rollup.progress_percent() / 100.0 * 480.0;
+        svg.push_str(&format!(
+            r##"<text x="40" y="{:.1}" font-size="13" fill="#333">{}</text>"##,
+            y + 14.0, escape_html(tag)
+        ));
+        svg.push_str(&format!(r##"<rect x="200" y="{:.1}" width="480" height="16" fill="#eee"/>"##, y));
+        svg.push_str(&format!(r##"<rect x="200" y="{:.1}" width="{:.1}" height="16" fill="#4b3f72"/>"##, y, bar));
+        svg.push_str(&format!(
+            r##"<text x="760" y="{:.1}" font-size="12" fill="#555" text-anchor="end">{}/{}</text>"##,
+            y + 13.0, rollup.completed_steps, rollup.total_steps
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractFixture;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn rollups_by_tag_product_and_participant() {
+        let mut done = ContractFixture::new().tag("lease").tag("urgent").completed(3).build();
+        done.product_uuid = Some("product".to_string());
+        let open = ContractFixture::new().seed(1).participants(1).tag("lease").completed(1).build();
+
+        let portfolio = progress(&[done.clone(), open.clone()]);
+        assert_eq!(portfolio.overall, ProgressRollup { contracts: 2, completed_contracts: 1, total_steps: 6, completed_steps: 4 });
+        assert_eq!(portfolio.by_tag["lease"].completed_steps, 4);
+        assert_eq!(portfolio.by_tag["urgent"], ProgressRollup { contracts: 1, completed_contracts: 1, total_steps: 3, completed_steps: 3 });
+        assert_eq!(portfolio.by_product["product"].contracts, 1);
+        assert_eq!(portfolio.by_participant[&done.participants[1]].contracts, 1);
+        assert_eq!(portfolio.by_participant.len(), 3);
+        assert!((portfolio.overall.progress_percent() - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn burndown_counts_completions_per_day() {
+        let first = ContractFixture::new().completed(2).build();
+        let later = ContractFixture::new().seed(2).completed(1).build();
+
+        let burndown = progress(&[first, later]).burndown;
+        assert_eq!(burndown, [
+            BurndownPoint { date: day(1), completed: 2, remaining: 4 },
+            BurndownPoint { date: day(2), completed: 2, remaining: 4 },
+            BurndownPoint { date: day(3), completed: 3, remaining: 3 },
+        ]);
+    }
+
+    #[test]
+    fn untimed_completions_count_from_the_first_day() {
+        let mut contract = ContractFixture::new().seed(4).build();
+        contract.steps[0].completed = true;
+        let other = ContractFixture::new().seed(2).build();
+
+        let burndown = progress(&[contract, other]).burndown;
+        assert_eq!(burndown.first(), Some(&BurndownPoint { date: day(3), completed: 1, remaining: 5 }));
+        assert_eq!(burndown.len(), 1);
+    }
+
+    #[test]
+    fn empty_portfolios() {
+        let portfolio = progress(&[]);
+        assert!(portfolio.burndown.is_empty());
+        assert_eq!(portfolio.overall.progress_percent(), 0.0);
+        assert!(render_portfolio_svg(&portfolio).ends_with("</svg>"));
+    }
+
+    #[test]
+    fn svg_escapes_tags() {
+        let contract = ContractFixture::new().tag("<b>&").completed(1).build();
+        let svg = render_portfolio_svg(&progress(&[contract]));
+        assert!(svg.contains("&lt;b&gt;&amp;"));
+        assert!(svg.contains("1 of 3 steps signed off (33%)"));
+    }
+}
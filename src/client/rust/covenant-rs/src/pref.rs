@@ -0,0 +1,176 @@
+/*!
+ * Display preferences (requires the `pref` feature)
+ * Stores a user's covenant display preferences in the Planet Nine Pref
+ * service, keyed by their sessionless uuid under the `covenant` hash. Once a
+ * client has a Pref service attached, SVG helpers fall back to the user's
+ * preferred theme when no theme is given.
+ */
+
+use std::sync::{Arc, Mutex};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{format, Authenticated, CovenantClient, CovenantError, SvgOptions};
+
+/// Pref hash covenant preferences are stored under
+pub const PREF_HASH: &str = "covenant";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum DigestFrequency {
+    Daily,
+    Weekly,
+    Never,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DisplayPreferences {
+    /// Default SVG theme
+    pub theme: Option<String>,
+    /// BCP 47 tag, e.g. "en-US"
+    pub locale: Option<String>,
+    #[serde(rename = "digestFrequency")]
+    pub digest_frequency: Option<DigestFrequency>,
+}
+
+/// Connection to a Pref service
+#[derive(Debug, Clone)]
+pub struct PrefService {
+    base_url: String,
+    client: Client,
+}
+
+impl PrefService {
+    pub fn new<S: Into<String>>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: Client::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PrefRecord {
+    #[serde(default)]
+    preferences: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Attached service plus the current user's preferences once fetched
+#[derive(Debug, Clone)]
+pub(crate) struct PrefLink {
+    service: PrefService,
+    cached: Arc<Mutex<Option<DisplayPreferences>>>,
+}
+
+impl PrefLink {
+    fn cached(&self) -> Option<DisplayPreferences> {
+        self.cached.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn cache(&self, preferences: DisplayPreferences) {
+        *self.cached.lock().unwrap_or_else(|e| e.into_inner()) = Some(preferences);
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Read and write display preferences through a Pref service
+    pub fn with_pref_service(mut self, service: PrefService) -> Self {
+        self.pref = Some(PrefLink {
+            service,
+            cached: Arc::new(Mutex::new(None)),
+        });
+        self
+    }
+
+    /// Fill in the preferred theme when `options` has none. Lookup failures
+    /// leave the options unchanged, so rendering never fails on Pref.
+    pub(crate) async fn with_preferred_theme(&self, options: SvgOptions) -> SvgOptions {
+        if options.theme.is_some() {
+            return options;
+        }
+
+        match self.cached_preferences().await {
+            Some(preferences) => SvgOptions { theme: preferences.theme, ..options },
+            None => options,
+        }
+    }
+
+    async fn cached_preferences(&self) -> Option<DisplayPreferences> {
+        let link = self.pref.as_ref()?;
+        self.signer.as_ref()?;
+        self.load_preferences(link).await.ok()
+    }
+
+    async fn load_preferences(&self, link: &PrefLink) -> Result<DisplayPreferences, CovenantError> {
+        if let Some(preferences) = link.cached() {
+            return Ok(preferences);
+        }
+
+        let preferences = self.fetch_preferences(&link.service).await?;
+        link.cache(preferences.clone());
+        Ok(preferences)
+    }
+
+    /// Signed `timestamp + uuid + hash` as Pref expects
+    fn pref_auth(&self) -> Result<(i64, String, String), CovenantError> {
+        let signer = self.signer.as_deref()
+            .ok_or_else(|| CovenantError::ValidationError("Preferences need an identity".to_string()))?;
+        let timestamp = self.signing_timestamp()?;
+        let signature = signer.sign(&format!("{}{}{}", timestamp, signer.uuid(), PREF_HASH))?;
+        Ok((timestamp, signer.uuid().to_string(), signature))
+    }
+
+    async fn fetch_preferences(&self, service: &PrefService) -> Result<DisplayPreferences, CovenantError> {
+        let (timestamp, uuid, signature) = self.pref_auth()?;
+        let url = format!("{}/user/{}/preferences", service.base_url, uuid);
+        let response = service.client.get(&url)
+            .query(&[("timestamp", timestamp.to_string()), ("hash", PREF_HASH.to_string()), ("signature", signature)])
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(DisplayPreferences::default());
+        }
+
+        let record: PrefRecord = format::decode_response(response).await?;
+        Ok(serde_json::from_value(serde_json::Value::Object(record.preferences))?)
+    }
+}
+
+impl CovenantClient<Authenticated> {
+    /// The user's display preferences (empty when none are stored)
+    pub async fn display_preferences(&self) -> Result<DisplayPreferences, CovenantError> {
+        let link = self.pref.as_ref()
+            .ok_or_else(|| CovenantError::ValidationError("No Pref service attached".to_string()))?;
+        self.load_preferences(link).await
+    }
+
+    /// Replace the user's display preferences
+    pub async fn set_display_preferences(&self, preferences: &DisplayPreferences) -> Result<(), CovenantError> {
+        let link = self.pref.as_ref()
+            .ok_or_else(|| CovenantError::ValidationError("No Pref service attached".to_string()))?;
+        let (timestamp, uuid, signature) = self.pref_auth()?;
+
+        let url = format!("{}/user/{}/preferences", link.service.base_url, uuid);
+        let response = link.service.client.put(&url)
+            .json(&serde_json::json!({
+                "timestamp": timestamp.to_string(),
+                "uuid": uuid,
+                "hash": PREF_HASH,
+                "preferences": preferences,
+                "signature": signature
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(CovenantError::ServiceError(format!("Pref service returned {}", response.status())));
+        }
+
+        link.cache(preferences.clone());
+        Ok(())
+    }
+}
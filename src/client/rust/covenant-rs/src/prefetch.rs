@@ -0,0 +1,316 @@
+/*!
+ * Background prefetching into a local content cache
+ * Warms the cache with a contract, its SVG and its attachments so detail
+ * screens open instantly, including offline. Downloads run one at a time
+ * with an optional pause between them to stay out of the way of
+ * foreground traffic.
+ *
+ * Attachments are the URLs listed under the contract's `"attachments"`
+ * metadata key, either as strings or as objects with a `"url"` field.
+ */
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "search")]
+use crate::search::SearchIndex;
+use crate::{Contract, CovenantError, SvgOptions};
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+
+/// Shared cache of fetched contracts, SVGs and attachments, in memory and
+/// optionally mirrored to a directory
+#[derive(Debug, Clone, Default)]
+pub struct ContentCache {
+    entries: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    dir: Option<PathBuf>,
+    #[cfg(feature = "search")]
+    index: SearchIndex,
+}
+
+impl ContentCache {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Cache that also persists entries under `dir` so they survive restarts
+    pub fn on_disk<P: AsRef<Path>>(dir: P) -> Result<Self, CovenantError> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: Some(dir.as_ref().to_path_buf()),
+            ..Self::default()
+        })
+    }
+
+    pub fn contract(&self, uuid: &str) -> Option<Contract> {
+        self.get(&contract_key(uuid)).and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    }
+
+    /// Store a contract, keeping the search index in step
+    pub fn put_contract(&self, contract: &Contract) -> Result<(), CovenantError> {
+        self.put(contract_key(&contract.uuid), serde_json::to_vec(contract)?)?;
+        #[cfg(feature = "search")]
+        self.index.index(contract);
+        Ok(())
+    }
+
+    /// Index over the contracts this cache has stored
+    #[cfg(feature = "search")]
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.index
+    }
+
+    pub fn svg(&self, uuid: &str, options: &SvgOptions) -> Option<String> {
+        self.get(&svg_key(uuid, options)).and_then(|bytes| String::from_utf8(bytes).ok())
+    }
+
+    pub fn attachment(&self, url: &str) -> Option<Vec<u8>> {
+        self.get(&attachment_key(url))
+    }
+
+    /// Drop every entry; on disk only the cache's own files are removed, so
+    /// a shared directory keeps anything else stored there
+    pub fn clear(&self) -> Result<(), CovenantError> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        #[cfg(feature = "search")]
+        self.index.clear();
+        if let Some(dir) = &self.dir {
+            for entry in std::fs::read_dir(dir)? {
+                let entry = entry?;
+                if entry.file_type()?.is_file() && is_cache_file(&entry.file_name().to_string_lossy()) {
+                    std::fs::remove_file(entry.path())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Vec<u8>> {
+        if let Some(bytes) = self.entries.lock().unwrap_or_else(|e| e.into_inner()).get(key) {
+            return Some(bytes.clone());
+        }
+
+        let bytes = std::fs::read(self.file_path(key)?).ok()?;
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).insert(key.to_string(), bytes.clone());
+        Some(bytes)
+    }
+
+    pub(crate) fn put(&self, key: String, bytes: Vec<u8>) -> Result<(), CovenantError> {
+        if let Some(path) = self.file_path(&key) {
+            std::fs::write(path, &bytes)?;
+        }
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).insert(key, bytes);
+        Ok(())
+    }
+
+    pub(crate) fn remove(&self, key: &str) -> Result<(), CovenantError> {
+        if let Some(path) = self.file_path(key) {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+        Ok(())
+    }
+
+    fn file_path(&self, key: &str) -> Option<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.dir.as_ref().map(|dir| dir.join(format!("{:016x}", hasher.finish())))
+    }
+}
+
+/// Whether a file name follows `file_path`'s scheme: the key's hash as 16
+/// lowercase hex digits
+fn is_cache_file(name: &str) -> bool {
+    name.len() == 16 && name.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+fn contract_key(uuid: &str) -> String {
+    format!("contract/{}", uuid)
+}
+
+fn svg_key(uuid: &str, options: &SvgOptions) -> String {
+    format!("svg/{}{}", uuid, options.query_string())
+}
+
+fn attachment_key(url: &str) -> String {
+    format!("attachment/{}", url)
+}
+
+#[derive(Debug, Clone)]
+pub struct PrefetchOptions {
+    /// SVG rendering to cache; defaults to the client's configured options
+    pub svg: Option<SvgOptions>,
+    pub include_svg: bool,
+    pub include_attachments: bool,
+    /// Pause between downloads so prefetching yields to foreground requests
+    pub pause_between: Duration,
+}
+
+impl Default for PrefetchOptions {
+    fn default() -> Self {
+        Self {
+            svg: None,
+            include_svg: true,
+            include_attachments: true,
+            pause_between: Duration::from_millis(250),
+        }
+    }
+}
+
+impl PrefetchOptions {
+    pub fn svg(mut self, svg: SvgOptions) -> Self {
+        self.svg = Some(svg);
+        self
+    }
+
+    pub fn include_svg(mut self, include_svg: bool) -> Self {
+        self.include_svg = include_svg;
+        self
+    }
+
+    pub fn include_attachments(mut self, include_attachments: bool) -> Self {
+        self.include_attachments = include_attachments;
+        self
+    }
+
+    pub fn pause_between(mut self, pause_between: Duration) -> Self {
+        self.pause_between = pause_between;
+        self
+    }
+}
+
+/// What a prefetch stored
+#[derive(Debug, Default)]
+pub struct PrefetchReport {
+    pub contract: bool,
+    pub svg: bool,
+    pub attachments: usize,
+    /// Attachment URLs that failed, with the error
+    pub failed: Vec<(String, CovenantError)>,
+}
+
+impl Contract {
+    /// Attachment URLs listed in the contract's metadata
+    pub fn attachment_urls(&self) -> Vec<String> {
+        self.metadata.get("attachments")
+            .and_then(|attachments| attachments.as_array())
+            .map(|attachments| attachments.iter().filter_map(|attachment| {
+                attachment.as_str()
+                    .or_else(|| attachment.get("url").and_then(|url| url.as_str()))
+                    .map(|url| url.to_string())
+            }).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Cache that `prefetch` fills and the `cached_*` getters read
+    pub fn with_cache(mut self, cache: ContentCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Default rendering for cached SVGs
+    pub fn with_svg_options(mut self, options: SvgOptions) -> Self {
+        self.svg_options = options;
+        self
+    }
+
+    pub fn cache(&self) -> &ContentCache {
+        &self.cache
+    }
+
+    pub fn cached_contract(&self, uuid: &str) -> Option<Contract> {
+        self.cache.contract(&self.cached_uuid(uuid))
+    }
+
+    /// Cached SVG in the client's configured rendering
+    pub fn cached_svg(&self, uuid: &str) -> Option<String> {
+        self.cache.svg(&self.cached_uuid(uuid), &self.svg_options)
+    }
+
+    /// Warm the cache with a contract, its SVG and its attachments. Spawn it
+    /// to run in the background. Attachment failures are collected in the
+    /// report rather than failing the prefetch.
+    pub async fn prefetch(&self, uuid: &str, options: PrefetchOptions) -> Result<PrefetchReport, CovenantError> {
+        let mut report = PrefetchReport::default();
+
+        let contract = self.get_contract(uuid).await?;
+        self.cache.put_contract(&contract)?;
+        report.contract = true;
+
+        if options.include_svg {
+            tokio::time::sleep(options.pause_between).await;
+            let svg_options = options.svg.clone().unwrap_or_else(|| self.svg_options.clone());
+            let svg = self.get_contract_svg(&contract.uuid, svg_options.theme.as_deref(), svg_options.width, svg_options.height).await?;
+            self.cache.put(svg_key(&contract.uuid, &svg_options), svg.into_bytes())?;
+            report.svg = true;
+        }
+
+        if options.include_attachments {
+            for url in contract.attachment_urls() {
+                tokio::time::sleep(options.pause_between).await;
+                match self.fetch_attachment(&url).await {
+                    Ok(bytes) => {
+                        self.cache.put(attachment_key(&url), bytes)?;
+                        report.attachments += 1;
+                    }
+                    Err(e) => report.failed.push((url, e)),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn fetch_attachment(&self, url: &str) -> Result<Vec<u8>, CovenantError> {
+        let response = self.send(self.client.get(url)).await?;
+
+        if !response.status().is_success() {
+            return Err(CovenantError::ServiceError(format!("Attachment fetch failed with status {}", response.status())));
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_keeps_files_the_cache_did_not_write() {
+        let dir = std::env::temp_dir().join(format!("covenant-cache-{}", uuid::Uuid::new_v4()));
+        let cache = ContentCache::on_disk(&dir).unwrap();
+        let contract = Contract::fixture();
+        cache.put_contract(&contract).unwrap();
+        std::fs::write(dir.join("notes.txt"), b"keep me").unwrap();
+        std::fs::write(dir.join("0123456789ABCDEF"), b"keep me too").unwrap();
+
+        cache.clear().unwrap();
+
+        let mut left: Vec<_> = std::fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["0123456789ABCDEF", "notes.txt"]);
+        assert!(cache.contract(&contract.uuid).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cache_file_names_are_sixteen_lowercase_hex_digits() {
+        let cache = ContentCache { dir: Some(PathBuf::from("cache")), ..ContentCache::default() };
+        let path = cache.file_path("contract/abc").unwrap();
+        assert!(is_cache_file(&path.file_name().unwrap().to_string_lossy()));
+        assert!(!is_cache_file("0123456789abcde"));
+        assert!(!is_cache_file("0123456789abcdeg"));
+    }
+}
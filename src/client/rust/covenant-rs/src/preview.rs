@@ -0,0 +1,128 @@
+/*!
+ * Magic spell previews
+ * Describes what a step's MAGIC spell will do when the step completes, so
+ * UIs can show consequences before the user signs. The service's preview
+ * endpoint is preferred; without it the spell payload itself is read.
+ *
+ * Payload fields are looked up at the top level and under `"components"`:
+ * `amount`/`price`/`cost` (minor units), `currency`, `payee`/`recipient`,
+ * `service`/`destination` and a `sideEffects` list of strings.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::ContractStep;
+#[cfg(feature = "client")]
+use crate::{format, CovenantClient, CovenantError, ServiceResponse};
+
+pub(crate) const AMOUNT_KEYS: &[&str] = &["amount", "price", "cost"];
+const PAYEE_KEYS: &[&str] = &["payee", "recipient"];
+const SERVICE_KEYS: &[&str] = &["service", "destination"];
+
+/// Where a preview came from
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum PreviewSource {
+    /// Evaluated by the service
+    #[default]
+    Server,
+    /// Read from the spell payload
+    Spell,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PaymentPreview {
+    /// In the currency's minor unit (e.g. cents)
+    pub amount: i64,
+    pub currency: Option<String>,
+    pub payee: Option<String>,
+}
+
+/// What completing a step will trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SpellPreview {
+    #[serde(rename = "stepId")]
+    pub step_id: String,
+    #[serde(rename = "spellType")]
+    pub spell_type: Option<String>,
+    pub payment: Option<PaymentPreview>,
+    #[serde(rename = "targetService")]
+    pub target_service: Option<String>,
+    #[serde(rename = "sideEffects", default)]
+    pub side_effects: Vec<String>,
+    #[serde(default)]
+    pub source: PreviewSource,
+}
+
+impl SpellPreview {
+    /// Preview read from a spell payload
+    pub fn from_spell(step_id: &str, spell: &serde_json::Value) -> Self {
+        let components = spell.get("components");
+        let field = |keys: &[&str]| keys.iter()
+            .find_map(|key| spell.get(*key).or_else(|| components.and_then(|c| c.get(*key))));
+        let text = |keys: &[&str]| field(keys).and_then(|value| value.as_str()).map(|value| value.to_string());
+
+        let spell_type = spell.get("spell").and_then(|t| t.as_str()).map(|t| t.to_string());
+        let payment = field(AMOUNT_KEYS)
+            .and_then(|amount| amount.as_i64().or_else(|| amount.as_f64().map(|a| a.round() as i64)))
+            .map(|amount| PaymentPreview {
+                amount,
+                currency: text(&["currency"]),
+                payee: text(PAYEE_KEYS),
+            });
+
+        let mut side_effects: Vec<String> = field(&["sideEffects"])
+            .and_then(|effects| effects.as_array())
+            .map(|effects| effects.iter().filter_map(|e| e.as_str()).map(|e| e.to_string()).collect())
+            .unwrap_or_default();
+        if let Some(spell_type) = &spell_type {
+            side_effects.insert(0, format!("Casts the {} spell", spell_type));
+        }
+
+        SpellPreview {
+            step_id: step_id.to_string(),
+            spell_type,
+            payment,
+            target_service: text(SERVICE_KEYS),
+            side_effects,
+            source: PreviewSource::Spell,
+        }
+    }
+}
+
+impl ContractStep {
+    /// Preview of this step's spell, if it has one
+    pub fn spell_preview(&self) -> Option<SpellPreview> {
+        self.magic_spell.as_ref().map(|spell| SpellPreview::from_spell(&self.id, spell))
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// What completing a step will trigger, or `None` when it has no spell
+    pub async fn preview_spell(&self, contract_uuid: &str, step_id: &str) -> Result<Option<SpellPreview>, CovenantError> {
+        let (contract_uuid, url) = self.contract_url(contract_uuid, &format!("step/{}/preview", step_id)).await?;
+        let response = self.send(self.signed_read(self.client.get(&url), Some(&contract_uuid))?).await?;
+
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            let service_response: ServiceResponse<SpellPreview> = format::decode_response(response).await?;
+
+            if !service_response.success {
+                return Err(CovenantError::ServiceError(
+                    service_response.error.unwrap_or_else(|| "Preview spell failed".to_string())
+                ));
+            }
+
+            return Ok(service_response.data);
+        }
+
+        let contract = self.get_contract(&contract_uuid).await?;
+        let step = contract.steps.iter().find(|step| step.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+        Ok(step.spell_preview())
+    }
+}
@@ -0,0 +1,134 @@
+/*!
+ * Partial contract fetches
+ * Asks the service for only the fields a screen needs (`?fields=status,progress`)
+ * instead of the whole contract with its step and signature maps. The uuid is
+ * always returned; every other field of `PartialContract` is `None` unless it
+ * was requested.
+ */
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, ContractProgress, ContractStep};
+#[cfg(feature = "client")]
+use crate::{CovenantClient, CovenantError, ServiceResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum Field {
+    Reference,
+    Title,
+    Description,
+    Participants,
+    /// Every step with its signatures; the expensive one
+    Steps,
+    Status,
+    /// Step counts computed by the service, without the steps themselves
+    Progress,
+    Tags,
+    OrgUuid,
+    CreatedAt,
+    UpdatedAt,
+    Revision,
+    Metadata,
+}
+
+impl Field {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Reference => "reference",
+            Field::Title => "title",
+            Field::Description => "description",
+            Field::Participants => "participants",
+            Field::Steps => "steps",
+            Field::Status => "status",
+            Field::Progress => "progress",
+            Field::Tags => "tags",
+            Field::OrgUuid => "orgUuid",
+            Field::CreatedAt => "createdAt",
+            Field::UpdatedAt => "updatedAt",
+            Field::Revision => "revision",
+            Field::Metadata => "metadata",
+        }
+    }
+}
+
+/// A contract with only the requested fields filled in
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PartialContract {
+    pub uuid: String,
+    pub reference: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub participants: Option<Vec<String>>,
+    pub steps: Option<Vec<ContractStep>>,
+    pub status: Option<String>,
+    pub progress: Option<ContractProgress>,
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "orgUuid")]
+    pub org_uuid: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<String>,
+    pub revision: Option<u64>,
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl Contract {
+    /// The same projection the service applies, e.g. to serve a partial
+    /// fetch from a cached full contract
+    pub fn project(&self, fields: &[Field]) -> PartialContract {
+        let mut partial = PartialContract { uuid: self.uuid.clone(), ..Default::default() };
+
+        for field in fields {
+            match field {
+                Field::Reference => partial.reference = self.reference.clone(),
+                Field::Title => partial.title = Some(self.title.clone()),
+                Field::Description => partial.description = Some(self.description.clone()),
+                Field::Participants => partial.participants = Some(self.participants.clone()),
+                Field::Steps => partial.steps = Some(self.steps.clone()),
+                Field::Status => partial.status = Some(self.status.clone()),
+                Field::Progress => partial.progress = Some(self.progress()),
+                Field::Tags => partial.tags = Some(self.tags.clone()),
+                Field::OrgUuid => partial.org_uuid = self.org_uuid.clone(),
+                Field::CreatedAt => partial.created_at = Some(self.created_at.clone()),
+                Field::UpdatedAt => partial.updated_at = Some(self.updated_at.clone()),
+                Field::Revision => partial.revision = self.revision,
+                Field::Metadata => partial.metadata = Some(self.metadata.clone()),
+            }
+        }
+
+        partial
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Get only the given fields of a contract, by uuid or reference. Spell
+    /// and trust checks are skipped, since the steps usually aren't fetched.
+    pub async fn get_contract_fields(&self, uuid: &str, fields: &[Field]) -> Result<PartialContract, CovenantError> {
+        if fields.is_empty() {
+            return Err(CovenantError::ValidationError("At least one field is required".to_string()));
+        }
+
+        let (uuid, url) = self.contract_url(uuid, "").await?;
+        let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+        let request = self.client.get(&url).query(&[("fields", names.join(","))]);
+        let request = self.signed_read(request, Some(&uuid))?;
+        let service_response: ServiceResponse<PartialContract> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Contract not found".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+}
@@ -0,0 +1,88 @@
+/*!
+ * Contract promotion across environments
+ * Recreates a contract from one covenant service (e.g. staging) on another
+ * (e.g. production): same steps, spells, phases, tags, product/BDO links and
+ * metadata, with participant and reviewer keys remapped and no signatures
+ * or review decisions carried over.
+ *
+ * Organization ownership is environment-specific and is not carried over;
+ * the new contract records where it came from under `"promotedFrom"`.
+ */
+
+use std::collections::HashMap;
+
+use crate::{Contract, CovenantError, SigningOrder};
+#[cfg(feature = "client")]
+use crate::{Authenticated, ContractBuilder, CovenantClient};
+
+/// Metadata key recording the source of a promoted contract
+pub const PROMOTED_FROM_KEY: &str = "promotedFrom";
+
+impl Contract {
+    /// Copy with every participant and reviewer key replaced through
+    /// `mapping`, including custom signing orders. Fails if any participant
+    /// or reviewer has no mapping.
+    pub fn remap_participants(&self, mapping: &HashMap<String, String>) -> Result<Contract, CovenantError> {
+        let unmapped: Vec<&str> = self.participants.iter()
+            .filter(|participant| !mapping.contains_key(*participant))
+            .map(|participant| participant.as_str())
+            .collect();
+
+        if !unmapped.is_empty() {
+            return Err(CovenantError::ValidationError(
+                format!("No mapping for participants: {}", unmapped.join(", "))
+            ));
+        }
+
+        let reviewers = self.review.as_ref().map(|review| review.reviewers.as_slice()).unwrap_or_default();
+        let unmapped: Vec<&str> = reviewers.iter()
+            .filter(|reviewer| !mapping.contains_key(&reviewer.pub_key))
+            .map(|reviewer| reviewer.pub_key.as_str())
+            .collect();
+
+        if !unmapped.is_empty() {
+            return Err(CovenantError::ValidationError(
+                format!("No mapping for reviewers: {}", unmapped.join(", "))
+            ));
+        }
+
+        let remap = |key: &String| mapping.get(key).cloned().unwrap_or_else(|| key.clone());
+        let remap_order = |order: &SigningOrder| match order {
+            SigningOrder::Custom(sequence) => SigningOrder::Custom(sequence.iter().map(remap).collect()),
+            other => other.clone(),
+        };
+
+        let mut contract = self.clone();
+        contract.participants = self.participants.iter().map(remap).collect();
+        contract.signing_order = remap_order(&self.signing_order);
+        for step in &mut contract.steps {
+            step.signing_order = step.signing_order.as_ref().map(remap_order);
+        }
+        if let Some(review) = &mut contract.review {
+            for reviewer in &mut review.reviewers {
+                reviewer.pub_key = remap(&reviewer.pub_key);
+            }
+            review.decisions.clear();
+        }
+
+        Ok(contract)
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Recreate a contract from this service on `target`, remapping
+    /// participant keys (source key to target key) and dropping signatures
+    pub async fn promote(&self, contract_uuid: &str, target: &CovenantClient<Authenticated>, mapping: &HashMap<String, String>) -> Result<Contract, CovenantError> {
+        let source = self.get_contract(contract_uuid).await?;
+        let mut remapped = source.remap_participants(mapping)?;
+        remapped.org_uuid = None;
+
+        let builder = ContractBuilder::from_contract(&remapped).metadata(PROMOTED_FROM_KEY, serde_json::json!({
+            "contractUuid": source.uuid,
+            "service": self.base_url
+        }));
+
+        target.create_contract(&builder).await
+    }
+}
@@ -0,0 +1,72 @@
+/*!
+ * Two-phase signing
+ * A participant first proposes a signature, which is visible to everyone on
+ * the step as "ready to sign", then confirms it to land the binding signature.
+ */
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient, CovenantError, ServiceResponse, SignStepResponse};
+
+/// Staged, unsigned intent to sign a step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SignatureProposal {
+    #[serde(rename = "proposalId")]
+    pub id: String,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    #[serde(rename = "stepId")]
+    pub step_id: String,
+    #[serde(rename = "userUUID")]
+    pub participant_uuid: String,
+    #[serde(rename = "pubKey")]
+    pub pub_key: String,
+    #[serde(rename = "proposedAt")]
+    pub proposed_at: i64,
+    pub note: Option<String>,
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Stage an intent to sign a step without binding the signature yet
+    pub async fn propose_signature(&self, contract_uuid: &str, step_id: &str, note: Option<&str>) -> Result<SignatureProposal, CovenantError> {
+        let (contract_uuid, url) = self.contract_url(contract_uuid, "propose").await?;
+        let payload = self.signed_payload(serde_json::json!({
+            "stepId": step_id,
+            "note": note
+        }), Some(&contract_uuid))?;
+
+        let service_response: ServiceResponse<SignatureProposal> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Propose signature failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No proposal data returned".to_string())
+        )
+    }
+
+    /// Finalize a staged proposal with the binding step signature
+    pub async fn confirm_signature(&self, proposal: &SignatureProposal) -> Result<SignStepResponse, CovenantError> {
+        let mut payload = self.build_sign_request(&proposal.contract_uuid, &proposal.step_id)?;
+
+        if payload.participant_uuid != proposal.participant_uuid {
+            return Err(CovenantError::ValidationError(
+                "Proposals can only be confirmed by the participant who made them".to_string()
+            ));
+        }
+
+        let contract = self.get_contract(&proposal.contract_uuid).await?;
+        contract.check_signable()?;
+        contract.check_signing_order(&proposal.step_id, &payload.participant_uuid, &payload.pub_key)?;
+
+        payload.proposal_id = Some(proposal.id.clone());
+        self.submit_signature(&contract, &payload, false).await
+            .map(|(response, _)| response)
+    }
+}
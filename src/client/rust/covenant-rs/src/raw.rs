@@ -0,0 +1,80 @@
+/*!
+ * Untyped escape hatch for endpoints the typed SDK does not cover yet
+ * Requests go through the client's auth signing, logging, and error mapping.
+ */
+
+use serde::de::DeserializeOwned;
+
+use crate::delegation::DelegatedAction;
+use crate::{Anonymous, CovenantClient, CovenantError, ServiceResponse};
+
+/// Raw access to service routes relative to the base URL
+pub struct RawApi<'a, A = Anonymous> {
+    client: &'a CovenantClient<A>,
+    contract_uuid: Option<String>,
+}
+
+impl<A> CovenantClient<A> {
+    pub fn raw(&self) -> RawApi<'_, A> {
+        RawApi {
+            client: self,
+            contract_uuid: None,
+        }
+    }
+}
+
+impl<A> RawApi<'_, A> {
+    /// Include the contract uuid in the signed auth message, as contract-scoped routes expect
+    pub fn for_contract<S: Into<String>>(mut self, contract_uuid: S) -> Self {
+        self.contract_uuid = Some(contract_uuid.into());
+        self
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, CovenantError> {
+        let request = self.client.signed_read(self.client.client.get(self.url(path)), self.contract_uuid.as_deref())?;
+        self.decode(self.client.send_json(request).await?)
+    }
+
+    /// A write as far as delegation scopes go
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T, CovenantError> {
+        self.client.check_delegation(DelegatedAction::Write)?;
+        let request = self.client.signed_read(self.client.client.delete(self.url(path)), self.contract_uuid.as_deref())?;
+        self.decode(self.client.send_json(request).await?)
+    }
+
+    pub async fn post<T: DeserializeOwned>(&self, path: &str, body: serde_json::Value) -> Result<T, CovenantError> {
+        self.send_with_body(reqwest::Method::POST, path, body).await
+    }
+
+    pub async fn put<T: DeserializeOwned>(&self, path: &str, body: serde_json::Value) -> Result<T, CovenantError> {
+        self.send_with_body(reqwest::Method::PUT, path, body).await
+    }
+
+    async fn send_with_body<T: DeserializeOwned>(&self, method: reqwest::Method, path: &str, body: serde_json::Value) -> Result<T, CovenantError> {
+        let body = self.client.signed_payload(body, self.contract_uuid.as_deref())?;
+        let request = self.client.client.request(method, self.url(path)).json(&body);
+        self.decode(self.client.send_json(request).await?)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.client.base_url, path.trim_start_matches('/'))
+    }
+
+    /// Unwrap the `{ success, data, error }` envelope when present
+    fn decode<T: DeserializeOwned>(&self, body: serde_json::Value) -> Result<T, CovenantError> {
+        let is_envelope = body.get("success").is_some_and(|s| s.is_boolean());
+        if !is_envelope {
+            return Ok(serde_json::from_value(body)?);
+        }
+
+        let service_response: ServiceResponse<serde_json::Value> = serde_json::from_value(body)?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Request failed".to_string())
+            ));
+        }
+
+        Ok(serde_json::from_value(service_response.data.unwrap_or(serde_json::Value::Null))?)
+    }
+}
@@ -0,0 +1,154 @@
+/*!
+ * Server-signed operation receipts
+ * Non-repudiable proof that the service accepted a create or sign operation.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{canonical, verify, CovenantError};
+#[cfg(feature = "client")]
+use crate::{Authenticated, Contract, ContractBuilder, CovenantClient, ServiceResponse, SignStepResponse};
+
+/// Header asking the service to attach a receipt
+#[cfg(feature = "client")]
+pub(crate) const RECEIPT_HEADER: &str = "X-Covenant-Receipt";
+
+#[cfg(feature = "client")]
+pub(crate) const CREATE_CONTRACT: &str = "createContract";
+#[cfg(feature = "client")]
+pub(crate) const SIGN_STEP: &str = "signStep";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OperationReceipt {
+    /// "createContract" or "signStep"
+    pub operation: String,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    /// The result the service returned, as signed
+    pub result: serde_json::Value,
+    pub timestamp: i64,
+    #[serde(rename = "serverPubKey")]
+    pub server_pub_key: String,
+    pub signature: String,
+}
+
+impl OperationReceipt {
+    /// Canonical message the service signs
+    pub fn message(&self) -> String {
+        canonical::canonical_json(&serde_json::json!({
+            "operation": self.operation,
+            "contractUuid": self.contract_uuid,
+            "result": self.result,
+            "timestamp": self.timestamp
+        }))
+    }
+
+    /// Check the receipt signature against a known server key
+    pub fn verify(&self, server_pub_key: &str) -> Result<bool, CovenantError> {
+        if self.server_pub_key != server_pub_key {
+            return Ok(false);
+        }
+
+        verify::verify_signature(&self.signature, &self.message(), server_pub_key)
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Create a contract and return the server's verified receipt for it. A
+    /// content-addressed contract the service already has is an error, as
+    /// there's no creation to issue a receipt for.
+    pub async fn create_contract_with_receipt(&self, contract: &ContractBuilder) -> Result<(Contract, OperationReceipt), CovenantError> {
+        let url = format!("{}/contract", self.base_url);
+        let (payload, content_uuid) = self.contract_payload(contract)?;
+        if let Some(uuid) = content_uuid {
+            if self.find_contract(&uuid).await?.is_some() {
+                return Err(CovenantError::InvalidReceipt(format!("Contract {} already exists, so there is no creation receipt", uuid)));
+            }
+        }
+        let payload = self.signed_payload(payload, None)?;
+
+        let request = self.client
+            .post(&url)
+            .header(RECEIPT_HEADER, "true")
+            .json(&payload);
+
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Unknown error".to_string())
+            ));
+        }
+
+        let data = service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )?;
+        let receipt = service_response.receipt.ok_or_else(||
+            CovenantError::InvalidReceipt("Service did not return a receipt".to_string())
+        )?;
+
+        let created: Contract = serde_json::from_value(data.clone())?;
+        self.verify_receipt(&receipt, CREATE_CONTRACT, &created.uuid, &data).await?;
+        Ok((created, receipt))
+    }
+
+    /// Sign a step and return the server's verified receipt for it
+    pub async fn sign_step_with_receipt(&self, contract_uuid: &str, step_id: &str) -> Result<(SignStepResponse, OperationReceipt), CovenantError> {
+        let contract_uuid = &self.resolve_uuid(contract_uuid).await?;
+        let payload = self.build_sign_request(contract_uuid, step_id)?;
+
+        let contract = self.get_contract(contract_uuid).await?;
+        contract.check_signable()?;
+        contract.check_signing_order(step_id, &payload.participant_uuid, &payload.pub_key)?;
+
+        let (response, receipt) = self.submit_signature(&contract, &payload, true).await?;
+        let receipt = receipt.ok_or_else(||
+            CovenantError::InvalidReceipt("Service did not return a receipt".to_string())
+        )?;
+
+        Ok((response, receipt))
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Key the service signs receipts with, from capabilities or health (cached)
+    pub async fn server_pub_key(&self) -> Result<String, CovenantError> {
+        self.server_pub_key.get_or_try_init(|| async {
+            if let Some(key) = self.get_capabilities().await?.server_pub_key {
+                return Ok(key);
+            }
+
+            self.health_check().await?.pub_key.ok_or_else(||
+                CovenantError::InvalidReceipt("Service does not advertise a signing key".to_string())
+            )
+        }).await.cloned()
+    }
+
+    pub(crate) async fn verify_receipt(&self, receipt: &OperationReceipt, operation: &str, contract_uuid: &str, data: &serde_json::Value) -> Result<(), CovenantError> {
+        if receipt.operation != operation {
+            return Err(CovenantError::InvalidReceipt(
+                format!("Expected a {} receipt, got {}", operation, receipt.operation)
+            ));
+        }
+
+        if receipt.contract_uuid != contract_uuid {
+            return Err(CovenantError::InvalidReceipt(
+                format!("Receipt is for contract {}, not {}", receipt.contract_uuid, contract_uuid)
+            ));
+        }
+
+        if &receipt.result != data {
+            return Err(CovenantError::InvalidReceipt("Receipt does not cover the returned result".to_string()));
+        }
+
+        let server_pub_key = self.server_pub_key().await?;
+        if !receipt.verify(&server_pub_key)? {
+            return Err(CovenantError::InvalidReceipt("Receipt signature does not verify".to_string()));
+        }
+
+        Ok(())
+    }
+}
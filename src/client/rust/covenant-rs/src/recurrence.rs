@@ -0,0 +1,199 @@
+/*!
+ * Recurring contracts
+ * A contract created with a `RecurrenceRule` heads a series; each later
+ * occurrence is a copy of the previous one, linked by `seriesId`, with step
+ * deadlines moved forward by one period.
+ */
+
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, ContractBuilder, ContractQuery, CovenantError};
+#[cfg(feature = "client")]
+use crate::{Authenticated, ContractSummary, CovenantClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// When a series' occurrences start, e.g. a monthly retainer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    /// Periods between occurrences (1 = every period)
+    pub interval: u32,
+    /// Start of the first occurrence
+    #[serde(rename = "startsAt")]
+    pub starts_at: DateTime<Utc>,
+    /// Total number of occurrences, if limited
+    pub count: Option<u32>,
+    /// No occurrence starts after this
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl RecurrenceRule {
+    pub fn new(frequency: Frequency, starts_at: DateTime<Utc>) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            starts_at,
+            count: None,
+            until: None,
+        }
+    }
+
+    pub fn monthly(starts_at: DateTime<Utc>) -> Self {
+        Self::new(Frequency::Monthly, starts_at)
+    }
+
+    pub fn interval(mut self, interval: u32) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Start of the nth occurrence (1-based), or `None` past the end of the series
+    pub fn occurrence_start(&self, occurrence: u32) -> Option<DateTime<Utc>> {
+        if occurrence == 0 || self.count.is_some_and(|count| occurrence > count) {
+            return None;
+        }
+
+        let periods = (occurrence - 1).checked_mul(self.interval.max(1))?;
+        let start = match self.frequency {
+            Frequency::Daily => self.starts_at.checked_add_signed(Duration::days(periods as i64)),
+            Frequency::Weekly => self.starts_at.checked_add_signed(Duration::weeks(periods as i64)),
+            Frequency::Monthly => self.starts_at.checked_add_months(Months::new(periods)),
+            Frequency::Yearly => self.starts_at.checked_add_months(Months::new(periods.checked_mul(12)?)),
+        }?;
+
+        match self.until {
+            Some(until) if start > until => None,
+            _ => Some(start),
+        }
+    }
+}
+
+impl Contract {
+    /// Builder for the occurrence after this one, with step deadlines
+    /// shifted by one period
+    pub fn next_occurrence(&self) -> Result<ContractBuilder, CovenantError> {
+        let rule = self.recurrence.as_ref()
+            .ok_or_else(|| CovenantError::ValidationError(format!("Contract {} does not recur", self.uuid)))?;
+
+        let current = self.occurrence.unwrap_or(1);
+        let ended = || CovenantError::ValidationError(format!("Series {} has no occurrence after {}", self.uuid, current));
+        let next_start = rule.occurrence_start(current + 1).ok_or_else(ended)?;
+        let shift = next_start - rule.occurrence_start(current).ok_or_else(ended)?;
+
+        let mut next = self.clone();
+        for step in &mut next.steps {
+            step.deadline = step.deadline_time().map(|deadline| (deadline + shift).timestamp_millis().to_string());
+        }
+
+        let series_id = self.series_id.clone().unwrap_or_else(|| self.uuid.clone());
+        Ok(ContractBuilder::from_contract(&next).series(series_id, current + 1))
+    }
+}
+
+impl ContractQuery {
+    pub fn series<S: Into<String>>(mut self, series_id: S) -> Self {
+        self.series_id = Some(series_id.into());
+        self
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Every contract in a series, in occurrence order
+    pub async fn list_series(&self, series_id: &str) -> Result<Vec<ContractSummary>, CovenantError> {
+        let mut occurrences = self.search_contracts(&ContractQuery::new().series(series_id)).await?;
+        occurrences.sort_by_key(|summary| summary.occurrence.unwrap_or(1));
+        Ok(occurrences)
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Create the next occurrence of a recurring contract, linked to its series
+    pub async fn spawn_next_occurrence(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        let contract = self.get_contract(uuid).await?;
+        self.create_contract(&contract.next_occurrence()?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> DateTime<Utc> {
+        format!("{}T09:00:00Z", date).parse().unwrap()
+    }
+
+    #[test]
+    fn occurrences_step_by_the_interval() {
+        let weekly = RecurrenceRule::new(Frequency::Weekly, at("2026-01-05")).interval(2);
+        assert_eq!(weekly.occurrence_start(1), Some(at("2026-01-05")));
+        assert_eq!(weekly.occurrence_start(3), Some(at("2026-02-02")));
+        assert_eq!(weekly.occurrence_start(0), None);
+
+        let daily = RecurrenceRule::new(Frequency::Daily, at("2026-12-31"));
+        assert_eq!(daily.occurrence_start(2), Some(at("2027-01-01")));
+
+        let yearly = RecurrenceRule::new(Frequency::Yearly, at("2024-02-29"));
+        assert_eq!(yearly.occurrence_start(2), Some(at("2025-02-28")));
+        assert_eq!(yearly.occurrence_start(5), Some(at("2028-02-29")));
+    }
+
+    #[test]
+    fn month_ends_clamp_without_drifting() {
+        let rule = RecurrenceRule::monthly(at("2026-01-31"));
+        assert_eq!(rule.occurrence_start(2), Some(at("2026-02-28")));
+        assert_eq!(rule.occurrence_start(3), Some(at("2026-03-31")));
+        assert_eq!(rule.occurrence_start(4), Some(at("2026-04-30")));
+        assert_eq!(RecurrenceRule::monthly(at("2024-01-31")).occurrence_start(2), Some(at("2024-02-29")));
+    }
+
+    #[test]
+    fn series_end_at_their_count_or_until() {
+        let counted = RecurrenceRule::monthly(at("2026-01-15")).count(3);
+        assert!(counted.occurrence_start(3).is_some());
+        assert_eq!(counted.occurrence_start(4), None);
+
+        let until = RecurrenceRule::monthly(at("2026-01-15")).until(at("2026-03-15"));
+        assert_eq!(until.occurrence_start(3), Some(at("2026-03-15")));
+        assert_eq!(until.occurrence_start(4), None);
+    }
+
+    #[test]
+    fn next_occurrence_shifts_deadlines_by_one_period() {
+        let mut contract = Contract::fixture();
+        contract.recurrence = Some(RecurrenceRule::monthly(at("2026-01-31")).count(2));
+        contract.steps[0].deadline = Some(at("2026-02-10").timestamp_millis().to_string());
+
+        let next = contract.next_occurrence().unwrap().draft();
+        assert_eq!(next.steps[0].deadline_time(), Some(at("2026-03-10")));
+        assert_eq!(next.steps[1].deadline, None);
+        assert_eq!(next.series_id.as_deref(), Some(contract.uuid.as_str()));
+        assert_eq!(next.occurrence, Some(2));
+
+        contract.occurrence = Some(2);
+        assert!(contract.next_occurrence().is_err());
+        assert!(Contract::fixture().next_occurrence().is_err());
+    }
+}
@@ -0,0 +1,244 @@
+/*!
+ * Human-friendly contract references
+ * The service numbers contracts as they are created (`COV-2025-00042`: the
+ * year, then a per-year sequence) and returns the number as `reference`.
+ * Contracts created before numbering was switched on get a reference derived
+ * from their uuid and creation date instead; derived numbers are stable but
+ * not sequential, and two contracts could in principle derive the same one.
+ *
+ * Every client call that takes a contract uuid accepts a reference in its
+ * place; `resolve_uuid` does the lookup, and lookups are remembered in the
+ * client's `ContentCache`, since a reference never moves to another contract.
+ */
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::prefetch::ContentCache;
+use crate::{parse_timestamp, Contract, CovenantError};
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+
+pub const REFERENCE_PREFIX: &str = "COV";
+
+/// Sequences are zero-padded to at least this many digits
+const SEQUENCE_DIGITS: usize = 5;
+
+/// Derived sequences are kept below this so they stay readable
+const DERIVED_SEQUENCE_LIMIT: u64 = 100_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ContractRef {
+    pub year: i32,
+    pub sequence: u64,
+}
+
+impl ContractRef {
+    pub fn new(year: i32, sequence: u64) -> Self {
+        Self { year, sequence }
+    }
+
+    /// Reference for a contract the service didn't number: the creation year
+    /// and a sequence taken from the uuid's leading hex digits
+    pub fn derive(uuid: &str, created_at: &str) -> Option<Self> {
+        let year = parse_timestamp(created_at)?.year();
+        let hex: String = uuid.chars().filter(|c| c.is_ascii_hexdigit()).take(12).collect();
+        let sequence = u64::from_str_radix(&hex, 16).ok()? % DERIVED_SEQUENCE_LIMIT;
+        Some(Self { year, sequence })
+    }
+
+    /// Whether `id` is a reference rather than a uuid
+    pub fn is_reference(id: &str) -> bool {
+        id.parse::<ContractRef>().is_ok()
+    }
+}
+
+impl fmt::Display for ContractRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{:04}-{:0width$}", REFERENCE_PREFIX, self.year, self.sequence, width = SEQUENCE_DIGITS)
+    }
+}
+
+impl FromStr for ContractRef {
+    type Err = CovenantError;
+
+    /// Case-insensitive, so `cov-2025-42` reads as `COV-2025-00042`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CovenantError::ValidationError(format!("Invalid contract reference: {}", s));
+        let mut parts = s.trim().splitn(3, '-');
+
+        let prefix = parts.next().ok_or_else(invalid)?;
+        if !prefix.eq_ignore_ascii_case(REFERENCE_PREFIX) {
+            return Err(invalid());
+        }
+        let year = digits(parts.next()).filter(|y| y.len() == 4).ok_or_else(invalid)?;
+        let sequence = digits(parts.next()).ok_or_else(invalid)?;
+
+        Ok(Self {
+            year: year.parse().map_err(|_| invalid())?,
+            sequence: sequence.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+fn digits(part: Option<&str>) -> Option<&str> {
+    part.filter(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl TryFrom<String> for ContractRef {
+    type Error = CovenantError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ContractRef> for String {
+    fn from(reference: ContractRef) -> Self {
+        reference.to_string()
+    }
+}
+
+impl Contract {
+    /// The service-assigned reference, or one derived from the uuid for
+    /// contracts created before numbering
+    pub fn contract_ref(&self) -> Option<ContractRef> {
+        self.reference.as_deref()
+            .and_then(|reference| reference.parse().ok())
+            .or_else(|| ContractRef::derive(&self.uuid, &self.created_at))
+    }
+}
+
+fn ref_key(reference: &ContractRef) -> String {
+    format!("ref/{}", reference)
+}
+
+impl ContentCache {
+    /// Uuid of the contract a reference was last seen on
+    pub fn ref_uuid(&self, reference: &ContractRef) -> Option<String> {
+        self.get(&ref_key(reference)).and_then(|bytes| String::from_utf8(bytes).ok())
+    }
+
+    /// Remember the contract's service-assigned reference, if it has one
+    pub fn put_ref(&self, contract: &Contract) -> Result<(), CovenantError> {
+        match contract.reference.as_deref().map(|reference| reference.parse::<ContractRef>()) {
+            Some(Ok(reference)) => self.put(ref_key(&reference), contract.uuid.as_bytes().to_vec()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Get a contract by its reference, e.g. `COV-2025-00042`
+    pub async fn get_contract_by_ref(&self, reference: &str) -> Result<Contract, CovenantError> {
+        let reference: ContractRef = reference.parse()?;
+
+        let request = match self.cache.ref_uuid(&reference) {
+            Some(uuid) => self.signed_read(self.client.get(self.contract_route(&uuid, "")), Some(&uuid))?,
+            None => {
+                let url = format!("{}/contract/ref/{}", self.base_url, reference);
+                self.signed_read(self.client.get(&url), None)?
+            }
+        };
+
+        self.fetch_contract(request).await
+    }
+
+    /// The uuid for `id`, looking it up if it's a reference. Uuids are
+    /// returned unchanged.
+    pub async fn resolve_uuid(&self, id: &str) -> Result<String, CovenantError> {
+        let Ok(reference) = id.parse::<ContractRef>() else {
+            return Ok(id.to_string());
+        };
+
+        match self.cache.ref_uuid(&reference) {
+            Some(uuid) => Ok(uuid),
+            None => Ok(self.get_contract_by_ref(id).await?.uuid),
+        }
+    }
+
+    /// `resolve_uuid` from the cache alone, for calls that can't fetch;
+    /// unknown references are returned unchanged
+    pub(crate) fn cached_uuid(&self, id: &str) -> String {
+        id.parse::<ContractRef>().ok()
+            .and_then(|reference| self.cache.ref_uuid(&reference))
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// URL of `route` (e.g. "sign") on a contract whose uuid is known; an
+    /// empty route is the contract itself
+    pub(crate) fn contract_route(&self, uuid: &str, route: &str) -> String {
+        match route {
+            "" => format!("{}/contract/{}", self.base_url, uuid),
+            route => format!("{}/contract/{}/{}", self.base_url, uuid, route),
+        }
+    }
+
+    /// Resolve a uuid or reference, returning the uuid (for the auth
+    /// message) and the URL of `route` on it
+    pub(crate) async fn contract_url(&self, id: &str, route: &str) -> Result<(String, String), CovenantError> {
+        let uuid = self.resolve_uuid(id).await?;
+        let url = self.contract_route(&uuid, route);
+        Ok((uuid, url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_round_trip() {
+        let reference = ContractRef::new(2025, 42);
+        assert_eq!(reference.to_string(), "COV-2025-00042");
+        assert_eq!("COV-2025-00042".parse::<ContractRef>().unwrap(), reference);
+        assert_eq!(" cov-2025-42 ".parse::<ContractRef>().unwrap(), reference);
+
+        let long = ContractRef::new(2026, 1_234_567);
+        assert_eq!(long.to_string().parse::<ContractRef>().unwrap(), long);
+
+        let json = serde_json::to_string(&reference).unwrap();
+        assert_eq!(json, "\"COV-2025-00042\"");
+        assert_eq!(serde_json::from_str::<ContractRef>(&json).unwrap(), reference);
+    }
+
+    #[test]
+    fn invalid_references_are_rejected() {
+        for invalid in ["", "COV", "COV-2025", "REF-2025-00042", "COV-25-00042", "COV-2025-", "COV-2025-4x", "COV-2025-00042-1"] {
+            assert!(invalid.parse::<ContractRef>().is_err(), "{}", invalid);
+        }
+        assert!(serde_json::from_str::<ContractRef>("\"COV-abcd-1\"").is_err());
+        assert!(!ContractRef::is_reference(&Contract::fixture().uuid));
+    }
+
+    #[test]
+    fn unnumbered_contracts_derive_a_stable_reference() {
+        let contract = Contract::fixture();
+        let derived = contract.contract_ref().unwrap();
+        assert_eq!(derived.year, 2024);
+        assert!(derived.sequence < DERIVED_SEQUENCE_LIMIT);
+        assert_eq!(Contract::fixture().contract_ref(), Some(derived));
+        assert_eq!(ContractRef::derive(&contract.uuid, "not a date"), None);
+
+        let mut numbered = contract;
+        numbered.reference = Some("COV-2025-00007".to_string());
+        assert_eq!(numbered.contract_ref(), Some(ContractRef::new(2025, 7)));
+    }
+
+    #[test]
+    fn the_cache_remembers_service_references() {
+        let cache = ContentCache::in_memory();
+        let mut contract = Contract::fixture();
+        cache.put_ref(&contract).unwrap();
+        assert_eq!(cache.ref_uuid(&contract.contract_ref().unwrap()), None);
+
+        contract.reference = Some("COV-2025-00042".to_string());
+        cache.put_ref(&contract).unwrap();
+        assert_eq!(cache.ref_uuid(&ContractRef::new(2025, 42)), Some(contract.uuid));
+    }
+}
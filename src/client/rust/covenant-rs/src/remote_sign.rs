@@ -0,0 +1,227 @@
+/*!
+ * Remote signing from a kiosk (requires the `remote_sign` feature)
+ * For in-person signings where the signer's key must never touch the shared
+ * screen:
+ *
+ * 1. The kiosk asks the service for a challenge (`create_signing_challenge`)
+ *    and shows it as a QR code
+ * 2. The signer's phone scans it (`SigningChallenge::from_qr_payload`),
+ *    checks the step it's about to sign matches what the kiosk showed, and
+ *    signs with its own key (`sign_challenge`)
+ * 3. The kiosk polls `await_remote_signature` until the signature lands
+ *
+ * The challenge carries a SHA-256 hash over the step as it stood when the
+ * challenge was made (contract uuid, revision, step id, description and
+ * spell); the phone refuses to sign if the contract has changed since.
+ */
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{canonical, encoding, Authenticated, Contract, CovenantClient, CovenantError, ServiceResponse, SignStepResponse};
+
+/// How often `await_remote_signature` checks the challenge
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Scheme prefix of the QR payload
+const QR_PREFIX: &str = "covenant://sign?c=";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SigningChallenge {
+    #[serde(rename = "challengeId")]
+    pub challenge_id: String,
+    /// Base URL of the service the phone should sign against
+    pub service: String,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    #[serde(rename = "stepId")]
+    pub step_id: String,
+    /// `step_payload_hash` of the step the kiosk showed
+    #[serde(rename = "payloadHash")]
+    pub payload_hash: String,
+    /// Milliseconds since the epoch
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ChallengeState {
+    Pending,
+    Signed,
+    Expired,
+    /// The signer declined, or the service rejected their signature
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ChallengeStatus {
+    pub state: ChallengeState,
+    /// Who signed, once signed
+    pub signer: Option<String>,
+    /// The sign result, once signed
+    pub response: Option<SignStepResponse>,
+    pub reason: Option<String>,
+}
+
+/// Hash over what the signer is agreeing to on a step
+pub fn step_payload_hash(contract: &Contract, step_id: &str) -> Result<String, CovenantError> {
+    let step = contract.steps.iter().find(|step| step.id == step_id)
+        .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+    let payload = serde_json::json!({
+        "contractUuid": contract.uuid,
+        "revision": contract.revision,
+        "stepId": step.id,
+        "description": step.description,
+        "magicSpell": step.magic_spell
+    });
+    Ok(encoding::to_hex(&Sha256::digest(canonical::canonical_json(&payload).as_bytes())))
+}
+
+impl SigningChallenge {
+    /// Text encoded in the QR code
+    pub fn qr_payload(&self) -> Result<String, CovenantError> {
+        let json = canonical::canonical_json(&serde_json::to_value(self)?);
+        Ok(format!("{}{}", QR_PREFIX, json))
+    }
+
+    /// Read a scanned QR payload
+    pub fn from_qr_payload(payload: &str) -> Result<Self, CovenantError> {
+        let json = payload.trim().strip_prefix(QR_PREFIX)
+            .ok_or_else(|| CovenantError::ValidationError("Not a covenant signing challenge".to_string()))?;
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The QR code as a standalone SVG, `module_size` pixels per module
+    pub fn qr_svg(&self, module_size: u32) -> Result<String, CovenantError> {
+        let (width, modules) = encoding::qr_modules(&self.qr_payload()?)?;
+        // Four modules of quiet zone on each side
+        let size = (width as u32 + 8) * module_size;
+
+        let mut svg = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}"><rect width="{size}" height="{size}" fill="#fff"/><g fill="#000">"##
+        );
+        svg.push_str(&encoding::qr_svg_rects(width, &modules, module_size as f64, 4));
+        svg.push_str("</g></svg>");
+        Ok(svg)
+    }
+
+    pub fn is_expired(&self, now_millis: i64) -> bool {
+        self.expires_at <= now_millis
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Open a remote signing challenge for a step; the kiosk needs no key
+    pub async fn create_signing_challenge(&self, contract_uuid: &str, step_id: &str, ttl: chrono::Duration) -> Result<SigningChallenge, CovenantError> {
+        let contract = self.get_contract(contract_uuid).await?;
+        let payload_hash = step_payload_hash(&contract, step_id)?;
+
+        let url = self.contract_route(&contract.uuid, "challenge");
+        let payload = self.signed_payload(serde_json::json!({
+            "stepId": step_id,
+            "payloadHash": payload_hash,
+            "expiresAt": (self.now() + ttl).timestamp_millis()
+        }), Some(&contract.uuid))?;
+
+        let service_response: ServiceResponse<SigningChallenge> = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Create signing challenge failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No challenge data returned".to_string())
+        )
+    }
+
+    pub async fn get_challenge_status(&self, challenge_id: &str) -> Result<ChallengeStatus, CovenantError> {
+        let url = format!("{}/challenge/{}", self.base_url, challenge_id);
+        let request = self.signed_read(self.client.get(&url), None)?;
+        let service_response: ServiceResponse<ChallengeStatus> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Challenge not found".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No challenge status returned".to_string())
+        )
+    }
+
+    /// Wait for the phone to sign. Fails if the challenge expires or is
+    /// rejected; cancel through `CallOptions` to stop waiting early.
+    pub async fn await_remote_signature(&self, challenge_id: &str) -> Result<SignStepResponse, CovenantError> {
+        loop {
+            let status = self.get_challenge_status(challenge_id).await?;
+            match status.state {
+                ChallengeState::Pending => {}
+                ChallengeState::Signed => {
+                    return status.response.ok_or_else(||
+                        CovenantError::ServiceError("Signed challenge has no sign response".to_string())
+                    );
+                }
+                ChallengeState::Expired => {
+                    return Err(CovenantError::ValidationError(format!("Signing challenge {} expired", challenge_id)));
+                }
+                ChallengeState::Rejected => {
+                    return Err(CovenantError::ServiceError(
+                        status.reason.unwrap_or_else(|| format!("Signing challenge {} was rejected", challenge_id))
+                    ));
+                }
+            }
+
+            self.call_options.cancellable(async {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                Ok(())
+            }).await?;
+        }
+    }
+}
+
+impl CovenantClient<Authenticated> {
+    /// Sign a scanned challenge with this identity, after checking it hasn't
+    /// expired and the step still matches what the kiosk showed
+    pub async fn sign_challenge(&self, challenge: &SigningChallenge) -> Result<SignStepResponse, CovenantError> {
+        if challenge.service.trim_end_matches('/') != self.base_url {
+            return Err(CovenantError::ValidationError(format!("Challenge is for {}, not {}", challenge.service, self.base_url)));
+        }
+        if challenge.is_expired(self.now().timestamp_millis()) {
+            return Err(CovenantError::ValidationError("Signing challenge has expired".to_string()));
+        }
+
+        let contract = self.get_contract(&challenge.contract_uuid).await?;
+        if step_payload_hash(&contract, &challenge.step_id)? != challenge.payload_hash {
+            return Err(CovenantError::ValidationError(
+                "Step has changed since the challenge was created; ask the kiosk for a new one".to_string()
+            ));
+        }
+
+        let payload = self.build_sign_request(&contract.uuid, &challenge.step_id)?;
+        contract.check_signable()?;
+        contract.check_signing_order(&challenge.step_id, &payload.participant_uuid, &payload.pub_key)?;
+
+        let url = format!("{}/challenge/{}/sign", self.base_url, challenge.challenge_id);
+        let service_response: ServiceResponse<SignStepResponse> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Sign challenge failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No sign response data returned".to_string())
+        )
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+ * SVG render failures
+ * The service tags render failures with a `code` (`unknownTheme`,
+ * `contractTooLarge`, `renderTimeout`); older services are recognised by
+ * status (413, 504). An unknown theme may come with a `fallbackTheme`, which
+ * is retried automatically when `SvgOptions::fallback_theme` is set.
+ */
+
+#[cfg(feature = "client")]
+use serde::Deserialize;
+
+#[cfg(feature = "client")]
+use crate::{format, CovenantClient, CovenantError, SvgOptions};
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RenderError {
+    #[error("Unknown theme {theme}")]
+    UnknownTheme {
+        theme: String,
+        /// Theme the service suggests instead
+        fallback: Option<String>,
+    },
+
+    #[error("Contract too large to render")]
+    TooLarge {
+        /// Largest step count the renderer accepts, if reported
+        max_steps: Option<usize>,
+    },
+
+    #[error("Renderer timed out")]
+    Timeout,
+}
+
+#[cfg(feature = "client")]
+#[derive(Deserialize)]
+struct RenderErrorBody {
+    error: Option<String>,
+    code: Option<String>,
+    #[serde(rename = "fallbackTheme")]
+    fallback_theme: Option<String>,
+    #[serde(rename = "maxSteps")]
+    max_steps: Option<usize>,
+}
+
+#[cfg(feature = "client")]
+/// Typed render failure from an error response, or a service error for
+/// anything else
+async fn render_error(response: reqwest::Response, options: &SvgOptions) -> CovenantError {
+    let status = response.status();
+    let body: RenderErrorBody = match format::decode_response(response).await {
+        Ok(body) => body,
+        Err(e) => return e,
+    };
+
+    let error = match (body.code.as_deref(), status) {
+        (Some("unknownTheme"), _) => RenderError::UnknownTheme {
+            theme: options.theme.clone().unwrap_or_default(),
+            fallback: body.fallback_theme,
+        },
+        (Some("contractTooLarge"), _) | (None, reqwest::StatusCode::PAYLOAD_TOO_LARGE) => RenderError::TooLarge {
+            max_steps: body.max_steps,
+        },
+        (Some("renderTimeout"), _) | (None, reqwest::StatusCode::GATEWAY_TIMEOUT) => RenderError::Timeout,
+        _ => return CovenantError::ServiceError(
+            body.error.unwrap_or_else(|| "SVG generation failed".to_string())
+        ),
+    };
+
+    CovenantError::RenderError(error)
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Successful SVG response, retrying once with the suggested fallback theme
+    pub(crate) async fn request_svg(&self, uuid: &str, options: &SvgOptions) -> Result<reqwest::Response, CovenantError> {
+        let (uuid, url) = &self.contract_url(uuid, &format!("svg{}", options.query_string())).await?;
+        let request = self.signed_read(self.client.get(url).header(reqwest::header::ACCEPT, "image/svg+xml"), Some(uuid))?;
+        let response = self.send(request).await?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        match render_error(response, options).await {
+            CovenantError::RenderError(RenderError::UnknownTheme { fallback: Some(fallback), .. })
+                if options.fallback_theme && options.theme.as_deref() != Some(fallback.as_str()) =>
+            {
+                let options = SvgOptions { theme: Some(fallback), fallback_theme: false, ..options.clone() };
+                Box::pin(self.request_svg(uuid, &options)).await
+            }
+            error => Err(error),
+        }
+    }
+}
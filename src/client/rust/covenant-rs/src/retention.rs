@@ -0,0 +1,138 @@
+/*!
+ * Retention policies
+ * Finds contracts past their retention period and archives or deletes them,
+ * with a dry-run report so purges can be reviewed first.
+ */
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{parse_timestamp, Contract};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient, CovenantError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionAction {
+    /// Set the contract status to "archived"
+    Archive,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// How long a contract is kept after completion (or last update)
+    pub max_age: Duration,
+    /// Only consider contracts whose every step is completed
+    pub completed_only: bool,
+    pub action: RetentionAction,
+}
+
+impl RetentionPolicy {
+    /// Purge completed contracts `years` years after completion
+    pub fn delete_completed_after_years(years: i64) -> Self {
+        Self {
+            max_age: Duration::days(365 * years),
+            completed_only: true,
+            action: RetentionAction::Delete,
+        }
+    }
+
+    pub fn action(mut self, action: RetentionAction) -> Self {
+        self.action = action;
+        self
+    }
+}
+
+/// A contract past its retention period
+#[derive(Debug, Clone)]
+pub struct RetentionMatch {
+    pub uuid: String,
+    pub title: String,
+    /// Completion time, or last update for incomplete contracts
+    pub retained_since: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub matched: Vec<RetentionMatch>,
+    /// Contracts the action was applied to
+    pub applied: Vec<String>,
+    /// Contracts the action failed for, with the error
+    pub failed: Vec<(String, String)>,
+}
+
+/// Check a single contract against a policy
+pub fn check(contract: &Contract, policy: &RetentionPolicy, now: DateTime<Utc>) -> Option<RetentionMatch> {
+    if contract.status == "archived" && policy.action == RetentionAction::Archive {
+        return None;
+    }
+
+    let completed = !contract.steps.is_empty() && contract.steps.iter().all(|step| step.completed);
+    if policy.completed_only && !completed {
+        return None;
+    }
+
+    let retained_since = if completed {
+        contract.steps.iter().filter_map(|step| step.completed_time()).max()
+    } else {
+        None
+    }.or_else(|| parse_timestamp(&contract.updated_at))?;
+
+    if now - retained_since < policy.max_age {
+        return None;
+    }
+
+    Some(RetentionMatch {
+        uuid: contract.uuid.clone(),
+        title: contract.title.clone(),
+        retained_since,
+    })
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Current user's contracts that are past the policy's retention period
+    pub async fn find_expired(&self, policy: &RetentionPolicy) -> Result<Vec<RetentionMatch>, CovenantError> {
+        let summaries = self.get_my_contracts().await?;
+        let uuids: Vec<&str> = summaries.iter().map(|summary| summary.uuid.as_str()).collect();
+
+        let now = self.now();
+        let mut matches = Vec::new();
+        for contract in self.get_contracts(&uuids).await {
+            if let Some(found) = check(&contract?, policy, now) {
+                matches.push(found);
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Archive or delete expired contracts. With `dry_run` nothing is changed
+    /// and the report lists what would be affected.
+    pub async fn apply_retention(&self, policy: &RetentionPolicy, dry_run: bool) -> Result<RetentionReport, CovenantError> {
+        let matched = self.find_expired(policy).await?;
+        let mut report = RetentionReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        if !dry_run {
+            for found in &matched {
+                let result = match policy.action {
+                    RetentionAction::Archive => self.update_contract(&found.uuid, serde_json::json!({ "status": "archived" }))
+                        .await
+                        .map(|_| ()),
+                    RetentionAction::Delete => self.delete_contract(&found.uuid).await.map(|_| ()),
+                };
+
+                match result {
+                    Ok(()) => report.applied.push(found.uuid.clone()),
+                    Err(error) => report.failed.push((found.uuid.clone(), error.to_string())),
+                }
+            }
+        }
+
+        report.matched = matched;
+        Ok(report)
+    }
+}
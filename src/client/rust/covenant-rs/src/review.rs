@@ -0,0 +1,138 @@
+/*!
+ * Review and approval before activation
+ * A contract created with reviewers starts as a draft. It becomes signable
+ * once a reviewer in every required role has approved; a request for
+ * changes sends it back to the author.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, CovenantError};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient, ServiceResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ReviewStatus {
+    #[default]
+    Draft,
+    InReview,
+    ChangesRequested,
+    Approved,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Reviewer {
+    #[serde(rename = "pubKey")]
+    pub pub_key: String,
+    /// e.g. "legal"
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ReviewVerdict {
+    Approved,
+    ChangesRequested,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReviewDecision {
+    #[serde(rename = "pubKey")]
+    pub pub_key: String,
+    pub verdict: ReviewVerdict,
+    pub comments: Option<String>,
+    pub timestamp: i64,
+}
+
+/// A contract's review state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Review {
+    #[serde(default)]
+    pub status: ReviewStatus,
+    #[serde(default)]
+    pub reviewers: Vec<Reviewer>,
+    /// Roles that must each approve before the contract can be signed
+    #[serde(rename = "requiredRoles", default)]
+    pub required_roles: Vec<String>,
+    #[serde(default)]
+    pub decisions: Vec<ReviewDecision>,
+}
+
+impl Review {
+    /// Latest verdict from a reviewer
+    fn latest(&self, pub_key: &str) -> Option<ReviewVerdict> {
+        self.decisions.iter()
+            .filter(|decision| decision.pub_key == pub_key)
+            .max_by_key(|decision| decision.timestamp)
+            .map(|decision| decision.verdict)
+    }
+
+    /// Required roles no reviewer has approved for yet
+    pub fn missing_roles(&self) -> Vec<&str> {
+        self.required_roles.iter()
+            .filter(|role| !self.reviewers.iter().any(|reviewer| {
+                &reviewer.role == *role && self.latest(&reviewer.pub_key) == Some(ReviewVerdict::Approved)
+            }))
+            .map(|role| role.as_str())
+            .collect()
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.status == ReviewStatus::Approved && self.missing_roles().is_empty()
+    }
+}
+
+impl Contract {
+    /// Fail unless the contract has cleared review (or has none)
+    pub fn check_signable(&self) -> Result<(), CovenantError> {
+        match &self.review {
+            Some(review) if !review.is_approved() => Err(CovenantError::ValidationError(format!(
+                "Contract {} is awaiting review approval from: {}",
+                self.uuid,
+                review.missing_roles().join(", ")
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Send a draft to its reviewers
+    pub async fn submit_for_review(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        self.review_action(uuid, "submit", serde_json::json!({})).await
+    }
+
+    /// Approve the contract in the caller's reviewer role
+    pub async fn approve(&self, uuid: &str) -> Result<Contract, CovenantError> {
+        self.review_action(uuid, "approve", serde_json::json!({})).await
+    }
+
+    /// Send the contract back to its author with the reviewer's comments
+    pub async fn request_changes(&self, uuid: &str, comments: &str) -> Result<Contract, CovenantError> {
+        self.review_action(uuid, "changes", serde_json::json!({ "comments": comments })).await
+    }
+
+    async fn review_action(&self, uuid: &str, action: &str, body: serde_json::Value) -> Result<Contract, CovenantError> {
+        let (uuid, url) = self.contract_url(uuid, &format!("review/{}", action)).await?;
+        let payload = self.signed_payload(body, Some(&uuid))?;
+
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Review update failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+}
@@ -0,0 +1,96 @@
+/*!
+ * Signature revocation
+ * A signer who signed the wrong step can withdraw the signature during a
+ * cooling-off window after signing, as long as the step hasn't completed.
+ * The window is set per contract (`ContractBuilder::revocation_window`) and
+ * defaults to 15 minutes. Revocations are recorded in the audit log as
+ * `SignatureRevoked`; the step can then be signed again with `resign_step`.
+ */
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{Contract, CovenantError};
+#[cfg(feature = "client")]
+use crate::{AuditEventKind, Authenticated, CovenantClient, ServiceResponse, SignStepResponse};
+
+/// Window used when the contract doesn't set one
+pub const DEFAULT_REVOCATION_WINDOW: Duration = Duration::minutes(15);
+
+impl Contract {
+    pub fn revocation_window(&self) -> Duration {
+        self.revocation_window
+            .map(|seconds| Duration::seconds(seconds as i64))
+            .unwrap_or(DEFAULT_REVOCATION_WINDOW)
+    }
+
+    /// Until when the signer (by uuid or pub key) can revoke their
+    /// signature on the step. Fails if there's nothing they can revoke.
+    pub fn revocable_until(&self, step_id: &str, signer_uuid: &str, signer_pub_key: &str) -> Result<DateTime<Utc>, CovenantError> {
+        let step = self.steps.iter().find(|step| step.id == step_id)
+            .ok_or_else(|| CovenantError::ValidationError(format!("Step {} not found", step_id)))?;
+
+        if step.completed {
+            return Err(CovenantError::ValidationError(format!("Step {} is completed; its signatures can't be revoked", step_id)));
+        }
+
+        let signature = [signer_uuid, signer_pub_key].iter()
+            .find_map(|id| step.signatures.get(*id).and_then(|s| s.as_ref()))
+            .ok_or_else(|| CovenantError::ValidationError(format!("No signature on step {} to revoke", step_id)))?;
+
+        DateTime::from_timestamp_millis(signature.timestamp)
+            .map(|signed_at| signed_at + self.revocation_window())
+            .ok_or_else(|| CovenantError::ValidationError("Signature has an invalid timestamp".to_string()))
+    }
+
+    /// Fail unless the signer can revoke their signature on the step at `now`
+    pub fn check_revocable(&self, step_id: &str, signer_uuid: &str, signer_pub_key: &str, now: DateTime<Utc>) -> Result<(), CovenantError> {
+        if now >= self.revocable_until(step_id, signer_uuid, signer_pub_key)? {
+            return Err(CovenantError::ValidationError(format!("The revocation window for step {} has passed", step_id)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Withdraw this identity's signature on a step
+    pub async fn revoke_signature(&self, contract_uuid: &str, step_id: &str) -> Result<Contract, CovenantError> {
+        let contract_uuid = &self.resolve_uuid(contract_uuid).await?;
+        let (signer_uuid, signer_pub_key) = self.acting_as();
+        let contract = self.get_contract(contract_uuid).await?;
+        contract.check_revocable(step_id, signer_uuid, signer_pub_key, self.now())?;
+
+        let url = self.contract_route(&contract.uuid, "revoke");
+        let payload = self.signed_payload(serde_json::json!({ "stepId": step_id }), Some(&contract.uuid))?;
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.put(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Revoke signature failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+
+    /// Sign a step again after revoking an earlier signature on it
+    pub async fn resign_step(&self, contract_uuid: &str, step_id: &str) -> Result<SignStepResponse, CovenantError> {
+        let contract_uuid = &self.resolve_uuid(contract_uuid).await?;
+        let (signer_uuid, signer_pub_key) = self.acting_as();
+        let revoked = self.get_audit_log(contract_uuid).await?.iter().any(|event| matches!(
+            &event.kind,
+            AuditEventKind::SignatureRevoked { step_id: revoked_step, signer: revoked_by }
+                if revoked_step == step_id && (revoked_by == signer_uuid || revoked_by == signer_pub_key)
+        ));
+
+        if !revoked {
+            return Err(CovenantError::ValidationError(
+                format!("No revoked signature on step {} to replace; use sign_step", step_id)
+            ));
+        }
+
+        self.sign_step(contract_uuid, step_id, None).await
+    }
+}
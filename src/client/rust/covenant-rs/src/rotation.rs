@@ -0,0 +1,200 @@
+/*!
+ * Key rotation
+ * Hands an identity's existing contracts over to a new key and lets
+ * verifiers follow the resulting chain of keys
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::{scheme, Clock, CovenantError, SignatureScheme, Signer};
+#[cfg(feature = "client")]
+use crate::{format, CovenantClient, ServiceResponse};
+
+/// Signed statement that `old_pub_key` is replaced by `new_pub_key`.
+/// Both keys sign the same message so the rotation proves possession of each.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RotationProof {
+    #[serde(rename = "userUUID")]
+    pub participant_uuid: String,
+    #[serde(rename = "oldPubKey")]
+    pub old_pub_key: String,
+    #[serde(rename = "newPubKey")]
+    pub new_pub_key: String,
+    pub timestamp: i64,
+    #[serde(rename = "oldSignature")]
+    pub old_signature: String,
+    #[serde(rename = "newSignature")]
+    pub new_signature: String,
+    /// Old key's scheme; secp256k1 when not recorded
+    #[serde(rename = "oldScheme", default, skip_serializing_if = "Option::is_none")]
+    pub old_scheme: Option<SignatureScheme>,
+    /// New key's scheme; secp256k1 when not recorded
+    #[serde(rename = "newScheme", default, skip_serializing_if = "Option::is_none")]
+    pub new_scheme: Option<SignatureScheme>,
+}
+
+impl RotationProof {
+    /// Create a rotation proof signed by both the old and new keys
+    pub fn create(old_signer: &dyn Signer, new_signer: &dyn Signer, clock: &dyn Clock) -> Result<Self, CovenantError> {
+        Self::create_at(old_signer, new_signer, clock.now().timestamp_millis())
+    }
+
+    /// Rotation proof stamped with a given millisecond timestamp
+    pub fn create_at(old_signer: &dyn Signer, new_signer: &dyn Signer, timestamp: i64) -> Result<Self, CovenantError> {
+        let message = rotation_message(timestamp, old_signer.uuid(), old_signer.public_key(), new_signer.public_key());
+        let recorded = |scheme: SignatureScheme| Some(scheme).filter(|scheme| *scheme != SignatureScheme::default());
+
+        Ok(RotationProof {
+            participant_uuid: old_signer.uuid().to_string(),
+            old_pub_key: old_signer.public_key().to_string(),
+            new_pub_key: new_signer.public_key().to_string(),
+            timestamp,
+            old_signature: old_signer.sign(&message)?,
+            new_signature: new_signer.sign(&message)?,
+            old_scheme: recorded(old_signer.scheme()),
+            new_scheme: recorded(new_signer.scheme()),
+        })
+    }
+
+    /// Message both keys sign: timestamp + userUUID + oldPubKey + newPubKey
+    pub fn message(&self) -> String {
+        rotation_message(self.timestamp, &self.participant_uuid, &self.old_pub_key, &self.new_pub_key)
+    }
+
+    /// Check both signatures
+    pub fn verify(&self) -> Result<bool, CovenantError> {
+        let message = self.message();
+        Ok(scheme::verify(self.old_scheme.unwrap_or_default(), &self.old_signature, &message, &self.old_pub_key)?
+            && scheme::verify(self.new_scheme.unwrap_or_default(), &self.new_signature, &message, &self.new_pub_key)?)
+    }
+}
+
+fn rotation_message(timestamp: i64, participant_uuid: &str, old_pub_key: &str, new_pub_key: &str) -> String {
+    format!("{}{}{}{}", timestamp, participant_uuid, old_pub_key, new_pub_key)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RotationResult {
+    #[serde(rename = "updatedContracts")]
+    pub updated_contracts: Vec<String>,
+    pub proof: Option<RotationProof>,
+}
+
+/// Verified rotation proofs, used to follow a participant's keys over time
+#[derive(Debug, Clone, Default)]
+pub struct RotationChain {
+    proofs: Vec<RotationProof>,
+}
+
+impl RotationChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a proof after verifying its signatures
+    pub fn add(&mut self, proof: RotationProof) -> Result<(), CovenantError> {
+        if !proof.verify()? {
+            return Err(CovenantError::ValidationError(
+                format!("Invalid rotation proof from {}", proof.old_pub_key)
+            ));
+        }
+
+        if self.proofs.iter().any(|p| p.old_pub_key == proof.old_pub_key) {
+            return Err(CovenantError::ValidationError(
+                format!("Key {} has already been rotated", proof.old_pub_key)
+            ));
+        }
+
+        self.proofs.push(proof);
+        Ok(())
+    }
+
+    pub fn proofs(&self) -> &[RotationProof] {
+        &self.proofs
+    }
+
+    /// Follow rotations forward from `pub_key` to the newest key
+    pub fn current_key<'a>(&'a self, pub_key: &'a str) -> &'a str {
+        let mut key = pub_key;
+        // Bounded by the number of proofs so a malformed cycle cannot loop forever
+        for _ in 0..=self.proofs.len() {
+            match self.proofs.iter().find(|p| p.old_pub_key == key) {
+                Some(proof) => key = &proof.new_pub_key,
+                None => break,
+            }
+        }
+        key
+    }
+
+    /// When `old_pub_key` was rotated away on the path to `current_pub_key`.
+    /// Returns `None` if `old_pub_key` is not a predecessor of `current_pub_key`.
+    pub fn rotated_at(&self, old_pub_key: &str, current_pub_key: &str) -> Option<i64> {
+        let first = self.proofs.iter().find(|p| p.old_pub_key == old_pub_key)?;
+
+        let mut key = first.new_pub_key.as_str();
+        for _ in 0..=self.proofs.len() {
+            if key == current_pub_key {
+                return Some(first.timestamp);
+            }
+            key = &self.proofs.iter().find(|p| p.old_pub_key == key)?.new_pub_key;
+        }
+
+        None
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Rotate the identity's key across all of its contracts.
+    /// Falls back to re-registering on each active contract when the service
+    /// has no rotation endpoint.
+    pub async fn rotate_identity(&self, old_signer: &dyn Signer, new_signer: &dyn Signer) -> Result<RotationResult, CovenantError> {
+        let proof = RotationProof::create_at(old_signer, new_signer, self.now().timestamp_millis())?;
+
+        let url = format!("{}/user/rotate", self.base_url);
+        let response = self.send(self.client.put(&url).json(&proof)).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return self.rotate_on_each_contract(proof).await;
+        }
+
+        let service_response: ServiceResponse<RotationResult> = format::decode_response(response).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Key rotation failed".to_string())
+            ));
+        }
+
+        let mut result = service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No rotation data returned".to_string())
+        )?;
+        result.proof.get_or_insert(proof);
+        Ok(result)
+    }
+
+    async fn rotate_on_each_contract(&self, proof: RotationProof) -> Result<RotationResult, CovenantError> {
+        let summaries = self.list_contracts(Some(&proof.participant_uuid)).await?;
+        let mut updated_contracts = Vec::new();
+
+        for summary in summaries.iter().filter(|s| s.completed_steps < s.step_count) {
+            let url = self.contract_route(&summary.uuid, "rotate");
+            let service_response: ServiceResponse<serde_json::Value> = self.send_json(self.client.put(&url).json(&proof)).await?;
+
+            if !service_response.success {
+                return Err(CovenantError::ServiceError(
+                    service_response.error.unwrap_or_else(|| format!("Key rotation failed for contract {}", summary.uuid))
+                ));
+            }
+
+            updated_contracts.push(summary.uuid.clone());
+        }
+
+        Ok(RotationResult {
+            updated_contracts,
+            proof: Some(proof),
+        })
+    }
+}
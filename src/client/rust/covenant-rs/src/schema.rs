@@ -0,0 +1,95 @@
+/*!
+ * JSON Schema for the wire types (requires the `schemars` feature)
+ */
+
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use std::collections::BTreeMap;
+
+use crate::*;
+
+/// Schemas for every public wire type, keyed by type name
+pub fn all() -> BTreeMap<&'static str, RootSchema> {
+    let mut schemas = BTreeMap::new();
+
+    schemas.insert("Contract", schema_for!(Contract));
+    schemas.insert("ContractStep", schema_for!(ContractStep));
+    schemas.insert("StepSignature", schema_for!(StepSignature));
+    schemas.insert("SignatureScheme", schema_for!(SignatureScheme));
+    #[cfg(feature = "client")]
+    schemas.insert("PendingStep", schema_for!(PendingStep));
+    #[cfg(feature = "client")]
+    schemas.insert("PendingPage", schema_for!(PendingPage));
+    schemas.insert("Phase", schema_for!(Phase));
+    schemas.insert("InviteToken", schema_for!(InviteToken));
+    schemas.insert("ShareLink", schema_for!(ShareLink));
+    schemas.insert("DelegationToken", schema_for!(DelegationToken));
+    schemas.insert("DelegationScope", schema_for!(DelegationScope));
+    schemas.insert("AuditEvent", schema_for!(AuditEvent));
+    schemas.insert("SpellWarning", schema_for!(SpellWarning));
+    #[cfg(feature = "client")]
+    schemas.insert("StepPage", schema_for!(StepPage));
+    schemas.insert("ContractSummary", schema_for!(ContractSummary));
+    schemas.insert("PartialContract", schema_for!(PartialContract));
+    schemas.insert("Field", schema_for!(Field));
+    schemas.insert("ServiceResponse", schema_for!(ServiceResponse<serde_json::Value>));
+    schemas.insert("HealthInfo", schema_for!(HealthInfo));
+    #[cfg(feature = "client")]
+    schemas.insert("Capabilities", schema_for!(Capabilities));
+    schemas.insert("SvgOptions", schema_for!(SvgOptions));
+    schemas.insert("SignStepRequest", schema_for!(SignStepRequest));
+    schemas.insert("SignStepResponse", schema_for!(SignStepResponse));
+    schemas.insert("ContractProgress", schema_for!(ContractProgress));
+    schemas.insert("UserSignatureStatus", schema_for!(UserSignatureStatus));
+    schemas.insert("SigningOrder", schema_for!(SigningOrder));
+    schemas.insert("Visibility", schema_for!(Visibility));
+    schemas.insert("RecurrenceRule", schema_for!(RecurrenceRule));
+    schemas.insert("Review", schema_for!(Review));
+    schemas.insert("SignatureProposal", schema_for!(SignatureProposal));
+    schemas.insert("EscrowRole", schema_for!(EscrowRole));
+    schemas.insert("EscrowState", schema_for!(EscrowState));
+    #[cfg(feature = "client")]
+    schemas.insert("Organization", schema_for!(Organization));
+    #[cfg(feature = "client")]
+    schemas.insert("OrganizationMember", schema_for!(OrganizationMember));
+    schemas.insert("RotationProof", schema_for!(RotationProof));
+    schemas.insert("RotationResult", schema_for!(RotationResult));
+    #[cfg(feature = "client")]
+    schemas.insert("ParticipantProfile", schema_for!(ParticipantProfile));
+    schemas.insert("OperationReceipt", schema_for!(OperationReceipt));
+    schemas.insert("BackupArchive", schema_for!(BackupArchive));
+    schemas.insert("ActivityEvent", schema_for!(ActivityEvent));
+    schemas.insert("AvailabilityWindow", schema_for!(AvailabilityWindow));
+    #[cfg(feature = "client")]
+    schemas.insert("DryRunReport", schema_for!(DryRunReport));
+    #[cfg(feature = "client")]
+    schemas.insert("TriggeredSpell", schema_for!(TriggeredSpell));
+    #[cfg(feature = "client")]
+    schemas.insert("OperationStatus", schema_for!(OperationStatus));
+    #[cfg(feature = "client")]
+    schemas.insert("ContractExport", schema_for!(ContractExport));
+    #[cfg(feature = "client")]
+    schemas.insert("PdfDocument", schema_for!(PdfDocument));
+    #[cfg(feature = "client")]
+    schemas.insert("BulkDeleteResult", schema_for!(BulkDeleteResult));
+    schemas.insert("SpellPreview", schema_for!(SpellPreview));
+    schemas.insert("TrustWarning", schema_for!(TrustWarning));
+    #[cfg(feature = "certificate")]
+    schemas.insert("Certificate", schema_for!(Certificate));
+    #[cfg(feature = "remote_sign")]
+    schemas.insert("SigningChallenge", schema_for!(SigningChallenge));
+    #[cfg(feature = "remote_sign")]
+    schemas.insert("ChallengeStatus", schema_for!(ChallengeStatus));
+    #[cfg(feature = "vault")]
+    schemas.insert("VaultEntry", schema_for!(VaultEntry));
+    #[cfg(feature = "pref")]
+    schemas.insert("DisplayPreferences", schema_for!(DisplayPreferences));
+    schemas.insert("VectorFile", schema_for!(testvectors::VectorFile));
+
+    schemas
+}
+
+/// All schemas as a single JSON document
+pub fn bundle() -> serde_json::Value {
+    serde_json::to_value(all()).unwrap_or(serde_json::Value::Null)
+}
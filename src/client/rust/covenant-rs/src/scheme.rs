@@ -0,0 +1,137 @@
+/*!
+ * Signature schemes
+ * Signing goes through the `Signer` trait so identities aren't tied to
+ * sessionless' secp256k1 keys. Each step signature records its scheme, and
+ * the schemes a service accepts are advertised in its capabilities.
+ *
+ * Ed25519 keys and signatures are hex encoded and need the `ed25519` feature.
+ */
+
+use serde::{Deserialize, Serialize};
+use sessionless::Sessionless;
+
+use crate::CovenantError;
+#[cfg(feature = "ed25519")]
+use crate::encoding::{from_hex, to_hex};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// Sessionless' scheme, assumed when none is recorded
+    #[default]
+    Secp256k1,
+    Ed25519,
+}
+
+impl SignatureScheme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureScheme::Secp256k1 => "secp256k1",
+            SignatureScheme::Ed25519 => "ed25519",
+        }
+    }
+}
+
+/// An identity that can sign auth and step messages
+pub trait Signer: Send + Sync {
+    fn uuid(&self) -> &str;
+    fn public_key(&self) -> &str;
+    fn scheme(&self) -> SignatureScheme;
+    fn sign(&self, message: &str) -> Result<String, CovenantError>;
+}
+
+impl Signer for Sessionless {
+    fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Secp256k1
+    }
+
+    fn sign(&self, message: &str) -> Result<String, CovenantError> {
+        Sessionless::sign(self, message).map_err(|e| CovenantError::SessionlessError(e.to_string()))
+    }
+}
+
+/// Ed25519 identity (requires the `ed25519` feature)
+#[cfg(feature = "ed25519")]
+pub struct Ed25519Signer {
+    uuid: String,
+    key: ed25519_dalek::SigningKey,
+    public_key: String,
+}
+
+#[cfg(feature = "ed25519")]
+impl Ed25519Signer {
+    /// Identity from a 32-byte secret key
+    pub fn from_bytes<S: Into<String>>(uuid: S, secret: &[u8; 32]) -> Self {
+        let key = ed25519_dalek::SigningKey::from_bytes(secret);
+        let public_key = to_hex(key.verifying_key().as_bytes());
+        Self { uuid: uuid.into(), key, public_key }
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl Signer for Ed25519Signer {
+    fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Ed25519
+    }
+
+    fn sign(&self, message: &str) -> Result<String, CovenantError> {
+        use ed25519_dalek::Signer as _;
+        Ok(to_hex(&self.key.sign(message.as_bytes()).to_bytes()))
+    }
+}
+
+/// Verify a signature over `message` under the given scheme
+pub fn verify(scheme: SignatureScheme, signature: &str, message: &str, pub_key: &str) -> Result<bool, CovenantError> {
+    match scheme {
+        SignatureScheme::Secp256k1 => Sessionless::verify_signature(signature, message, pub_key)
+            .map_err(|e| CovenantError::SessionlessError(e.to_string())),
+        #[cfg(feature = "ed25519")]
+        SignatureScheme::Ed25519 => {
+            use ed25519_dalek::Verifier as _;
+            let key: [u8; 32] = from_hex(pub_key)?.try_into()
+                .map_err(|_| CovenantError::EncodingError("Ed25519 public keys are 32 bytes".to_string()))?;
+            let signature: [u8; 64] = from_hex(signature)?.try_into()
+                .map_err(|_| CovenantError::EncodingError("Ed25519 signatures are 64 bytes".to_string()))?;
+            let key = ed25519_dalek::VerifyingKey::from_bytes(&key)
+                .map_err(|e| CovenantError::EncodingError(e.to_string()))?;
+            Ok(key.verify(message.as_bytes(), &ed25519_dalek::Signature::from_bytes(&signature)).is_ok())
+        }
+        #[cfg(not(feature = "ed25519"))]
+        SignatureScheme::Ed25519 => Err(CovenantError::UnsupportedScheme(scheme.name().to_string())),
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Fail early if the service doesn't accept this identity's signature scheme.
+    /// Services that don't advertise schemes accept only secp256k1.
+    pub async fn negotiate_signature_scheme(&self) -> Result<SignatureScheme, CovenantError> {
+        let scheme = self.identity().scheme();
+        let capabilities = self.get_capabilities().await?;
+
+        if capabilities.supports_scheme(scheme) {
+            Ok(scheme)
+        } else {
+            Err(CovenantError::UnsupportedScheme(scheme.name().to_string()))
+        }
+    }
+}
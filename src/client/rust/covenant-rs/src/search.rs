@@ -0,0 +1,344 @@
+/*!
+ * Local contract search (requires the `search` feature)
+ * A small in-memory inverted index over cached contracts, so offline-first
+ * apps can search without the service. The client's `ContentCache` indexes
+ * every contract it stores; `SearchIndex::index` adds others (e.g. contracts
+ * loaded from a vault or an on-disk cache after a restart).
+ *
+ * Queries are whitespace-separated terms, all of which must match. A term
+ * may be fielded (`title:`, `participant:`, `tag:`, `step:`, `note:`) and may end
+ * in `*` for a prefix match: `title:lease participant:alice tag:urgent ren*`.
+ * Participants and tags match whole values; titles, step text and private
+ * notes match words.
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use crate::Contract;
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SearchField {
+    Title,
+    Participant,
+    Tag,
+    /// Step descriptions and checklist items
+    Step,
+    /// The caller's private note
+    Note,
+}
+
+impl SearchField {
+    const ALL: [SearchField; 5] = [SearchField::Title, SearchField::Participant, SearchField::Tag, SearchField::Step, SearchField::Note];
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "title" => Some(SearchField::Title),
+            "participant" => Some(SearchField::Participant),
+            "tag" => Some(SearchField::Tag),
+            "step" => Some(SearchField::Step),
+            "note" => Some(SearchField::Note),
+            _ => None,
+        }
+    }
+
+    /// Whole-value fields aren't split into words
+    fn is_text(&self) -> bool {
+        matches!(self, SearchField::Title | SearchField::Step | SearchField::Note)
+    }
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+fn terms(field: SearchField, value: &str) -> Vec<String> {
+    if field.is_text() {
+        words(value).collect()
+    } else {
+        vec![value.trim().to_lowercase()]
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// field -> term -> contract uuids
+    postings: HashMap<SearchField, HashMap<String, HashSet<String>>>,
+    /// uuid -> every (field, term) it was indexed under, for removal
+    documents: HashMap<String, Vec<(SearchField, String)>>,
+}
+
+impl Inner {
+    /// Drop a contract's entries in the fields `drop` selects
+    fn remove_fields<F: Fn(SearchField) -> bool>(&mut self, uuid: &str, drop: F) {
+        let (dropped, kept): (Vec<_>, Vec<_>) = self.documents.remove(uuid).unwrap_or_default()
+            .into_iter()
+            .partition(|(field, _)| drop(*field));
+
+        for (field, term) in dropped {
+            if let Some(postings) = self.postings.get_mut(&field) {
+                if let Some(uuids) = postings.get_mut(&term) {
+                    uuids.remove(uuid);
+                    if uuids.is_empty() {
+                        postings.remove(&term);
+                    }
+                }
+            }
+        }
+
+        if !kept.is_empty() {
+            self.documents.insert(uuid.to_string(), kept);
+        }
+    }
+
+    fn add(&mut self, uuid: &str, entries: Vec<(SearchField, String)>) {
+        if entries.is_empty() {
+            return;
+        }
+        for (field, term) in &entries {
+            self.postings.entry(*field).or_default()
+                .entry(term.clone()).or_default()
+                .insert(uuid.to_string());
+        }
+        self.documents.entry(uuid.to_string()).or_default().extend(entries);
+    }
+
+    /// Contracts matching one term in one field
+    fn lookup(&self, field: SearchField, term: &str) -> HashSet<&str> {
+        let postings = match self.postings.get(&field) {
+            Some(postings) => postings,
+            None => return HashSet::new(),
+        };
+
+        match term.strip_suffix('*') {
+            Some(prefix) => postings.iter()
+                .filter(|(indexed, _)| indexed.starts_with(prefix))
+                .flat_map(|(_, uuids)| uuids.iter().map(|uuid| uuid.as_str()))
+                .collect(),
+            None => postings.get(term)
+                .map(|uuids| uuids.iter().map(|uuid| uuid.as_str()).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Inverted index over contracts; clones share the same index
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or re-index a contract. Its private note, if indexed, is kept.
+    pub fn index(&self, contract: &Contract) {
+        let mut entries: Vec<(SearchField, String)> = Vec::new();
+        let mut add = |field: SearchField, value: &str| {
+            entries.extend(terms(field, value).into_iter().map(|term| (field, term)));
+        };
+
+        add(SearchField::Title, &contract.title);
+        for participant in &contract.participants {
+            add(SearchField::Participant, participant);
+        }
+        for tag in &contract.tags {
+            add(SearchField::Tag, tag);
+        }
+        for step in contract.rendered_steps() {
+            add(SearchField::Step, &step.description);
+            for item in &step.checklist {
+                add(SearchField::Step, &item.text);
+            }
+        }
+        entries.sort();
+        entries.dedup();
+
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.remove_fields(&contract.uuid, |field| field != SearchField::Note);
+        inner.add(&contract.uuid, entries);
+    }
+
+    /// Replace the private note indexed for a contract
+    pub fn index_note(&self, uuid: &str, text: &str) {
+        let mut entries: Vec<(SearchField, String)> = terms(SearchField::Note, text).into_iter()
+            .map(|term| (SearchField::Note, term))
+            .collect();
+        entries.sort();
+        entries.dedup();
+
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.remove_fields(uuid, |field| field == SearchField::Note);
+        inner.add(uuid, entries);
+    }
+
+    pub fn remove(&self, uuid: &str) {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).remove_fields(uuid, |_| true);
+    }
+
+    pub fn clear(&self) {
+        *self.inner.lock().unwrap_or_else(|e| e.into_inner()) = Inner::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Uuids of contracts matching every term, those matching in the most
+    /// fields first
+    pub fn search(&self, query: &str) -> Vec<String> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut scores: Option<HashMap<&str, usize>> = None;
+
+        for token in query.split_whitespace() {
+            let (fields, value) = match token.split_once(':') {
+                Some((prefix, value)) => match SearchField::from_prefix(&prefix.to_lowercase()) {
+                    Some(field) => (vec![field], value),
+                    None => (SearchField::ALL.to_vec(), token),
+                },
+                None => (SearchField::ALL.to_vec(), token),
+            };
+
+            let wildcard = value.ends_with('*');
+            let mut token_hits: HashMap<&str, usize> = HashMap::new();
+            for field in fields {
+                let mut field_terms = terms(field, value.trim_end_matches('*'));
+                if wildcard {
+                    if let Some(last) = field_terms.last_mut() {
+                        last.push('*');
+                    }
+                }
+                if field_terms.is_empty() {
+                    continue;
+                }
+
+                let mut matched: Option<HashSet<&str>> = None;
+                for term in &field_terms {
+                    let found = inner.lookup(field, term);
+                    matched = Some(match matched {
+                        Some(matched) => matched.intersection(&found).copied().collect(),
+                        None => found,
+                    });
+                }
+                for uuid in matched.unwrap_or_default() {
+                    *token_hits.entry(uuid).or_default() += 1;
+                }
+            }
+
+            scores = Some(match scores {
+                None => token_hits,
+                Some(scores) => scores.into_iter()
+                    .filter_map(|(uuid, score)| token_hits.get(uuid).map(|hits| (uuid, score + hits)))
+                    .collect(),
+            });
+        }
+
+        let mut ranked: Vec<(&str, usize)> = scores.unwrap_or_default().into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked.into_iter().map(|(uuid, _)| uuid.to_string()).collect()
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Search contracts in the local cache
+    pub fn search_local(&self, query: &str) -> Vec<Contract> {
+        self.cache.search_index().search(query).iter()
+            .filter_map(|uuid| self.cache.contract(uuid))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContractFixture;
+
+    fn lease() -> Contract {
+        let mut contract = ContractFixture::new().title("Lease for Flat 4").tag("urgent").build();
+        contract.participants[0] = "alice".to_string();
+        contract.steps[0].description = "Pay rental deposit".to_string();
+        contract
+    }
+
+    fn loan() -> Contract {
+        let mut contract = ContractFixture::new().seed(1).title("Car loan").tag("finance").build();
+        contract.participants[0] = "bob".to_string();
+        contract
+    }
+
+    fn index() -> SearchIndex {
+        let index = SearchIndex::new();
+        index.index(&lease());
+        index.index(&loan());
+        index
+    }
+
+    #[test]
+    fn terms_must_all_match() {
+        let index = index();
+        assert_eq!(index.search("lease"), [lease().uuid]);
+        assert_eq!(index.search("LEASE flat"), [lease().uuid]);
+        assert!(index.search("lease loan").is_empty());
+        assert!(index.search("").is_empty());
+    }
+
+    #[test]
+    fn fielded_terms() {
+        let index = index();
+        assert_eq!(index.search("participant:alice"), [lease().uuid]);
+        assert_eq!(index.search("tag:finance"), [loan().uuid]);
+        assert_eq!(index.search("step:deposit"), [lease().uuid]);
+        assert!(index.search("title:deposit").is_empty());
+        assert!(index.search("participant:ali").is_empty());
+    }
+
+    #[test]
+    fn prefix_matches() {
+        let index = index();
+        assert_eq!(index.search("ren*"), [lease().uuid]);
+        assert_eq!(index.search("participant:ali*"), [lease().uuid]);
+        assert_eq!(index.search("fixture*").len(), 2);
+    }
+
+    #[test]
+    fn matches_in_more_fields_rank_first() {
+        let index = index();
+        let mut noted = loan();
+        noted.uuid = "noted".to_string();
+        noted.title = "Deposit schedule".to_string();
+        index.index(&noted);
+        index.index_note("noted", "deposit due on the call");
+
+        assert_eq!(index.search("note:call"), ["noted"]);
+        assert_eq!(index.search("deposit"), ["noted".to_string(), lease().uuid]);
+    }
+
+    #[test]
+    fn reindexing_keeps_notes_and_drops_old_terms() {
+        let index = index();
+        index.index_note(&lease().uuid, "parking included");
+
+        let mut renamed = lease();
+        renamed.title = "Tenancy".to_string();
+        index.index(&renamed);
+        assert!(index.search("title:lease").is_empty());
+        assert_eq!(index.search("parking tenancy"), [lease().uuid]);
+
+        index.remove(&lease().uuid);
+        assert!(index.search("parking").is_empty());
+        assert_eq!(index.len(), 1);
+        index.clear();
+        assert!(index.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+/*!
+ * Startup self-test
+ * Runs a fixed sequence of checks against the configured endpoint before it
+ * takes traffic: health, capability fetch, an authenticated read, clock skew
+ * and, optionally, a dry-run contract creation that the service validates
+ * but doesn't store. Every check runs even if an earlier one failed; checks
+ * that need an identity are skipped on anonymous clients.
+ */
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::{Authenticated, Capabilities, ContractBuilder, CovenantClient, CovenantError, HealthInfo, ServiceResponse};
+
+/// Stand-in counterparty for the dry-run contract
+const SELF_TEST_PARTICIPANT: &str = "00000000-0000-4000-8000-000000000000";
+
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestOptions {
+    /// Also dry-run a contract creation
+    pub dry_run_create: bool,
+}
+
+impl SelfTestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dry_run_create(mut self, dry_run_create: bool) -> Self {
+        self.dry_run_create = dry_run_create;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub outcome: CheckOutcome,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+    /// Why it failed or was skipped
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub endpoint: String,
+    pub checks: Vec<SelfTestCheck>,
+    pub health: Option<HealthInfo>,
+    pub capabilities: Option<Capabilities>,
+    /// Server clock minus local clock
+    #[serde(rename = "clockSkewMs")]
+    pub clock_skew_ms: Option<i64>,
+}
+
+impl SelfTestReport {
+    /// No check failed
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.outcome != CheckOutcome::Failed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &SelfTestCheck> {
+        self.checks.iter().filter(|check| check.outcome == CheckOutcome::Failed)
+    }
+
+    fn record<T>(&mut self, name: &str, started: Instant, result: Result<T, CovenantError>) -> Option<T> {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (outcome, detail, value) = match result {
+            Ok(value) => (CheckOutcome::Passed, None, Some(value)),
+            Err(error) => (CheckOutcome::Failed, Some(error.to_string()), None),
+        };
+        self.checks.push(SelfTestCheck { name: name.to_string(), outcome, duration_ms, detail });
+        value
+    }
+
+    fn skip(&mut self, name: &str, reason: &str) {
+        self.checks.push(SelfTestCheck {
+            name: name.to_string(),
+            outcome: CheckOutcome::Skipped,
+            duration_ms: 0,
+            detail: Some(reason.to_string()),
+        });
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Check the endpoint is ready to serve; see `SelfTestReport::passed`
+    pub async fn self_test(&self, options: &SelfTestOptions) -> SelfTestReport {
+        let mut report = SelfTestReport {
+            endpoint: self.base_url.clone(),
+            checks: Vec::new(),
+            health: None,
+            capabilities: None,
+            clock_skew_ms: None,
+        };
+
+        let started = Instant::now();
+        let health = self.health_check().await.and_then(|health| match health.status.as_str() {
+            "healthy" => Ok(health),
+            status => Err(CovenantError::ServiceError(format!("Service reports status {}", status))),
+        });
+        report.health = report.record("health", started, health);
+
+        let started = Instant::now();
+        report.capabilities = report.record("capabilities", started, self.get_capabilities().await);
+
+        match &self.signer {
+            Some(signer) => {
+                let authenticated: CovenantClient<Authenticated> = self.clone().with_state(Some(signer.clone()));
+                let started = Instant::now();
+                let probe = authenticated.auth_probe().await;
+                report.record("auth", started, probe);
+            }
+            None => report.skip("auth", "Client has no identity"),
+        }
+
+        let started = Instant::now();
+        let skew = self.sync_clock().await.and_then(|skew| {
+            let max = chrono::Duration::from_std(self.skew_policy.max_skew).unwrap_or(chrono::Duration::MAX);
+            match skew.abs() > max {
+                true => Err(CovenantError::ClockSkewExceeded(skew.num_milliseconds(), max.num_milliseconds())),
+                false => Ok(skew),
+            }
+        });
+        let skew = report.record("clockSkew", started, skew);
+        report.clock_skew_ms = skew.map(|skew| skew.num_milliseconds()).or_else(|| self.skew.get());
+
+        match (&self.signer, options.dry_run_create) {
+            (_, false) => report.skip("dryRunCreate", "Not requested"),
+            (None, true) => report.skip("dryRunCreate", "Client has no identity"),
+            (Some(signer), true) => {
+                let authenticated: CovenantClient<Authenticated> = self.clone().with_state(Some(signer.clone()));
+                let builder = ContractBuilder::new()
+                    .title("Covenant self-test")
+                    .participant(signer.uuid())
+                    .participant(SELF_TEST_PARTICIPANT)
+                    .step("Self-test step");
+                let started = Instant::now();
+                let dry_run = authenticated.dry_run().create_contract(&builder).await;
+                report.record("dryRunCreate", started, dry_run);
+            }
+        }
+
+        report
+    }
+}
+
+impl CovenantClient<Authenticated> {
+    /// A signed read the service must accept
+    async fn auth_probe(&self) -> Result<(), CovenantError> {
+        let url = format!("{}/contracts", self.base_url);
+        let request = self.signed_read(self.client.get(&url).query(&[("participant", self.identity().uuid())]), None)?;
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Authenticated read was rejected".to_string())
+            ));
+        }
+        Ok(())
+    }
+}
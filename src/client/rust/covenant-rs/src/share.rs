@@ -0,0 +1,121 @@
+/*!
+ * Read-only share links
+ * Signed, time-limited URLs that let someone who isn't a participant view a
+ * contract or its SVG, e.g. from an email. The service issues them; services
+ * advertising the `queryTokens` feature also accept links signed locally,
+ * carrying the signature as query parameters.
+ */
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "client")]
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "client")]
+use crate::{canonical, format, Authenticated, CovenantClient, CovenantError, ServiceResponse};
+
+/// Capability a service advertises when it accepts locally signed share links
+pub const QUERY_TOKENS_FEATURE: &str = "queryTokens";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ShareScope {
+    /// The contract JSON
+    Contract,
+    /// The rendered SVG only
+    Svg,
+}
+
+impl ShareScope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShareScope::Contract => "contract",
+            ShareScope::Svg => "svg",
+        }
+    }
+
+    #[cfg(feature = "client")]
+    fn path(&self, contract_uuid: &str) -> String {
+        match self {
+            ShareScope::Contract => format!("contract/{}", contract_uuid),
+            ShareScope::Svg => format!("contract/{}/svg", contract_uuid),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ShareLink {
+    pub url: String,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: String,
+    pub scope: ShareScope,
+    /// Milliseconds since the epoch
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+}
+
+impl ShareLink {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_millis(self.expires_at)
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Time-limited read-only link to a contract or its SVG
+    pub async fn create_share_link(&self, uuid: &str, expiry: Duration, scope: ShareScope) -> Result<ShareLink, CovenantError> {
+        let expires_at = (self.now() + expiry).timestamp_millis();
+        let (uuid, url) = &self.contract_url(uuid, "share").await?;
+        let payload = self.signed_payload(serde_json::json!({
+            "scope": scope,
+            "expiresAt": expires_at
+        }), Some(uuid))?;
+
+        let response = self.send(self.client.post(url).json(&payload)).await?;
+
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            let service_response: ServiceResponse<ShareLink> = format::decode_response(response).await?;
+
+            if !service_response.success {
+                return Err(CovenantError::ServiceError(
+                    service_response.error.unwrap_or_else(|| "Create share link failed".to_string())
+                ));
+            }
+
+            return service_response.data.ok_or_else(||
+                CovenantError::ServiceError("No share link data returned".to_string())
+            );
+        }
+
+        if !self.get_capabilities().await?.supports(QUERY_TOKENS_FEATURE) {
+            return Err(CovenantError::ServiceError("Service does not support share links".to_string()));
+        }
+
+        self.sign_share_link(uuid, expires_at, scope)
+    }
+
+    /// Share link carrying this identity's signature in its query string
+    fn sign_share_link(&self, uuid: &str, expires_at: i64, scope: ShareScope) -> Result<ShareLink, CovenantError> {
+        let signer = self.identity();
+        let message = canonical::share_message(expires_at, signer.uuid(), uuid, scope.name());
+        let signature = signer.sign(&message)?;
+
+        let mut url = reqwest::Url::parse(&format!("{}/{}", self.base_url, scope.path(uuid)))
+            .map_err(|e| CovenantError::ValidationError(e.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("token", &signature)
+            .append_pair("expires", &expires_at.to_string())
+            .append_pair("userUUID", signer.uuid())
+            .append_pair("pubKey", signer.public_key())
+            .append_pair("scope", scope.name());
+
+        Ok(ShareLink {
+            url: url.to_string(),
+            contract_uuid: uuid.to_string(),
+            scope,
+            expires_at,
+        })
+    }
+}
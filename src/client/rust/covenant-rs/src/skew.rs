@@ -0,0 +1,133 @@
+/*!
+ * Clock skew detection and correction
+ * Signatures embed client timestamps, so a device with a bad clock gets its
+ * signatures rejected. The client estimates its offset from the server clock
+ * using each response's `Date` header (or the health endpoint's timestamp via
+ * `sync_clock`) and shifts signing timestamps by it.
+ */
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "client")]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "client")]
+use crate::{parse_timestamp, CovenantClient, CovenantError};
+
+/// How much drift from the server clock is tolerated
+#[derive(Debug, Clone)]
+pub struct ClockSkewPolicy {
+    /// Largest offset signing tolerates before failing with `ClockSkewExceeded`
+    pub max_skew: Duration,
+    /// Shift signing timestamps by the estimated offset
+    pub correct: bool,
+}
+
+impl Default for ClockSkewPolicy {
+    fn default() -> Self {
+        Self {
+            max_skew: Duration::from_secs(5 * 60),
+            correct: true,
+        }
+    }
+}
+
+impl ClockSkewPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_skew(mut self, max_skew: Duration) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    pub fn correct(mut self, correct: bool) -> Self {
+        self.correct = correct;
+        self
+    }
+}
+
+/// Latest offset estimate (server minus local, in milliseconds), shared by clones
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClockSkew {
+    offset_ms: Arc<Mutex<Option<i64>>>,
+}
+
+impl ClockSkew {
+    #[cfg(feature = "client")]
+    fn set(&self, offset_ms: i64) {
+        *self.offset_ms.lock().unwrap_or_else(|e| e.into_inner()) = Some(offset_ms);
+    }
+
+    pub(crate) fn get(&self) -> Option<i64> {
+        *self.offset_ms.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(feature = "client")]
+    /// Update the estimate from a response's `Date` header; `sent` and
+    /// `received` bracket the request so the midpoint stands in for the
+    /// moment the server stamped it
+    pub(crate) fn observe(&self, headers: &reqwest::header::HeaderMap, sent: DateTime<Utc>, received: DateTime<Utc>) {
+        let server = headers.get(reqwest::header::DATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok());
+
+        if let Some(server) = server {
+            // Date only has second precision; assume the middle of that second
+            let server_ms = server.timestamp_millis() + 500;
+            self.set(server_ms - midpoint(sent, received));
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+fn midpoint(sent: DateTime<Utc>, received: DateTime<Utc>) -> i64 {
+    sent.timestamp_millis() + (received - sent).num_milliseconds() / 2
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Drift tolerance and correction applied to signing timestamps
+    pub fn with_clock_skew_policy(mut self, policy: ClockSkewPolicy) -> Self {
+        self.skew_policy = policy;
+        self
+    }
+
+    /// Estimated server clock minus local clock, once any response has been seen
+    pub fn clock_skew(&self) -> Option<chrono::Duration> {
+        self.skew.get().map(chrono::Duration::milliseconds)
+    }
+
+    /// Measure the offset against the health endpoint's millisecond timestamp
+    pub async fn sync_clock(&self) -> Result<chrono::Duration, CovenantError> {
+        let sent = self.now();
+        let health = self.health_check().await?;
+        let received = self.now();
+
+        if let Some(server) = parse_timestamp(&health.timestamp) {
+            self.skew.set(server.timestamp_millis() - midpoint(sent, received));
+        }
+
+        self.clock_skew().ok_or_else(||
+            CovenantError::ServiceError("Service did not report its time".to_string())
+        )
+    }
+
+    /// Timestamp for auth and step signatures, corrected per the skew policy
+    pub(crate) fn signing_timestamp(&self) -> Result<i64, CovenantError> {
+        let now = self.now().timestamp_millis();
+        let offset = match self.skew.get() {
+            Some(offset) => offset,
+            None => return Ok(now),
+        };
+
+        let max_ms = self.skew_policy.max_skew.as_millis() as i64;
+        if offset.abs() > max_ms {
+            return Err(CovenantError::ClockSkewExceeded(offset, max_ms));
+        }
+
+        Ok(if self.skew_policy.correct { now + offset } else { now })
+    }
+}
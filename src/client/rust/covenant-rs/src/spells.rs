@@ -0,0 +1,249 @@
+/*!
+ * Spell schema registry
+ * Applications register a JSON Schema per spell type (the spell's `"spell"`
+ * field); spells on built and fetched contracts are checked against it.
+ *
+ * The validator covers the common keywords: type, enum, const, properties,
+ * required, additionalProperties, items, minItems/maxItems,
+ * minLength/maxLength and minimum/maximum. Other keywords are ignored.
+ *
+ * Typed spell payloads implement `MagicSpellPayload` (derivable with the
+ * `derive` feature), which supplies both the serialized spell and the schema
+ * to register for it.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{Contract, CovenantError};
+
+/// A spell that couldn't be checked against the registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SpellWarning {
+    /// Step id, or "step-N" for steps not yet created
+    #[serde(rename = "stepId")]
+    pub step_id: String,
+    pub kind: SpellWarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", content = "spell", rename_all = "camelCase")]
+pub enum SpellWarningKind {
+    /// No schema is registered for this spell type
+    UnknownType(String),
+    /// The spell has no `"spell"` type field
+    MissingType,
+}
+
+/// A struct that serializes to a spell of one type
+pub trait MagicSpellPayload: Serialize + Sized {
+    /// The spell's `"spell"` type
+    const SPELL: &'static str;
+
+    /// JSON Schema for the serialized spell, including its `"spell"` field
+    fn schema() -> serde_json::Value;
+
+    /// Serialize with the `"spell"` field added, checked against `schema`
+    fn to_spell(&self) -> Result<serde_json::Value, CovenantError> {
+        let mut spell = serde_json::to_value(self)?;
+        spell.as_object_mut()
+            .ok_or_else(|| CovenantError::ValidationError(format!("Spell {} payload must be an object", Self::SPELL)))?
+            .insert("spell".to_string(), serde_json::Value::String(Self::SPELL.to_string()));
+
+        SpellRegistry::new().register_payload::<Self>().validate_spell("payload", &spell)?;
+        Ok(spell)
+    }
+}
+
+#[doc(hidden)]
+pub type JsonValue = serde_json::Value;
+
+/// Used by the `MagicSpellPayload` derive. Panics on invalid JSON, which
+/// would be a bug in the derive rather than in the payload.
+#[doc(hidden)]
+pub fn schema_from_json(json: &str) -> serde_json::Value {
+    serde_json::from_str(json).unwrap_or_else(|e| panic!("MagicSpellPayload derived an invalid schema: {}", e))
+}
+
+/// JSON Schemas keyed by spell type
+#[derive(Debug, Clone, Default)]
+pub struct SpellRegistry {
+    schemas: HashMap<String, serde_json::Value>,
+}
+
+impl SpellRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the schema for a spell type, replacing any earlier one
+    pub fn register<S: Into<String>>(mut self, spell_type: S, schema: serde_json::Value) -> Self {
+        self.schemas.insert(spell_type.into(), schema);
+        self
+    }
+
+    /// Register the schema of a typed payload under its spell type
+    pub fn register_payload<P: MagicSpellPayload>(self) -> Self {
+        self.register(P::SPELL, P::schema())
+    }
+
+    pub fn schema(&self, spell_type: &str) -> Option<&serde_json::Value> {
+        self.schemas.get(spell_type)
+    }
+
+    /// Check one spell. Schema violations are errors; unknown or untyped
+    /// spells come back as a warning.
+    pub fn validate_spell(&self, step_id: &str, spell: &serde_json::Value) -> Result<Option<SpellWarning>, CovenantError> {
+        let spell_type = match spell.get("spell").and_then(|t| t.as_str()) {
+            Some(spell_type) => spell_type,
+            None => return Ok(Some(SpellWarning { step_id: step_id.to_string(), kind: SpellWarningKind::MissingType })),
+        };
+
+        let schema = match self.schemas.get(spell_type) {
+            Some(schema) => schema,
+            None => return Ok(Some(SpellWarning {
+                step_id: step_id.to_string(),
+                kind: SpellWarningKind::UnknownType(spell_type.to_string()),
+            })),
+        };
+
+        let mut errors = Vec::new();
+        check(schema, spell, "", &mut errors);
+        if !errors.is_empty() {
+            return Err(CovenantError::ValidationError(
+                format!("Spell {} on {} is invalid: {}", spell_type, step_id, errors.join("; "))
+            ));
+        }
+
+        Ok(None)
+    }
+
+    /// Check every spell on a contract
+    pub fn validate_contract(&self, contract: &Contract) -> Result<Vec<SpellWarning>, CovenantError> {
+        self.validate_spells(contract.steps.iter()
+            .filter_map(|step| step.magic_spell.as_ref().map(|spell| (step.id.as_str(), spell))))
+    }
+
+    pub(crate) fn validate_spells<'a, I>(&self, spells: I) -> Result<Vec<SpellWarning>, CovenantError>
+    where
+        I: IntoIterator<Item = (&'a str, &'a serde_json::Value)>,
+    {
+        let mut warnings = Vec::new();
+        for (step_id, spell) in spells {
+            warnings.extend(self.validate_spell(step_id, spell)?);
+        }
+        Ok(warnings)
+    }
+}
+
+impl Contract {
+    /// Check this contract's spells against a registry
+    pub fn validate_spells(&self, registry: &SpellRegistry) -> Result<Vec<SpellWarning>, CovenantError> {
+        registry.validate_contract(self)
+    }
+}
+
+fn type_matches(expected: &str, value: &serde_json::Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn check(schema: &serde_json::Value, value: &serde_json::Value, path: &str, errors: &mut Vec<String>) {
+    let at = if path.is_empty() { "/" } else { path };
+
+    match schema.get("type") {
+        Some(serde_json::Value::String(expected)) if !type_matches(expected, value) => {
+            errors.push(format!("{} must be {}", at, expected));
+            return;
+        }
+        Some(serde_json::Value::Array(options)) if !options.iter().filter_map(|o| o.as_str()).any(|o| type_matches(o, value)) => {
+            errors.push(format!("{} has the wrong type", at));
+            return;
+        }
+        _ => {}
+    }
+
+    if let Some(options) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !options.contains(value) {
+            errors.push(format!("{} must be one of {}", at, serde_json::Value::Array(options.clone())));
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{} must be {}", at, expected));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(|m| m.as_f64()) {
+            if number < minimum {
+                errors.push(format!("{} must be at least {}", at, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(|m| m.as_f64()) {
+            if number > maximum {
+                errors.push(format!("{} must be at most {}", at, maximum));
+            }
+        }
+    }
+
+    if let Some(text) = value.as_str() {
+        let length = text.chars().count() as u64;
+        if schema.get("minLength").and_then(|m| m.as_u64()).is_some_and(|min| length < min) {
+            errors.push(format!("{} is too short", at));
+        }
+        if schema.get("maxLength").and_then(|m| m.as_u64()).is_some_and(|max| length > max) {
+            errors.push(format!("{} is too long", at));
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        let count = items.len() as u64;
+        if schema.get("minItems").and_then(|m| m.as_u64()).is_some_and(|min| count < min) {
+            errors.push(format!("{} has too few items", at));
+        }
+        if schema.get("maxItems").and_then(|m| m.as_u64()).is_some_and(|max| count > max) {
+            errors.push(format!("{} has too many items", at));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                check(item_schema, item, &format!("{}/{}", path, index), errors);
+            }
+        }
+    }
+
+    if let Some(fields) = value.as_object() {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+
+        if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+            for name in required.iter().filter_map(|r| r.as_str()) {
+                if !fields.contains_key(name) {
+                    errors.push(format!("{}/{} is required", path, name));
+                }
+            }
+        }
+
+        for (name, field) in fields {
+            let field_path = format!("{}/{}", path, name);
+            match properties.and_then(|p| p.get(name)) {
+                Some(field_schema) => check(field_schema, field, &field_path, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(serde_json::Value::Bool(false)) => errors.push(format!("{} is not allowed", field_path)),
+                    Some(extra) if extra.is_object() => check(extra, field, &field_path, errors),
+                    _ => {}
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+/*!
+ * Stalled contract detection
+ * Flags contracts with no signature activity for a while, naming the step
+ * and participants holding them up, and lets the caller send a reminder.
+ */
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{parse_timestamp, Contract};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient, CovenantError, ServiceResponse};
+
+#[derive(Debug, Clone)]
+pub struct StallPolicy {
+    /// How long without a signature before a contract counts as stalled
+    pub max_idle: Duration,
+}
+
+impl StallPolicy {
+    pub fn idle_for_days(days: i64) -> Self {
+        Self { max_idle: Duration::days(days) }
+    }
+}
+
+/// A contract with no recent signature activity
+#[derive(Debug, Clone)]
+pub struct StalledContract {
+    pub uuid: String,
+    pub title: String,
+    /// Latest signature, or creation for contracts nobody has signed
+    pub last_activity: DateTime<Utc>,
+    pub idle: Duration,
+    /// First incomplete step
+    pub blocking_step: String,
+    /// Participants whose signature that step is waiting on
+    pub blocking_participants: Vec<String>,
+}
+
+/// Check a single contract against a policy
+pub fn check(contract: &Contract, policy: &StallPolicy, now: DateTime<Utc>) -> Option<StalledContract> {
+    let step = contract.steps.iter().find(|step| !step.completed)?;
+
+    let last_signature = contract.steps.iter()
+        .flat_map(|step| step.signatures.values().flatten())
+        .map(|signature| signature.timestamp)
+        .max()
+        .and_then(DateTime::from_timestamp_millis);
+    let last_activity = last_signature.or_else(|| parse_timestamp(&contract.created_at))?;
+
+    let idle = now - last_activity;
+    if idle < policy.max_idle {
+        return None;
+    }
+
+    let blocking_participants = match contract.next_signer(step) {
+        Some(next) => vec![next.to_string()],
+        None => step.awaiting_signers(&contract.participants),
+    };
+
+    Some(StalledContract {
+        uuid: contract.uuid.clone(),
+        title: contract.title.clone(),
+        last_activity,
+        idle,
+        blocking_step: step.id.clone(),
+        blocking_participants,
+    })
+}
+
+/// Stalled contracts among `contracts` as of `now`, longest idle first
+pub fn find_stalled(contracts: &[Contract], policy: &StallPolicy, now: DateTime<Utc>) -> Vec<StalledContract> {
+    let mut stalled: Vec<StalledContract> = contracts.iter()
+        .filter_map(|contract| check(contract, policy, now))
+        .collect();
+    stalled.sort_by_key(|found| std::cmp::Reverse(found.idle));
+    stalled
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Ask the service to send a reminder event to a step's pending signers
+    pub async fn nudge(&self, contract_uuid: &str, step_id: &str) -> Result<(), CovenantError> {
+        let (contract_uuid, url) = self.contract_url(contract_uuid, "nudge").await?;
+        let payload = self.signed_payload(serde_json::json!({ "stepId": step_id }), Some(&contract_uuid))?;
+
+        let service_response: ServiceResponse<serde_json::Value> = self.send_json(self.client.post(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Nudge failed".to_string())
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, MockClock};
+    use crate::fixtures::FIXTURE_EPOCH_MILLIS;
+    use crate::{ContractFixture, SigningOrder};
+
+    fn clock() -> MockClock {
+        MockClock::at_millis(FIXTURE_EPOCH_MILLIS)
+    }
+
+    #[test]
+    fn idle_is_measured_from_the_last_signature() {
+        let clock = clock();
+        let contract = ContractFixture::new().completed(1).build();
+        let policy = StallPolicy::idle_for_days(3);
+
+        clock.advance(Duration::days(3));
+        assert!(check(&contract, &policy, clock.now()).is_none());
+
+        clock.advance(Duration::hours(1));
+        let stalled = check(&contract, &policy, clock.now()).unwrap();
+        assert_eq!(stalled.last_activity, DateTime::from_timestamp_millis(FIXTURE_EPOCH_MILLIS).unwrap() + Duration::hours(1));
+        assert_eq!(stalled.idle, Duration::days(3));
+        assert_eq!(stalled.blocking_step, "step-2");
+        assert_eq!(stalled.blocking_participants, contract.participants);
+    }
+
+    #[test]
+    fn unsigned_contracts_are_idle_since_creation() {
+        let clock = clock();
+        clock.advance(Duration::days(2));
+        let stalled = check(&Contract::fixture(), &StallPolicy::idle_for_days(1), clock.now()).unwrap();
+        assert_eq!(stalled.idle, Duration::days(2));
+        assert_eq!(stalled.blocking_step, "step-1");
+    }
+
+    #[test]
+    fn ordered_steps_block_on_the_next_signer() {
+        let clock = clock();
+        clock.advance(Duration::days(10));
+        let mut contract = Contract::fixture();
+        contract.signing_order = SigningOrder::Sequential;
+
+        let stalled = check(&contract, &StallPolicy::idle_for_days(1), clock.now()).unwrap();
+        assert_eq!(stalled.blocking_participants, [contract.participants[0].clone()]);
+    }
+
+    #[test]
+    fn completed_contracts_never_stall() {
+        let clock = clock();
+        clock.advance(Duration::days(365));
+        assert!(check(&ContractFixture::new().completed(3).build(), &StallPolicy::idle_for_days(1), clock.now()).is_none());
+    }
+
+    #[test]
+    fn longest_idle_first() {
+        let clock = clock();
+        clock.advance(Duration::days(30));
+        let older = Contract::fixture();
+        let newer = ContractFixture::new().seed(5).build();
+
+        let stalled = find_stalled(&[newer.clone(), older.clone()], &StallPolicy::idle_for_days(7), clock.now());
+        let uuids: Vec<_> = stalled.iter().map(|found| found.uuid.as_str()).collect();
+        assert_eq!(uuids, [older.uuid.as_str(), newer.uuid.as_str()]);
+
+        let stalled = find_stalled(&[newer, older.clone()], &StallPolicy::idle_for_days(28), clock.now());
+        assert_eq!(stalled.len(), 1);
+        assert_eq!(stalled[0].uuid, older.uuid);
+    }
+}
@@ -0,0 +1,92 @@
+/*!
+ * Paged step fetching for very large contracts
+ * Lets small devices walk a multi-thousand-step contract a slice at a time
+ * instead of deserializing it wholesale.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+use crate::{Anonymous, ContractStep, CovenantClient, CovenantError, ServiceResponse};
+
+/// One slice of a contract's steps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StepPage {
+    pub steps: Vec<ContractStep>,
+    pub offset: usize,
+    /// Total steps on the contract
+    pub total: usize,
+}
+
+impl StepPage {
+    pub fn is_last(&self) -> bool {
+        self.offset + self.steps.len() >= self.total
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// Fetch steps `range.start..range.end` of a contract
+    pub async fn get_contract_steps(&self, uuid: &str, range: Range<usize>) -> Result<StepPage, CovenantError> {
+        let (uuid, url) = &self.contract_url(uuid, "steps").await?;
+        let params = [
+            ("offset", range.start.to_string()),
+            ("limit", range.len().to_string()),
+        ];
+
+        let request = self.signed_read(self.client.get(url).query(&params), Some(uuid))?;
+        let service_response: ServiceResponse<StepPage> = self.send_json(request).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Get steps failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No steps data returned".to_string())
+        )
+    }
+
+    /// Walk a contract's steps `page_size` at a time
+    pub fn step_pages(&self, uuid: &str, page_size: usize) -> StepPages<'_, A> {
+        StepPages {
+            client: self,
+            uuid: uuid.to_string(),
+            page_size: page_size.max(1),
+            offset: 0,
+            done: false,
+        }
+    }
+}
+
+/// Cursor over a contract's steps
+pub struct StepPages<'a, A = Anonymous> {
+    client: &'a CovenantClient<A>,
+    uuid: String,
+    page_size: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<A> StepPages<'_, A> {
+    /// Fetch the next page, or `None` once every step has been returned
+    pub async fn next(&mut self) -> Option<Result<StepPage, CovenantError>> {
+        if self.done {
+            return None;
+        }
+
+        let range = self.offset..self.offset + self.page_size;
+        let page = match self.client.get_contract_steps(&self.uuid, range).await {
+            Ok(page) => page,
+            Err(error) => {
+                self.done = true;
+                return Some(Err(error));
+            }
+        };
+
+        self.offset += page.steps.len();
+        self.done = page.is_last() || page.steps.is_empty();
+        Some(Ok(page))
+    }
+}
@@ -0,0 +1,325 @@
+/*!
+ * Plain-language contract summaries
+ * A compact, deterministic brief of who is party to a contract, what each
+ * step obliges, which payments its spells move and what is still waiting on
+ * whom. Briefs are rendered by a `BriefFormatter`; `PlainText` suits
+ * notifications and `Spoken` reads as sentences for voice assistants.
+ */
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::Contract;
+
+/// What one step asks of the parties
+#[derive(Debug, Clone, PartialEq)]
+pub struct Obligation {
+    pub step_id: String,
+    /// Description with the contract's variables filled in
+    pub description: String,
+    pub completed: bool,
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+/// Payment a step's spell makes when the step completes
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoneyMovement {
+    pub step_id: String,
+    pub description: String,
+    /// In the currency's minor unit (e.g. cents)
+    pub amount: i64,
+    pub currency: Option<String>,
+    pub payee: Option<String>,
+    /// Whether the step, and so the payment, has happened
+    pub completed: bool,
+}
+
+/// Signatures an incomplete step is waiting on
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutstandingAction {
+    pub step_id: String,
+    pub description: String,
+    /// In signing order when one applies, otherwise in participant order
+    pub awaiting: Vec<String>,
+    pub deadline: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractBrief {
+    pub uuid: String,
+    pub title: String,
+    pub parties: Vec<String>,
+    pub obligations: Vec<Obligation>,
+    pub payments: Vec<MoneyMovement>,
+    pub outstanding: Vec<OutstandingAction>,
+}
+
+/// Renders a brief as text
+pub trait BriefFormatter {
+    fn format(&self, brief: &ContractBrief) -> String;
+}
+
+impl<F: Fn(&ContractBrief) -> String> BriefFormatter for F {
+    fn format(&self, brief: &ContractBrief) -> String {
+        self(brief)
+    }
+}
+
+/// Summarize a contract. Steps appear in their `order`; the same contract
+/// always produces the same brief.
+pub fn summarize(contract: &Contract) -> ContractBrief {
+    let mut steps = contract.rendered_steps();
+    steps.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.id.cmp(&b.id)));
+
+    let mut brief = ContractBrief {
+        uuid: contract.uuid.clone(),
+        title: contract.title.clone(),
+        parties: contract.participants.clone(),
+        obligations: Vec::new(),
+        payments: Vec::new(),
+        outstanding: Vec::new(),
+    };
+
+    for step in &steps {
+        brief.obligations.push(Obligation {
+            step_id: step.id.clone(),
+            description: step.description.clone(),
+            completed: step.completed,
+            deadline: step.deadline_time(),
+        });
+
+        if let Some(payment) = step.spell_preview().and_then(|preview| preview.payment) {
+            brief.payments.push(MoneyMovement {
+                step_id: step.id.clone(),
+                description: step.description.clone(),
+                amount: payment.amount,
+                currency: payment.currency,
+                payee: payment.payee,
+                completed: step.completed,
+            });
+        }
+
+        if step.completed {
+            continue;
+        }
+
+        let awaiting = match contract.next_signer(step) {
+            Some(next) => vec![next.to_string()],
+            None => step.awaiting_signers(&contract.participants),
+        };
+
+        brief.outstanding.push(OutstandingAction {
+            step_id: step.id.clone(),
+            description: step.description.clone(),
+            awaiting,
+            deadline: step.deadline_time(),
+        });
+    }
+
+    brief
+}
+
+impl ContractBrief {
+    pub fn is_complete(&self) -> bool {
+        !self.obligations.is_empty() && self.obligations.iter().all(|o| o.completed)
+    }
+
+    pub fn completed_steps(&self) -> usize {
+        self.obligations.iter().filter(|o| o.completed).count()
+    }
+
+    /// Render with a specific formatter
+    pub fn format_with<F: BriefFormatter + ?Sized>(&self, formatter: &F) -> String {
+        formatter.format(self)
+    }
+}
+
+impl fmt::Display for ContractBrief {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&PlainText.format(self))
+    }
+}
+
+/// Short line-per-item text for notifications
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainText;
+
+impl BriefFormatter for PlainText {
+    fn format(&self, brief: &ContractBrief) -> String {
+        let mut out = format!(
+            "{} ({} of {} steps complete)\nParties: {}\n",
+            brief.title,
+            brief.completed_steps(),
+            brief.obligations.len(),
+            brief.parties.join(", ")
+        );
+
+        for obligation in &brief.obligations {
+            let mark = if obligation.completed { "x" } else { " " };
+            out.push_str(&format!("[{}] {}{}\n", mark, obligation.description, due(obligation.deadline)));
+        }
+
+        for payment in &brief.payments {
+            let verb = if payment.completed { "Paid" } else { "Pays" };
+            let payee = payment.payee.as_deref().map(|p| format!(" to {}", p)).unwrap_or_default();
+            out.push_str(&format!(
+                "{} {}{} for {}\n", verb, format_amount(payment.amount, payment.currency.as_deref()), payee, payment.description
+            ));
+        }
+
+        for action in &brief.outstanding {
+            if !action.awaiting.is_empty() {
+                out.push_str(&format!("Waiting on {} for {}\n", action.awaiting.join(", "), action.description));
+            }
+        }
+
+        out
+    }
+}
+
+/// Full sentences without symbols, for text-to-speech
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spoken;
+
+impl BriefFormatter for Spoken {
+    fn format(&self, brief: &ContractBrief) -> String {
+        let mut sentences = vec![format!(
+            "{} is between {} and has {} of {} steps complete.",
+            brief.title,
+            spoken_list(&brief.parties),
+            brief.completed_steps(),
+            brief.obligations.len()
+        )];
+
+        if brief.is_complete() {
+            sentences.push("Everything has been signed.".to_string());
+        }
+
+        for payment in brief.payments.iter().filter(|payment| !payment.completed) {
+            let payee = payment.payee.as_deref().map(|p| format!(" to {}", p)).unwrap_or_default();
+            sentences.push(format!(
+                "Completing {} pays {}{}.", payment.description, format_amount(payment.amount, payment.currency.as_deref()), payee
+            ));
+        }
+
+        for action in brief.outstanding.iter().filter(|action| !action.awaiting.is_empty()) {
+            sentences.push(format!(
+                "{} still {} to sign {}{}.",
+                spoken_list(&action.awaiting),
+                if action.awaiting.len() == 1 { "needs" } else { "need" },
+                action.description,
+                due(action.deadline)
+            ));
+        }
+
+        sentences.join(" ")
+    }
+}
+
+/// Minor units as a decimal amount when the currency is known
+pub(crate) fn format_amount(amount: i64, currency: Option<&str>) -> String {
+    match currency {
+        Some(currency) => {
+            let sign = if amount < 0 { "-" } else { "" };
+            let amount = amount.unsigned_abs();
+            format!("{}{}.{:02} {}", sign, amount / 100, amount % 100, currency)
+        }
+        None => amount.to_string(),
+    }
+}
+
+fn due(deadline: Option<DateTime<Utc>>) -> String {
+    deadline.map(|d| format!(" by {}", d.format("%Y-%m-%d"))).unwrap_or_default()
+}
+
+fn spoken_list(items: &[String]) -> String {
+    match items {
+        [] => "nobody".to_string(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brief() -> ContractBrief {
+        ContractBrief {
+            uuid: "contract".to_string(),
+            title: "Lease".to_string(),
+            parties: vec!["alice".to_string(), "bob".to_string()],
+            obligations: vec![
+                Obligation { step_id: "step-1".to_string(), description: "Sign lease".to_string(), completed: true, deadline: None },
+                Obligation {
+                    step_id: "step-2".to_string(),
+                    description: "Pay deposit".to_string(),
+                    completed: false,
+                    deadline: Some("2026-03-01T00:00:00Z".parse().unwrap()),
+                },
+            ],
+            payments: vec![MoneyMovement {
+                step_id: "step-2".to_string(),
+                description: "Pay deposit".to_string(),
+                amount: 150_005,
+                currency: Some("USD".to_string()),
+                payee: Some("bob".to_string()),
+                completed: false,
+            }],
+            outstanding: vec![OutstandingAction {
+                step_id: "step-2".to_string(),
+                description: "Pay deposit".to_string(),
+                awaiting: vec!["alice".to_string()],
+                deadline: Some("2026-03-01T00:00:00Z".parse().unwrap()),
+            }],
+        }
+    }
+
+    #[test]
+    fn amounts_in_minor_units() {
+        assert_eq!(format_amount(150_005, Some("USD")), "1500.05 USD");
+        assert_eq!(format_amount(-7, Some("EUR")), "-0.07 EUR");
+        assert_eq!(format_amount(i64::MIN, Some("USD")), "-92233720368547758.08 USD");
+        assert_eq!(format_amount(-250, None), "-250");
+    }
+
+    #[test]
+    fn spoken_lists_join_with_and() {
+        assert_eq!(spoken_list(&[]), "nobody");
+        assert_eq!(spoken_list(&["alice".to_string()]), "alice");
+        assert_eq!(spoken_list(&["alice".to_string(), "bob".to_string(), "carol".to_string()]), "alice, bob and carol");
+    }
+
+    #[test]
+    fn plain_text_lists_steps_payments_and_waits() {
+        assert_eq!(
+            PlainText.format(&brief()),
+            "Lease (1 of 2 steps complete)\n\
+             Parties: alice, bob\n\
+             [x] Sign lease\n\
+             [ ] Pay deposit by 2026-03-01\n\
+             Pays 1500.05 USD to bob for Pay deposit\n\
+             Waiting on alice for Pay deposit\n"
+        );
+    }
+
+    #[test]
+    fn spoken_reads_as_sentences() {
+        assert_eq!(
+            Spoken.format(&brief()),
+            "Lease is between alice and bob and has 1 of 2 steps complete. \
+             Completing Pay deposit pays 1500.05 USD to bob. \
+             alice still needs to sign Pay deposit by 2026-03-01."
+        );
+    }
+
+    #[test]
+    fn summaries_are_deterministic() {
+        let contract = Contract::fixture();
+        let brief = summarize(&contract);
+        assert_eq!(brief, summarize(&contract));
+        assert_eq!(brief.obligations.len(), contract.steps.len());
+        assert_eq!(brief.outstanding.len(), contract.steps.iter().filter(|step| !step.completed).count());
+    }
+}
@@ -0,0 +1,179 @@
+/*!
+ * Cross-SDK signature test vectors
+ * Fixture files shared with the JS/Swift/Kotlin clients pin the canonical
+ * signing messages and JSON serialization so drift shows up in CI.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::{canonical, scheme, CovenantError, SignatureScheme, Signer};
+
+pub const VECTOR_FILE_VERSION: u32 = 1;
+
+/// What a vector exercises
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum VectorInput {
+    /// Request auth message: timestamp + userUUID (+ contractUUID)
+    Auth {
+        timestamp: i64,
+        #[serde(rename = "userUUID")]
+        user_uuid: String,
+        #[serde(rename = "contractUuid")]
+        contract_uuid: Option<String>,
+    },
+    /// Step signature message: timestamp + userUUID + contractUUID + stepId
+    Step {
+        timestamp: i64,
+        #[serde(rename = "userUUID")]
+        user_uuid: String,
+        #[serde(rename = "contractUuid")]
+        contract_uuid: String,
+        #[serde(rename = "stepId")]
+        step_id: String,
+    },
+    /// Canonical JSON serialization of an arbitrary value
+    CanonicalJson {
+        value: serde_json::Value,
+    },
+}
+
+impl VectorInput {
+    /// Canonical message or serialization for this input
+    pub fn canonical(&self) -> String {
+        match self {
+            VectorInput::Auth { timestamp, user_uuid, contract_uuid } => {
+                canonical::auth_message(*timestamp, user_uuid, contract_uuid.as_deref())
+            }
+            VectorInput::Step { timestamp, user_uuid, contract_uuid, step_id } => {
+                canonical::step_message(*timestamp, user_uuid, contract_uuid, step_id)
+            }
+            VectorInput::CanonicalJson { value } => canonical::canonical_json(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TestVector {
+    pub name: String,
+    #[serde(flatten)]
+    pub input: VectorInput,
+    /// Expected canonical output
+    pub expected: String,
+    #[serde(rename = "pubKey")]
+    pub pub_key: Option<String>,
+    /// Signature over `expected`, checked when `pub_key` is present
+    pub signature: Option<String>,
+    /// Signing scheme; secp256k1 when not recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<SignatureScheme>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VectorFile {
+    pub version: u32,
+    pub vectors: Vec<TestVector>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VectorFailure {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VectorReport {
+    pub passed: usize,
+    pub failures: Vec<VectorFailure>,
+}
+
+impl VectorReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Build a vector from an input, signing it when a signer is given
+pub fn generate<S: Into<String>>(name: S, input: VectorInput, signer: Option<&dyn Signer>) -> Result<TestVector, CovenantError> {
+    let expected = input.canonical();
+
+    let (pub_key, signature, scheme) = match signer {
+        Some(signer) => (
+            Some(signer.public_key().to_string()),
+            Some(signer.sign(&expected)?),
+            Some(signer.scheme()).filter(|scheme| *scheme != SignatureScheme::default()),
+        ),
+        None => (None, None, None),
+    };
+
+    Ok(TestVector {
+        name: name.into(),
+        input,
+        expected,
+        pub_key,
+        signature,
+        scheme,
+    })
+}
+
+/// Check one vector's canonical output and signature
+pub fn validate(vector: &TestVector) -> Result<(), String> {
+    let actual = vector.input.canonical();
+    if actual != vector.expected {
+        return Err(format!("expected {:?}, produced {:?}", vector.expected, actual));
+    }
+
+    match (&vector.pub_key, &vector.signature) {
+        (Some(pub_key), Some(signature)) => match scheme::verify(vector.scheme.unwrap_or_default(), signature, &vector.expected, pub_key) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("signature does not verify".to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+        (None, None) => Ok(()),
+        _ => Err("pubKey and signature must be provided together".to_string()),
+    }
+}
+
+/// Validate every vector in a file's contents
+pub fn verify_vectors(file: &VectorFile) -> Result<VectorReport, CovenantError> {
+    if file.version != VECTOR_FILE_VERSION {
+        return Err(CovenantError::ValidationError(
+            format!("Unsupported test vector version {}", file.version)
+        ));
+    }
+
+    let mut report = VectorReport::default();
+    for vector in &file.vectors {
+        match validate(vector) {
+            Ok(()) => report.passed += 1,
+            Err(reason) => report.failures.push(VectorFailure {
+                name: vector.name.clone(),
+                reason,
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Load and validate a vector fixture file
+pub fn verify_vector_file<P: AsRef<Path>>(path: P) -> Result<VectorReport, CovenantError> {
+    let contents = std::fs::read_to_string(path)?;
+    let file: VectorFile = serde_json::from_str(&contents)?;
+    verify_vectors(&file)
+}
+
+/// Write vectors to a fixture file for the other SDKs to consume
+pub fn write_vector_file<P: AsRef<Path>>(path: P, vectors: Vec<TestVector>) -> Result<(), CovenantError> {
+    let file = VectorFile {
+        version: VECTOR_FILE_VERSION,
+        vectors,
+    };
+
+    std::fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(())
+}
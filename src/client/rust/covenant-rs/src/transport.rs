@@ -0,0 +1,97 @@
+/*!
+ * Tower transport (requires the `tower` feature)
+ * Every request the client makes goes through a `tower::Service`, so
+ * standard middleware (timeouts, load shedding, buffering, retries) can
+ * wrap it:
+ *
+ * ```ignore
+ * let stack = tower::ServiceBuilder::new()
+ *     .timeout(Duration::from_secs(10))
+ *     .service(client.http_service());
+ * let client = client.with_service(stack);
+ * ```
+ */
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use tower::util::BoxCloneService;
+use tower::{BoxError, Service, ServiceExt};
+
+use crate::{CovenantClient, CovenantError};
+
+/// A fully built HTTP request on its way to the service
+#[derive(Debug)]
+pub struct CovenantRequest(pub reqwest::Request);
+
+impl CovenantRequest {
+    /// Copy for retries; `None` for streaming bodies
+    pub fn try_clone(&self) -> Option<Self> {
+        self.0.try_clone().map(CovenantRequest)
+    }
+}
+
+/// The plain HTTP transport at the bottom of the stack
+#[derive(Debug, Clone)]
+pub struct HttpService {
+    client: reqwest::Client,
+}
+
+impl Service<CovenantRequest> for HttpService {
+    type Response = reqwest::Response;
+    type Error = CovenantError;
+    type Future = Pin<Box<dyn Future<Output = Result<reqwest::Response, CovenantError>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: CovenantRequest) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move { Ok(client.execute(request.0).await?) })
+    }
+}
+
+/// Installed service stack, shared by client clones
+#[derive(Clone)]
+pub(crate) struct SharedService(Arc<Mutex<BoxCloneService<CovenantRequest, reqwest::Response, BoxError>>>);
+
+impl SharedService {
+    pub(crate) async fn call(&self, request: reqwest::Request) -> Result<reqwest::Response, CovenantError> {
+        let service = self.0.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        service.oneshot(CovenantRequest(request)).await.map_err(into_covenant_error)
+    }
+}
+
+fn into_covenant_error(error: BoxError) -> CovenantError {
+    let error = match error.downcast::<CovenantError>() {
+        Ok(error) => return *error,
+        Err(error) => error,
+    };
+
+    match error.downcast::<reqwest::Error>() {
+        Ok(error) => CovenantError::RequestError(*error),
+        Err(error) => CovenantError::TransportError(error.to_string()),
+    }
+}
+
+impl<A> CovenantClient<A> {
+    /// The client's HTTP transport, for wrapping in middleware
+    pub fn http_service(&self) -> HttpService {
+        HttpService { client: self.client.clone() }
+    }
+
+    /// Send every request through `service` instead of calling HTTP directly
+    pub fn with_service<S>(mut self, service: S) -> Self
+    where
+        S: Service<CovenantRequest, Response = reqwest::Response> + Clone + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+    {
+        let service = service.map_err(|error: S::Error| error.into());
+        self.service = Some(SharedService(Arc::new(Mutex::new(BoxCloneService::new(service)))));
+        self
+    }
+}
@@ -0,0 +1,187 @@
+/*!
+ * Participant key pinning
+ * Protects against a compromised service substituting keys. The first key
+ * seen for a participant is pinned (trust on first use) unless the store is
+ * strict, in which case only keys pinned by the application are trusted.
+ * A pinned key is never replaced automatically: a different key raises
+ * `KeyChanged` until the application accepts it.
+ *
+ * A client with a trust store checks the signature keys on every contract
+ * it fetches or joins; the warnings collect in the store.
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Contract, CovenantError};
+#[cfg(feature = "client")]
+use crate::CovenantClient;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TrustWarningKind {
+    /// The participant presented a different key from the pinned one
+    KeyChanged {
+        pinned: String,
+        presented: String,
+    },
+    /// No key is pinned for the participant and the store is strict
+    UntrustedKey { presented: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TrustWarning {
+    pub participant: String,
+    #[serde(rename = "contractUuid")]
+    pub contract_uuid: Option<String>,
+    #[serde(flatten)]
+    pub kind: TrustWarningKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PinSource {
+    /// Pinned explicitly by the application
+    Manual,
+    /// Pinned the first time the key was seen
+    FirstUse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedKey {
+    #[serde(rename = "pubKey")]
+    pub pub_key: String,
+    pub source: PinSource,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Pins {
+    #[serde(default)]
+    keys: HashMap<String, PinnedKey>,
+    #[serde(skip)]
+    warnings: Vec<TrustWarning>,
+}
+
+/// Pinned participant keys; clones share the same pins
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    pins: Arc<Mutex<Pins>>,
+    strict: bool,
+}
+
+impl TrustStore {
+    /// Trust-on-first-use store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only trust keys pinned with `pin`
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Load pins saved with `save`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CovenantError> {
+        let pins: Pins = serde_json::from_slice(&std::fs::read(path)?)?;
+        Ok(Self { pins: Arc::new(Mutex::new(pins)), strict: false })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CovenantError> {
+        let bytes = serde_json::to_vec_pretty(&*self.lock())?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Pins> {
+        self.pins.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Trust `pub_key` for `participant`, replacing any earlier pin
+    pub fn pin<P: Into<String>, K: Into<String>>(&self, participant: P, pub_key: K) {
+        self.lock().keys.insert(participant.into(), PinnedKey { pub_key: pub_key.into(), source: PinSource::Manual });
+    }
+
+    /// Accept a changed key after checking it out of band
+    pub fn accept_change<P: Into<String>, K: Into<String>>(&self, participant: P, pub_key: K) {
+        self.pin(participant, pub_key)
+    }
+
+    pub fn unpin(&self, participant: &str) {
+        self.lock().keys.remove(participant);
+    }
+
+    pub fn pinned(&self, participant: &str) -> Option<PinnedKey> {
+        self.lock().keys.get(participant).cloned()
+    }
+
+    /// Check a presented key, pinning it on first use. Returns (and records)
+    /// a warning when it can't be trusted.
+    pub fn observe(&self, participant: &str, pub_key: &str, contract_uuid: Option<&str>) -> Option<TrustWarning> {
+        let mut pins = self.lock();
+
+        let kind = match pins.keys.get(participant) {
+            Some(pinned) if pinned.pub_key == pub_key => return None,
+            Some(pinned) => TrustWarningKind::KeyChanged {
+                pinned: pinned.pub_key.clone(),
+                presented: pub_key.to_string(),
+            },
+            None if self.strict => TrustWarningKind::UntrustedKey { presented: pub_key.to_string() },
+            None => {
+                pins.keys.insert(participant.to_string(), PinnedKey {
+                    pub_key: pub_key.to_string(),
+                    source: PinSource::FirstUse,
+                });
+                return None;
+            }
+        };
+
+        let warning = TrustWarning {
+            participant: participant.to_string(),
+            contract_uuid: contract_uuid.map(|uuid| uuid.to_string()),
+            kind,
+        };
+        pins.warnings.push(warning.clone());
+        Some(warning)
+    }
+
+    /// Check every signature key recorded on a contract
+    pub fn check_contract(&self, contract: &Contract) -> Vec<TrustWarning> {
+        contract.steps.iter()
+            .flat_map(|step| step.signatures.iter())
+            .filter_map(|(participant, signature)| {
+                let pub_key = signature.as_ref()?.pub_key.as_deref()?;
+                self.observe(participant, pub_key, Some(&contract.uuid))
+            })
+            .collect()
+    }
+
+    /// Warnings recorded since the last call
+    pub fn take_warnings(&self) -> Vec<TrustWarning> {
+        std::mem::take(&mut self.lock().warnings)
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Check signature keys on fetched and joined contracts against `store`
+    pub fn with_trust_store(mut self, store: TrustStore) -> Self {
+        self.trust = Some(store);
+        self
+    }
+
+    pub fn trust_store(&self) -> Option<&TrustStore> {
+        self.trust.as_ref()
+    }
+
+    pub(crate) fn check_trust(&self, contract: &Contract) {
+        if let Some(store) = &self.trust {
+            store.check_contract(contract);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+/*!
+ * Step description variables
+ * Step descriptions may contain `{{name}}` placeholders filled from the
+ * contract's `variables` map, e.g. "Pay {{amount}} by {{delivery_date}}".
+ * Every placeholder must be bound when the contract is built.
+ */
+
+use std::collections::HashMap;
+
+use crate::{Contract, ContractStep};
+
+/// Placeholder names in a template, in order of first use
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = match after.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+
+        let name = after[..end].trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after[end + 2..];
+    }
+
+    names
+}
+
+/// Fill placeholders from `variables`. Unbound placeholders are left as-is;
+/// strings are inserted without quotes, other values as JSON.
+pub fn render(template: &str, variables: &HashMap<String, serde_json::Value>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = match after.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+
+        out.push_str(&rest[..start]);
+        match variables.get(after[..end].trim()) {
+            Some(serde_json::Value::String(text)) => out.push_str(text),
+            Some(value) => out.push_str(&value.to_string()),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Placeholders in `descriptions` that `variables` doesn't bind
+pub(crate) fn unbound<'a, I>(descriptions: I, variables: &HashMap<String, serde_json::Value>) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut missing: Vec<String> = Vec::new();
+    for name in descriptions.into_iter().flat_map(template_variables) {
+        if !variables.contains_key(&name) && !missing.contains(&name) {
+            missing.push(name);
+        }
+    }
+    missing
+}
+
+impl Contract {
+    /// Steps with their descriptions filled in from the contract's variables
+    pub fn rendered_steps(&self) -> Vec<ContractStep> {
+        self.steps.iter().map(|step| ContractStep {
+            description: render(&step.description, &self.variables),
+            ..step.clone()
+        }).collect()
+    }
+
+    /// Placeholders used by step descriptions but missing from `variables`
+    pub fn unbound_variables(&self) -> Vec<String> {
+        unbound(self.steps.iter().map(|step| step.description.as_str()), &self.variables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> HashMap<String, serde_json::Value> {
+        HashMap::from([
+            ("amount".to_string(), serde_json::json!(500)),
+            ("delivery_date".to_string(), serde_json::json!("2025-03-01")),
+        ])
+    }
+
+    #[test]
+    fn lists_placeholders_once_in_order() {
+        let names = template_variables("Pay {{ amount }} by {{delivery_date}}, then {{amount}} again {{}}");
+        assert_eq!(names, ["amount", "delivery_date"]);
+    }
+
+    #[test]
+    fn stops_at_an_unterminated_placeholder() {
+        assert_eq!(template_variables("{{a}} and {{b"), ["a"]);
+        assert_eq!(render("{{amount}} and {{b", &variables()), "500 and {{b");
+    }
+
+    #[test]
+    fn renders_strings_bare_and_other_values_as_json() {
+        let rendered = render("Pay {{amount}} by {{ delivery_date }}", &variables());
+        assert_eq!(rendered, "Pay 500 by 2025-03-01");
+    }
+
+    #[test]
+    fn leaves_unbound_placeholders() {
+        let rendered = render("Pay {{amount}} to {{ payee }}.", &variables());
+        assert_eq!(rendered, "Pay 500 to {{ payee }}.");
+    }
+
+    #[test]
+    fn contract_reports_and_renders_its_variables() {
+        let mut contract = Contract::fixture();
+        contract.variables = variables();
+        contract.steps[0].description = "Pay {{amount}} to {{payee}}".to_string();
+        contract.steps[1].description = "Deliver by {{delivery_date}}, tell {{payee}} and {{witness}}".to_string();
+
+        assert_eq!(contract.unbound_variables(), ["payee", "witness"]);
+
+        let steps = contract.rendered_steps();
+        assert_eq!(steps[0].description, "Pay 500 to {{payee}}");
+        assert_eq!(steps[1].description, "Deliver by 2025-03-01, tell {{payee}} and {{witness}}");
+        assert_eq!(steps[2].description, contract.steps[2].description);
+    }
+}
@@ -0,0 +1,359 @@
+/*!
+ * Encrypted on-disk contract vault (requires the `vault` feature)
+ * Keeps fetched contracts and the caller's signature bundles on disk,
+ * encrypted at rest, so contract history survives offline and a lost laptop
+ * doesn't leak it.
+ *
+ * Layout: `vault.json` (format version, passphrase salt, key check) plus one
+ * `<uuid>.cv` file per contract holding a nonce and XChaCha20-Poly1305
+ * ciphertext of the entry JSON.
+ */
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sessionless::Sessionless;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::encoding::{from_hex, to_hex};
+use crate::{Clock, Contract, CovenantError, SignStepRequest, SystemClock};
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient};
+
+pub const VAULT_VERSION: u32 = 1;
+
+const HEADER_FILE: &str = "vault.json";
+const ENTRY_EXTENSION: &str = "cv";
+const NONCE_LEN: usize = 24;
+const KEY_CHECK: &[u8] = b"covenant-vault";
+/// Message signed to derive a key from a sessionless identity
+const SESSIONLESS_KEY_MESSAGE: &str = "covenant-vault-key-v1";
+
+/// 256-bit vault encryption key
+#[derive(Clone)]
+pub struct VaultKey([u8; 32]);
+
+impl VaultKey {
+    /// Derive a key from a passphrase (Argon2id)
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self, CovenantError> {
+        let mut key = [0u8; 32];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| CovenantError::EncodingError(format!("Key derivation failed: {}", e)))?;
+        Ok(Self(key))
+    }
+
+    /// Derive a key from a sessionless identity. Relies on sessionless
+    /// signatures being deterministic, so the same identity always yields
+    /// the same key.
+    pub fn from_sessionless(sessionless: &Sessionless) -> Result<Self, CovenantError> {
+        let signature = sessionless.sign(SESSIONLESS_KEY_MESSAGE)
+            .map_err(|e| CovenantError::SessionlessError(e.to_string()))?;
+        Ok(Self(Sha256::digest(signature.as_bytes()).into()))
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new((&self.0).into())
+    }
+
+    pub(crate) fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, CovenantError> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher().encrypt(&nonce, plaintext)
+            .map_err(|_| CovenantError::EncodingError("Vault encryption failed".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    pub(crate) fn open(&self, sealed: &[u8]) -> Result<Vec<u8>, CovenantError> {
+        if sealed.len() < NONCE_LEN {
+            return Err(CovenantError::EncodingError("Vault entry is truncated".to_string()));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher().decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CovenantError::EncodingError("Vault entry could not be decrypted (wrong key?)".to_string()))
+    }
+}
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("VaultKey(..)")
+    }
+}
+
+/// A stored contract and the signatures this device submitted for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VaultEntry {
+    pub contract: Contract,
+    #[serde(default)]
+    pub signatures: Vec<SignStepRequest>,
+    #[serde(rename = "storedAt")]
+    pub stored_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct VaultHeader {
+    version: u32,
+    /// Hex salt, for passphrase-derived keys only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    /// `KEY_CHECK` sealed with the vault key, to reject wrong keys early
+    check: String,
+}
+
+/// Portable export of a whole vault
+#[derive(Serialize, Deserialize)]
+struct VaultExport {
+    version: u32,
+    /// Set when the vault is keyed by a passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    /// Hex of the sealed entry list
+    entries: String,
+}
+
+pub struct Vault {
+    root: PathBuf,
+    key: VaultKey,
+    salt: Option<Vec<u8>>,
+    /// Stamps `stored_at`
+    clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for Vault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vault").field("root", &self.root).finish_non_exhaustive()
+    }
+}
+
+impl Vault {
+    /// Open (or create) a vault with an explicit key. New vaults get no
+    /// salt, so their exports can't be imported with a passphrase.
+    pub fn open<P: AsRef<Path>>(root: P, key: VaultKey) -> Result<Self, CovenantError> {
+        let root = root.as_ref().to_path_buf();
+        let salt = match read_header(&root)? {
+            Some(header) => {
+                key.open(&from_hex(&header.check)?)?;
+                header.salt.as_deref().map(from_hex).transpose()?
+            }
+            None => None,
+        };
+
+        let vault = Self { root, key, salt, clock: Arc::new(SystemClock) };
+        vault.write_header()?;
+        Ok(vault)
+    }
+
+    /// Open (or create) a vault keyed by a passphrase
+    pub fn open_with_passphrase<P: AsRef<Path>>(root: P, passphrase: &str) -> Result<Self, CovenantError> {
+        let root = root.as_ref().to_path_buf();
+        let header = read_header(&root)?;
+        let salt = match header.as_ref().map(|header| header.salt.as_deref()) {
+            Some(Some(salt)) => from_hex(salt)?,
+            Some(None) => return Err(CovenantError::ValidationError("Vault is keyed by an explicit key, not a passphrase".to_string())),
+            None => random_salt(),
+        };
+
+        let key = VaultKey::from_passphrase(passphrase, &salt)?;
+        if let Some(header) = header {
+            key.open(&from_hex(&header.check)?)?;
+        }
+
+        let vault = Self { root, key, salt: Some(salt), clock: Arc::new(SystemClock) };
+        vault.write_header()?;
+        Ok(vault)
+    }
+
+    /// Open (or create) a vault keyed by a sessionless identity
+    pub fn open_with_sessionless<P: AsRef<Path>>(root: P, sessionless: &Sessionless) -> Result<Self, CovenantError> {
+        Self::open(root, VaultKey::from_sessionless(sessionless)?)
+    }
+
+    /// Source for `stored_at` timestamps; pass the client's clock to keep them in step
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Store (or replace) a contract, keeping any signatures already stored for it
+    pub fn store(&self, contract: &Contract) -> Result<(), CovenantError> {
+        let signatures = self.get(&contract.uuid)?
+            .map(|entry| entry.signatures)
+            .unwrap_or_default();
+
+        self.write_entry(&VaultEntry {
+            contract: contract.clone(),
+            signatures,
+            stored_at: self.clock.now().timestamp_millis(),
+        })
+    }
+
+    /// Record a signature bundle against an already stored contract
+    pub fn store_signature(&self, contract_uuid: &str, signature: &SignStepRequest) -> Result<(), CovenantError> {
+        let mut entry = self.get(contract_uuid)?
+            .ok_or_else(|| CovenantError::ValidationError(format!("Contract {} is not in the vault", contract_uuid)))?;
+
+        entry.signatures.push(signature.clone());
+        entry.stored_at = self.clock.now().timestamp_millis();
+        self.write_entry(&entry)
+    }
+
+    pub fn get(&self, uuid: &str) -> Result<Option<VaultEntry>, CovenantError> {
+        let path = self.entry_path(uuid);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let plaintext = self.key.open(&std::fs::read(path)?)?;
+        Ok(Some(serde_json::from_slice(&plaintext)?))
+    }
+
+    pub fn remove(&self, uuid: &str) -> Result<bool, CovenantError> {
+        let path = self.entry_path(uuid);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        std::fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    /// Every stored entry, oldest contract first
+    pub fn list(&self) -> Result<Vec<VaultEntry>, CovenantError> {
+        let mut entries = Vec::new();
+        for dir_entry in std::fs::read_dir(&self.root)? {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+
+            let plaintext = self.key.open(&std::fs::read(path)?)?;
+            entries.push(serde_json::from_slice::<VaultEntry>(&plaintext)?);
+        }
+
+        entries.sort_by(|a, b| a.contract.created_at.cmp(&b.contract.created_at));
+        Ok(entries)
+    }
+
+    /// Case-insensitive search over title, description, participants and tags
+    pub fn search(&self, text: &str) -> Result<Vec<VaultEntry>, CovenantError> {
+        let needle = text.to_lowercase();
+        Ok(self.list()?.into_iter().filter(|entry| {
+            let contract = &entry.contract;
+            std::iter::once(&contract.title)
+                .chain(std::iter::once(&contract.description))
+                .chain(contract.participants.iter())
+                .chain(contract.tags.iter())
+                .any(|field| field.to_lowercase().contains(&needle))
+        }).collect())
+    }
+
+    /// Write every entry into one encrypted file, readable with the same key or passphrase
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<usize, CovenantError> {
+        let entries = self.list()?;
+        let export = VaultExport {
+            version: VAULT_VERSION,
+            salt: self.salt.as_deref().map(to_hex),
+            entries: to_hex(&self.key.seal(&serde_json::to_vec(&entries)?)?),
+        };
+
+        std::fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        Ok(entries.len())
+    }
+
+    /// Merge an export into this vault, keeping the newer copy of each contract
+    pub fn import<P: AsRef<Path>>(&self, path: P, key: &VaultKey) -> Result<usize, CovenantError> {
+        let export: VaultExport = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if export.version != VAULT_VERSION {
+            return Err(CovenantError::ValidationError(format!("Unsupported vault export version {}", export.version)));
+        }
+
+        let entries: Vec<VaultEntry> = serde_json::from_slice(&key.open(&from_hex(&export.entries)?)?)?;
+        let mut imported = 0;
+        for entry in entries {
+            let newer = self.get(&entry.contract.uuid)?
+                .map(|existing| existing.stored_at < entry.stored_at)
+                .unwrap_or(true);
+            if newer {
+                self.write_entry(&entry)?;
+                imported += 1;
+            }
+        }
+
+        Ok(imported)
+    }
+
+    /// Import an export protected by a passphrase
+    pub fn import_with_passphrase<P: AsRef<Path>>(&self, path: P, passphrase: &str) -> Result<usize, CovenantError> {
+        let export: VaultExport = serde_json::from_str(&std::fs::read_to_string(path.as_ref())?)?;
+        let salt = export.salt.as_deref()
+            .ok_or_else(|| CovenantError::ValidationError("Vault export wasn't made with a passphrase".to_string()))?;
+        let key = VaultKey::from_passphrase(passphrase, &from_hex(salt)?)?;
+        self.import(path, &key)
+    }
+
+    /// Fetch the caller's contracts and store them all
+    #[cfg(feature = "client")]
+    pub async fn refresh(&self, client: &CovenantClient<Authenticated>) -> Result<usize, CovenantError> {
+        let summaries = client.get_my_contracts().await?;
+        let uuids: Vec<&str> = summaries.iter().map(|summary| summary.uuid.as_str()).collect();
+
+        let mut stored = 0;
+        for contract in client.get_contracts(&uuids).await {
+            self.store(&contract?)?;
+            stored += 1;
+        }
+
+        Ok(stored)
+    }
+
+    fn entry_path(&self, uuid: &str) -> PathBuf {
+        let file_name: String = uuid.chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        self.root.join(format!("{}.{}", file_name, ENTRY_EXTENSION))
+    }
+
+    fn write_entry(&self, entry: &VaultEntry) -> Result<(), CovenantError> {
+        let sealed = self.key.seal(&serde_json::to_vec(entry)?)?;
+        std::fs::write(self.entry_path(&entry.contract.uuid), sealed)?;
+        Ok(())
+    }
+
+    fn write_header(&self) -> Result<(), CovenantError> {
+        std::fs::create_dir_all(&self.root)?;
+        let header = VaultHeader {
+            version: VAULT_VERSION,
+            salt: self.salt.as_deref().map(to_hex),
+            check: to_hex(&self.key.seal(KEY_CHECK)?),
+        };
+        std::fs::write(self.root.join(HEADER_FILE), serde_json::to_string_pretty(&header)?)?;
+        Ok(())
+    }
+}
+
+fn read_header(root: &Path) -> Result<Option<VaultHeader>, CovenantError> {
+    let path = root.join(HEADER_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let header: VaultHeader = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if header.version != VAULT_VERSION {
+        return Err(CovenantError::ValidationError(format!("Unsupported vault version {}", header.version)));
+    }
+
+    Ok(Some(header))
+}
+
+fn random_salt() -> Vec<u8> {
+    let mut salt = vec![0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
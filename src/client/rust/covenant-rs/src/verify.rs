@@ -0,0 +1,65 @@
+/*!
+ * Local signature verification
+ */
+
+use crate::{canonical, scheme, CovenantError, RotationChain, StepSignature, TrustStore, TrustWarning};
+
+/// Verify a sessionless signature over `message`
+pub fn verify_signature(signature: &str, message: &str, pub_key: &str) -> Result<bool, CovenantError> {
+    sessionless::Sessionless::verify_signature(signature, message, pub_key)
+        .map_err(|e| CovenantError::SessionlessError(e.to_string()))
+}
+
+/// Whether the signed message is the canonical one for this participant and step
+fn signs_step(signature: &StepSignature, participant: &str, contract_uuid: &str, step_id: &str) -> bool {
+    signature.message == canonical::step_message(signature.timestamp, participant, contract_uuid, step_id)
+}
+
+/// Verify `participant`'s signature on a step was made by `expected_pub_key`
+pub fn verify_step_signature(signature: &StepSignature, participant: &str, contract_uuid: &str, step_id: &str, expected_pub_key: &str) -> Result<bool, CovenantError> {
+    if let Some(pub_key) = &signature.pub_key {
+        if pub_key != expected_pub_key {
+            return Ok(false);
+        }
+    }
+    if !signs_step(signature, participant, contract_uuid, step_id) {
+        return Ok(false);
+    }
+
+    scheme::verify(signature.scheme(), &signature.signature, &signature.message, expected_pub_key)
+}
+
+/// Verify a step signature and check its key against the trust store. A key
+/// that can't be trusted fails verification and comes back as a warning.
+pub fn verify_step_signature_trusted(signature: &StepSignature, participant: &str, contract_uuid: &str, step_id: &str, store: &TrustStore) -> Result<(bool, Option<TrustWarning>), CovenantError> {
+    let pub_key = signature.pub_key.clone()
+        .or_else(|| store.pinned(participant).map(|pinned| pinned.pub_key))
+        .ok_or_else(|| CovenantError::ValidationError(format!("No key known for {}", participant)))?;
+
+    if let Some(warning) = store.observe(participant, &pub_key, None) {
+        return Ok((false, Some(warning)));
+    }
+
+    Ok((verify_step_signature(signature, participant, contract_uuid, step_id, &pub_key)?, None))
+}
+
+/// Verify a step signature against a participant's current key, accepting
+/// signatures made by earlier keys before they were rotated away
+pub fn verify_step_signature_with_rotation(signature: &StepSignature, participant: &str, contract_uuid: &str, step_id: &str, current_pub_key: &str, chain: &RotationChain) -> Result<bool, CovenantError> {
+    if !signs_step(signature, participant, contract_uuid, step_id) {
+        return Ok(false);
+    }
+
+    let signing_key = signature.pub_key.as_deref().unwrap_or(current_pub_key);
+
+    if signing_key == current_pub_key {
+        return scheme::verify(signature.scheme(), &signature.signature, &signature.message, current_pub_key);
+    }
+
+    match chain.rotated_at(signing_key, current_pub_key) {
+        Some(rotated_at) if signature.timestamp <= rotated_at => {
+            scheme::verify(signature.scheme(), &signature.signature, &signature.message, signing_key)
+        }
+        _ => Ok(false),
+    }
+}
@@ -0,0 +1,104 @@
+/*!
+ * Contract visibility and read grants
+ * Contracts are readable by participants only, by their organization, or by
+ * anyone with the UUID, plus any keys explicitly granted read access. Reads
+ * from a client with an identity are signed so the service can enforce this.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::Contract;
+#[cfg(feature = "client")]
+use crate::{Authenticated, CovenantClient, CovenantError, ServiceResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum Visibility {
+    /// Participants and read grants only
+    ParticipantsOnly,
+    /// Members of the owning organization as well
+    Org,
+    /// Anyone with the UUID (the historical behaviour)
+    #[default]
+    PublicRead,
+}
+
+impl Contract {
+    /// Whether a key may read this contract, not counting organization membership
+    pub fn can_read(&self, pub_key: &str) -> bool {
+        self.visibility == Visibility::PublicRead
+            || self.participants.iter().any(|p| p == pub_key)
+            || self.read_grants.iter().any(|g| g == pub_key)
+    }
+}
+
+#[cfg(feature = "client")]
+impl<A> CovenantClient<A> {
+    /// Add auth fields as query parameters when the client has an identity,
+    /// and any delegation token as a header
+    pub(crate) fn signed_read(&self, request: reqwest::RequestBuilder, contract_uuid: Option<&str>) -> Result<reqwest::RequestBuilder, CovenantError> {
+        let auth = self.auth_fields(serde_json::json!({}), contract_uuid)?;
+        let query: Vec<(String, String)> = auth.as_object()
+            .map(|fields| fields.iter().map(|(key, value)| {
+                let value = value.as_str().map(|v| v.to_string()).unwrap_or_else(|| value.to_string());
+                (key.clone(), value)
+            }).collect())
+            .unwrap_or_default();
+
+        let request = if query.is_empty() { request } else { request.query(&query) };
+        Ok(match &self.delegation {
+            Some(token) => request.header(crate::delegation::DELEGATION_HEADER, serde_json::to_string(token.as_ref())?),
+            None => request,
+        })
+    }
+}
+
+#[cfg(feature = "client")]
+impl CovenantClient<Authenticated> {
+    /// Change who can read a contract
+    pub async fn set_visibility(&self, uuid: &str, visibility: Visibility) -> Result<Contract, CovenantError> {
+        let (uuid, url) = self.contract_url(uuid, "visibility").await?;
+        let payload = self.signed_payload(serde_json::json!({ "visibility": visibility }), Some(&uuid))?;
+        self.put_access(&url, &payload, "Set visibility failed").await
+    }
+
+    /// Let a key that isn't a participant read the contract
+    pub async fn grant_read(&self, uuid: &str, pub_key: &str) -> Result<Contract, CovenantError> {
+        let (uuid, url) = self.contract_url(uuid, "grants").await?;
+        let payload = self.signed_payload(serde_json::json!({ "pubKey": pub_key }), Some(&uuid))?;
+        self.put_access(&url, &payload, "Grant read failed").await
+    }
+
+    /// Withdraw a read grant
+    pub async fn revoke_read(&self, uuid: &str, pub_key: &str) -> Result<Contract, CovenantError> {
+        let (uuid, url) = self.contract_url(uuid, &format!("grants/{}", pub_key)).await?;
+        let payload = self.signed_payload(serde_json::json!({}), Some(&uuid))?;
+
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.delete(&url).json(&payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| "Revoke read failed".to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+
+    async fn put_access(&self, url: &str, payload: &serde_json::Value, failure: &str) -> Result<Contract, CovenantError> {
+        let service_response: ServiceResponse<Contract> = self.send_json(self.client.put(url).json(payload)).await?;
+
+        if !service_response.success {
+            return Err(CovenantError::ServiceError(
+                service_response.error.unwrap_or_else(|| failure.to_string())
+            ));
+        }
+
+        service_response.data.ok_or_else(||
+            CovenantError::ServiceError("No contract data returned".to_string())
+        )
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+ * Shared contract watchers
+ * One poller per contract uuid, fanned out to any number of subscribers
+ * over a broadcast channel and torn down when the last subscriber drops.
+ * Subscribers joining a running poller get its latest `Updated` first.
+ * Events are also handed to the client's `on_event` handlers.
+ */
+
+use std::sync::Arc;
+#[cfg(feature = "client")]
+use std::collections::HashMap;
+#[cfg(feature = "client")]
+use std::sync::Mutex;
+#[cfg(feature = "client")]
+use std::time::Duration;
+#[cfg(feature = "client")]
+use tokio::sync::broadcast;
+#[cfg(feature = "client")]
+use tokio::task::JoinHandle;
+
+use crate::Contract;
+#[cfg(feature = "client")]
+use crate::{Anonymous, CovenantClient};
+
+/// Change observed on a watched contract
+#[derive(Debug, Clone)]
+pub enum ContractEvent {
+    /// Latest contract state, sent on the first poll and after every change
+    Updated(Arc<Contract>),
+    StepSigned {
+        contract_uuid: String,
+        step_id: String,
+        signer: String,
+        timestamp: i64,
+    },
+    StepCompleted {
+        contract_uuid: String,
+        step_id: String,
+    },
+    ContractCompleted {
+        contract_uuid: String,
+    },
+    /// Polling failed; the watcher keeps retrying on its interval
+    PollFailed {
+        contract_uuid: String,
+        error: String,
+    },
+}
+
+impl ContractEvent {
+    pub fn contract_uuid(&self) -> &str {
+        match self {
+            ContractEvent::Updated(contract) => &contract.uuid,
+            ContractEvent::StepSigned { contract_uuid, .. }
+            | ContractEvent::StepCompleted { contract_uuid, .. }
+            | ContractEvent::ContractCompleted { contract_uuid }
+            | ContractEvent::PollFailed { contract_uuid, .. } => contract_uuid,
+        }
+    }
+}
+
+/// Events describing how `new` differs from `old` (excluding `Updated`)
+pub fn diff_events(old: &Contract, new: &Contract) -> Vec<ContractEvent> {
+    let mut events = Vec::new();
+
+    for step in &new.steps {
+        let previous = old.steps.iter().find(|s| s.id == step.id);
+
+        for (signer, signature) in &step.signatures {
+            let Some(signature) = signature else { continue };
+            let was_signed = previous
+                .and_then(|p| p.signatures.get(signer))
+                .is_some_and(|s| s.is_some());
+
+            if !was_signed {
+                events.push(ContractEvent::StepSigned {
+                    contract_uuid: new.uuid.clone(),
+                    step_id: step.id.clone(),
+                    signer: signer.clone(),
+                    timestamp: signature.timestamp,
+                });
+            }
+        }
+
+        if step.completed && !previous.is_some_and(|p| p.completed) {
+            events.push(ContractEvent::StepCompleted {
+                contract_uuid: new.uuid.clone(),
+                step_id: step.id.clone(),
+            });
+        }
+    }
+
+    let is_complete = |c: &Contract| !c.steps.is_empty() && c.steps.iter().all(|s| s.completed);
+    if is_complete(new) && !is_complete(old) {
+        events.push(ContractEvent::ContractCompleted { contract_uuid: new.uuid.clone() });
+    }
+
+    events
+}
+
+#[cfg(feature = "client")]
+type Latest = Arc<Mutex<Option<Arc<Contract>>>>;
+
+#[cfg(feature = "client")]
+struct WatcherEntry {
+    sender: broadcast::Sender<ContractEvent>,
+    /// Last contract the poller saw, for subscribers joining later
+    latest: Latest,
+    subscribers: usize,
+    task: JoinHandle<()>,
+}
+
+#[cfg(feature = "client")]
+type Watchers = Arc<Mutex<HashMap<String, WatcherEntry>>>;
+
+#[cfg(feature = "client")]
+/// Process-wide registry multiplexing one poller per contract to many receivers
+pub struct WatcherRegistry<A = Anonymous> {
+    client: Arc<CovenantClient<A>>,
+    interval: Duration,
+    capacity: usize,
+    watchers: Watchers,
+}
+
+#[cfg(feature = "client")]
+impl<A: Send + Sync + 'static> WatcherRegistry<A> {
+    pub fn new(client: Arc<CovenantClient<A>>, interval: Duration) -> Self {
+        Self {
+            client,
+            interval,
+            capacity: 64,
+            watchers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Broadcast buffer size per contract, at least 1; slow receivers past this lag
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Subscribe to a contract, starting its poller if this is the first subscriber.
+    /// Must be called from within a Tokio runtime.
+    pub fn subscribe(&self, contract_uuid: &str) -> ContractSubscription {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|e| e.into_inner());
+
+        let entry = watchers.entry(contract_uuid.to_string()).or_insert_with(|| {
+            let (sender, _) = broadcast::channel(self.capacity);
+            let latest = Latest::default();
+            let task = tokio::spawn(poll_contract(
+                self.client.clone(),
+                contract_uuid.to_string(),
+                self.interval,
+                sender.clone(),
+                latest.clone(),
+            ));
+
+            WatcherEntry { sender, latest, subscribers: 0, task }
+        });
+
+        entry.subscribers += 1;
+
+        // Subscribe before reading the cache so an update in between is
+        // delivered twice rather than missed
+        let receiver = entry.sender.subscribe();
+        let pending = entry.latest.lock().unwrap_or_else(|e| e.into_inner()).clone().map(ContractEvent::Updated);
+
+        ContractSubscription {
+            contract_uuid: contract_uuid.to_string(),
+            pending,
+            receiver,
+            watchers: self.watchers.clone(),
+        }
+    }
+
+    /// Number of contracts currently being polled
+    pub fn active_watchers(&self) -> usize {
+        self.watchers.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+#[cfg(feature = "client")]
+/// Receiver for one contract's events; dropping it unsubscribes
+pub struct ContractSubscription {
+    contract_uuid: String,
+    /// Cached state to hand out before anything from the channel
+    pending: Option<ContractEvent>,
+    receiver: broadcast::Receiver<ContractEvent>,
+    watchers: Watchers,
+}
+
+#[cfg(feature = "client")]
+impl ContractSubscription {
+    pub fn contract_uuid(&self) -> &str {
+        &self.contract_uuid
+    }
+
+    pub async fn recv(&mut self) -> Result<ContractEvent, broadcast::error::RecvError> {
+        if let Some(event) = self.pending.take() {
+            return Ok(event);
+        }
+        self.receiver.recv().await
+    }
+}
+
+#[cfg(feature = "client")]
+impl Drop for ContractSubscription {
+    fn drop(&mut self) {
+        let mut watchers = self.watchers.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(entry) = watchers.get_mut(&self.contract_uuid) {
+            entry.subscribers -= 1;
+            if entry.subscribers == 0 {
+                entry.task.abort();
+                watchers.remove(&self.contract_uuid);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+async fn poll_contract<A>(client: Arc<CovenantClient<A>>, contract_uuid: String, interval: Duration, sender: broadcast::Sender<ContractEvent>, latest: Latest) {
+    let mut last: Option<Contract> = None;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        match client.get_contract(&contract_uuid).await {
+            Ok(contract) => {
+                let changed = match &last {
+                    Some(previous) => {
+                        let events = diff_events(previous, &contract);
+                        let changed = !events.is_empty() || previous.updated_at != contract.updated_at;
+                        for event in events {
+                            client.dispatch_event(&event, Some(&contract)).await;
+                            let _ = sender.send(event);
+                        }
+                        changed
+                    }
+                    None => true,
+                };
+
+                if changed {
+                    let updated = Arc::new(contract.clone());
+                    *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(updated.clone());
+                    let event = ContractEvent::Updated(updated);
+                    client.dispatch_event(&event, None).await;
+                    let _ = sender.send(event);
+                    last = Some(contract);
+                }
+            }
+            Err(error) => {
+                let event = ContractEvent::PollFailed {
+                    contract_uuid: contract_uuid.clone(),
+                    error: error.to_string(),
+                };
+                client.dispatch_event(&event, last.as_ref()).await;
+                let _ = sender.send(event);
+            }
+        }
+    }
+}